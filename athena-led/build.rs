@@ -0,0 +1,27 @@
+// ==========================================
+// 🛠️ build.rs — 编译期常量
+// ATHENA_BUILD_YEAR: 编译年份 (timesync.rs 用来判断系统时间是否明显不对)。
+// 设置了 SOURCE_DATE_EPOCH (可复现构建 / OpenWrt SDK) 时以它为准。
+// ==========================================
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// 1970-01-01 起的天数 -> 公历年份 (Howard Hinnant 的 civil_from_days)
+fn year_from_days(days: i64) -> i64 {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    yoe + era * 400 + i64::from(month <= 2)
+}
+
+fn main() {
+    println!("cargo:rerun-if-env-changed=SOURCE_DATE_EPOCH");
+    let secs = std::env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|s| s.trim().parse::<i64>().ok())
+        .unwrap_or_else(|| SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs() as i64));
+    println!("cargo:rustc-env=ATHENA_BUILD_YEAR={}", year_from_days(secs.div_euclid(86_400)));
+}
@@ -0,0 +1,802 @@
+// ==========================================
+// 📅 agenda.rs — iCalendar (ICS) 日程: 下一个事件 (agenda 模块) 与会前提醒 (v2.6.0)
+//
+// 数据源: --ics <地址> (可重复，http(s) 或本地文件)，后台按 --ics-interval 拉取，多个日历合并。
+// 支持的内容 (足够覆盖 Google / Outlook / iCloud / Nextcloud 导出的日历):
+//   - VEVENT: SUMMARY / DTSTART / DTEND / DURATION / EXDATE / RECURRENCE-ID (单次改期) / STATUS:CANCELLED
+//   - RRULE: FREQ=DAILY|WEEKLY|MONTHLY|YEARLY + INTERVAL / COUNT / UNTIL / BYDAY (含 2SU、-1FR) / BYMONTHDAY / BYMONTH
+//   - 时区: UTC ("Z")、浮动时间 (按路由器本地时区)、TZID (按文件内 VTIMEZONE 的
+//     STANDARD/DAYLIGHT 规则换算，夏令时切换前后都正确；找不到定义时按本地时区处理)
+// 不依赖时区数据库 (路由器上通常没有 zoneinfo)，也不引入额外的 crate。
+// ==========================================
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveDateTime, TimeZone, Utc, Weekday};
+use std::collections::HashMap;
+
+// 一个重复规则最多展开多少个周期 (防止永远匹配不上的规则死循环，如 2 月 30 日)
+const MAX_PERIODS: u32 = 20_000;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Zone {
+    Utc,
+    Floating,      // 没写时区 = 路由器本地时间
+    Named(String), // TZID=...
+}
+
+#[derive(Debug, Clone)]
+struct When {
+    local: NaiveDateTime, // 墙上时间 (全天事件为当天 00:00)
+    zone: Zone,
+    all_day: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+enum Freq {
+    #[default]
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Rule {
+    freq: Freq,
+    interval: u32,
+    count: Option<u32>,
+    until: Option<When>,
+    by_day: Vec<(i32, Weekday)>, // (序号, 星期)，序号 0 = 每个，2 = 第二个，-1 = 最后一个
+    by_month_day: Vec<i32>,
+    by_month: Vec<u32>,
+}
+
+// VTIMEZONE 里的一段 STANDARD / DAYLIGHT
+#[derive(Debug, Clone)]
+struct Observance {
+    start: NaiveDateTime, // 切换时刻 (按切换前的墙上时间)
+    offset_from: i32,     // 秒
+    offset_to: i32,
+    rule: Option<Rule>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Event {
+    uid: String,
+    summary: String,
+    start: Option<When>,
+    end: Option<When>,
+    duration: Option<Duration>,
+    rule: Option<Rule>,
+    exdates: Vec<When>,
+    recurrence_id: Option<When>,
+    cancelled: bool,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct Calendar {
+    events: Vec<Event>,
+    zones: HashMap<String, Vec<Observance>>,
+}
+
+// 展开后的一次具体事件
+#[derive(Debug, Clone, PartialEq)]
+pub struct Occurrence {
+    pub uid: String,
+    pub summary: String,
+    pub start: DateTime<Utc>,
+    pub all_day: bool,
+}
+
+// ==========================================
+// 🔍 解析
+// ==========================================
+// 续行: 以空格/Tab 开头的行接到上一行 (去掉这一个空白字符)
+fn unfold(text: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for raw in text.split('\n') {
+        let line = raw.strip_suffix('\r').unwrap_or(raw);
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(prev)) => prev.push_str(rest),
+            _ => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+type Params = Vec<(String, String)>;
+
+// "DTSTART;TZID=Europe/Berlin:20260302T093000" -> ("DTSTART", [("TZID","Europe/Berlin")], "2026...")
+fn split_prop(line: &str) -> Option<(String, Params, &str)> {
+    let mut quoted = false;
+    let colon = line.char_indices().find(|&(_, c)| {
+        if c == '"' {
+            quoted = !quoted;
+        }
+        c == ':' && !quoted
+    })?.0;
+    let mut parts = line[..colon].split(';');
+    let name = parts.next()?.trim().to_ascii_uppercase();
+    let params = parts
+        .filter_map(|p| p.split_once('='))
+        .map(|(k, v)| (k.trim().to_ascii_uppercase(), v.trim_matches('"').to_string()))
+        .collect();
+    Some((name, params, &line[colon + 1..]))
+}
+
+fn param<'a>(params: &'a [(String, String)], key: &str) -> Option<&'a str> {
+    params.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str())
+}
+
+fn unescape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') | Some('N') => out.push(' '),
+            Some(other) => out.push(other),
+            None => {}
+        }
+    }
+    out.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn parse_when(value: &str, params: &[(String, String)]) -> Option<When> {
+    let value = value.trim();
+    if param(params, "VALUE") == Some("DATE") || value.len() == 8 {
+        let d = NaiveDate::parse_from_str(value, "%Y%m%d").ok()?;
+        return Some(When { local: d.and_hms_opt(0, 0, 0)?, zone: Zone::Floating, all_day: true });
+    }
+    let (text, zone) = match value.strip_suffix(['Z', 'z']) {
+        Some(t) => (t, Zone::Utc),
+        None => match param(params, "TZID") {
+            Some(id) => (value, Zone::Named(id.trim_start_matches('/').to_string())),
+            None => (value, Zone::Floating),
+        },
+    };
+    let local = NaiveDateTime::parse_from_str(text, "%Y%m%dT%H%M%S").ok()?;
+    Some(When { local, zone, all_day: false })
+}
+
+// "+0530" / "-0400" / "+013000" -> 秒
+fn parse_offset(s: &str) -> Option<i32> {
+    let s = s.trim();
+    let sign = match s.chars().next()? {
+        '-' => -1,
+        '+' => 1,
+        _ => return None,
+    };
+    let digits = &s[1..];
+    if digits.len() < 4 || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let h: i32 = digits[0..2].parse().ok()?;
+    let m: i32 = digits[2..4].parse().ok()?;
+    let sec: i32 = digits.get(4..6).and_then(|x| x.parse().ok()).unwrap_or(0);
+    Some(sign * (h * 3600 + m * 60 + sec))
+}
+
+// "PT1H30M" / "P1D" / "P1W" / "-PT15M"
+fn parse_duration(s: &str) -> Option<Duration> {
+    let s = s.trim();
+    let (sign, s) = match s.strip_prefix('-') {
+        Some(rest) => (-1, rest),
+        None => (1, s.strip_prefix('+').unwrap_or(s)),
+    };
+    let s = s.strip_prefix('P')?;
+    let mut secs: i64 = 0;
+    let mut num = String::new();
+    for c in s.chars() {
+        match c {
+            '0'..='9' => num.push(c),
+            'T' => {}
+            'W' | 'D' | 'H' | 'M' | 'S' => {
+                let n: i64 = num.parse().ok()?;
+                num.clear();
+                secs += n * match c {
+                    'W' => 7 * 86400,
+                    'D' => 86400,
+                    'H' => 3600,
+                    'M' => 60,
+                    _ => 1,
+                };
+            }
+            _ => return None,
+        }
+    }
+    Some(Duration::seconds(sign * secs))
+}
+
+fn parse_weekday(s: &str) -> Option<Weekday> {
+    Some(match s {
+        "MO" => Weekday::Mon,
+        "TU" => Weekday::Tue,
+        "WE" => Weekday::Wed,
+        "TH" => Weekday::Thu,
+        "FR" => Weekday::Fri,
+        "SA" => Weekday::Sat,
+        "SU" => Weekday::Sun,
+        _ => return None,
+    })
+}
+
+// 不支持的频率 (HOURLY 等) 返回 None，事件按单次处理
+fn parse_rule(value: &str) -> Option<Rule> {
+    let mut rule = Rule { interval: 1, ..Rule::default() };
+    let mut freq = None;
+    for part in value.split(';') {
+        let Some((k, v)) = part.split_once('=') else { continue };
+        let v = v.trim().to_ascii_uppercase();
+        match k.trim().to_ascii_uppercase().as_str() {
+            "FREQ" => {
+                freq = match v.as_str() {
+                    "DAILY" => Some(Freq::Daily),
+                    "WEEKLY" => Some(Freq::Weekly),
+                    "MONTHLY" => Some(Freq::Monthly),
+                    "YEARLY" => Some(Freq::Yearly),
+                    _ => None,
+                }
+            }
+            "INTERVAL" => rule.interval = v.parse().unwrap_or(1).max(1),
+            "COUNT" => rule.count = v.parse().ok(),
+            "UNTIL" => rule.until = parse_when(&v, &[]),
+            "BYDAY" => {
+                rule.by_day = v
+                    .split(',')
+                    .filter_map(|d| {
+                        let d = d.trim();
+                        let split = d.len().checked_sub(2)?;
+                        let n = match &d[..split] {
+                            "" => 0,
+                            n => n.trim_start_matches('+').parse().ok()?,
+                        };
+                        Some((n, parse_weekday(&d[split..])?))
+                    })
+                    .collect()
+            }
+            "BYMONTHDAY" => rule.by_month_day = v.split(',').filter_map(|d| d.trim().parse().ok()).collect(),
+            "BYMONTH" => rule.by_month = v.split(',').filter_map(|d| d.trim().parse().ok()).collect(),
+            _ => {}
+        }
+    }
+    rule.freq = freq?;
+    Some(rule)
+}
+
+pub fn parse(text: &str) -> Calendar {
+    let mut cal = Calendar::default();
+    let mut stack: Vec<String> = Vec::new();
+    let mut event = Event::default();
+    let mut tzid = String::new();
+    let mut obs: Option<Observance> = None;
+
+    for line in unfold(text) {
+        let Some((name, params, value)) = split_prop(&line) else { continue };
+        let inside = stack.last().map(String::as_str).unwrap_or("");
+        match name.as_str() {
+            "BEGIN" => {
+                let comp = value.trim().to_ascii_uppercase();
+                match comp.as_str() {
+                    "VEVENT" => event = Event::default(),
+                    "VTIMEZONE" => tzid.clear(),
+                    "STANDARD" | "DAYLIGHT" => {
+                        obs = Some(Observance { start: NaiveDateTime::MIN, offset_from: 0, offset_to: 0, rule: None })
+                    }
+                    _ => {}
+                }
+                stack.push(comp);
+            }
+            "END" => {
+                match stack.pop().as_deref() {
+                    Some("VEVENT") if event.start.is_some() => cal.events.push(std::mem::take(&mut event)),
+                    Some("STANDARD") | Some("DAYLIGHT") => {
+                        if let Some(o) = obs.take() {
+                            cal.zones.entry(tzid.clone()).or_default().push(o);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            _ if inside == "VEVENT" => match name.as_str() {
+                "UID" => event.uid = value.trim().to_string(),
+                "SUMMARY" => event.summary = unescape(value),
+                "DTSTART" => event.start = parse_when(value, &params),
+                "DTEND" => event.end = parse_when(value, &params),
+                "DURATION" => event.duration = parse_duration(value),
+                "RRULE" => event.rule = parse_rule(value),
+                "EXDATE" => event.exdates.extend(value.split(',').filter_map(|v| parse_when(v, &params))),
+                "RECURRENCE-ID" => event.recurrence_id = parse_when(value, &params),
+                "STATUS" => event.cancelled = value.trim().eq_ignore_ascii_case("CANCELLED"),
+                _ => {}
+            },
+            "TZID" if inside == "VTIMEZONE" => tzid = value.trim().trim_start_matches('/').to_string(),
+            _ if inside == "STANDARD" || inside == "DAYLIGHT" => {
+                if let Some(o) = obs.as_mut() {
+                    match name.as_str() {
+                        "DTSTART" => o.start = parse_when(value, &[]).map(|w| w.local).unwrap_or(NaiveDateTime::MIN),
+                        "TZOFFSETFROM" => o.offset_from = parse_offset(value).unwrap_or(0),
+                        "TZOFFSETTO" => o.offset_to = parse_offset(value).unwrap_or(0),
+                        "RRULE" => o.rule = parse_rule(value),
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    cal
+}
+
+// ==========================================
+// 🔁 重复规则展开 (在事件自己的墙上时间里展开，跨夏令时也保持同一钟点)
+// ==========================================
+fn days_in_month(y: i32, m: u32) -> u32 {
+    let (ny, nm) = if m == 12 { (y + 1, 1) } else { (y, m + 1) };
+    NaiveDate::from_ymd_opt(ny, nm, 1)
+        .and_then(|d| d.pred_opt())
+        .map_or(28, |d| d.day())
+}
+
+fn add_months(y: i32, m: u32, n: i64) -> Option<(i32, u32)> {
+    let total = i64::from(y) * 12 + i64::from(m) - 1 + n;
+    Some((i32::try_from(total.div_euclid(12)).ok()?, (total.rem_euclid(12) + 1) as u32))
+}
+
+// 某年某月里符合 BYMONTHDAY / BYDAY 的日期 (都没写时取 default_day)
+fn month_days(rule: &Rule, y: i32, m: u32, default_day: u32) -> Vec<NaiveDate> {
+    let last = days_in_month(y, m);
+    let date = |d: u32| NaiveDate::from_ymd_opt(y, m, d);
+    let mut out: Vec<NaiveDate> = rule
+        .by_month_day
+        .iter()
+        .filter_map(|&md| {
+            let d = if md > 0 { md } else { last as i32 + 1 + md };
+            (1..=last as i32).contains(&d).then(|| date(d as u32)).flatten()
+        })
+        .collect();
+    if !rule.by_month_day.is_empty() && !rule.by_day.is_empty() {
+        // 两者都写时取交集 (如 "13 号且是星期五")
+        out.retain(|d| rule.by_day.iter().any(|(_, wd)| d.weekday() == *wd));
+    } else {
+        for &(n, wd) in &rule.by_day {
+            let all: Vec<NaiveDate> = (1..=last).filter_map(date).filter(|d| d.weekday() == wd).collect();
+            match n {
+                0 => out.extend(all),
+                n if n > 0 => out.extend(all.get(n as usize - 1)),
+                n => out.extend(all.len().checked_sub(n.unsigned_abs() as usize).and_then(|i| all.get(i))),
+            }
+        }
+    }
+    if rule.by_month_day.is_empty() && rule.by_day.is_empty() {
+        out.extend(date(default_day));
+    }
+    out.sort();
+    out.dedup();
+    out
+}
+
+// 第 k 个周期里的候选日期 (升序)
+fn period_dates(rule: &Rule, start: NaiveDate, k: u32) -> Vec<NaiveDate> {
+    let step = i64::from(k) * i64::from(rule.interval);
+    let in_months = |d: &NaiveDate| rule.by_month.is_empty() || rule.by_month.contains(&d.month());
+    let mut dates = match rule.freq {
+        Freq::Daily => {
+            let d = start + Duration::days(step);
+            if rule.by_day.is_empty() || rule.by_day.iter().any(|(_, wd)| d.weekday() == *wd) {
+                vec![d]
+            } else {
+                Vec::new()
+            }
+        }
+        Freq::Weekly => {
+            let week = start - Duration::days(i64::from(start.weekday().num_days_from_monday())) + Duration::weeks(step);
+            let days: Vec<Weekday> = if rule.by_day.is_empty() {
+                vec![start.weekday()]
+            } else {
+                rule.by_day.iter().map(|(_, wd)| *wd).collect()
+            };
+            days.iter().map(|wd| week + Duration::days(i64::from(wd.num_days_from_monday()))).collect()
+        }
+        Freq::Monthly => add_months(start.year(), start.month(), step)
+            .map(|(y, m)| month_days(rule, y, m, start.day()))
+            .unwrap_or_default(),
+        Freq::Yearly => {
+            let y = start.year() + step as i32;
+            let months = if rule.by_month.is_empty() { vec![start.month()] } else { rule.by_month.clone() };
+            months.iter().flat_map(|&m| month_days(rule, y, m, start.day())).collect()
+        }
+    };
+    dates.retain(in_months);
+    dates.sort();
+    dates.dedup();
+    dates
+}
+
+// 逐个回调展开出的墙上时间，回调返回 false 时停止
+fn expand(start: NaiveDateTime, rule: &Rule, until: Option<NaiveDateTime>, mut f: impl FnMut(NaiveDateTime) -> bool) {
+    let mut n = 0;
+    for k in 0..MAX_PERIODS {
+        for d in period_dates(rule, start.date(), k) {
+            let t = d.and_time(start.time());
+            if t < start {
+                continue;
+            }
+            if until.is_some_and(|u| t > u) || rule.count.is_some_and(|c| n >= c) {
+                return;
+            }
+            n += 1;
+            if !f(t) {
+                return;
+            }
+        }
+    }
+}
+
+// ==========================================
+// 🕰️ 时区换算
+// ==========================================
+impl Observance {
+    // 不晚于 t 的最近一次切换
+    fn last_onset(&self, t: NaiveDateTime) -> Option<NaiveDateTime> {
+        let mut last = None;
+        match &self.rule {
+            Some(rule) => expand(self.start, rule, None, |onset| {
+                if onset > t {
+                    return false;
+                }
+                last = Some(onset);
+                true
+            }),
+            None => last = (self.start <= t).then_some(self.start),
+        }
+        last
+    }
+}
+
+// 墙上时间 t 处的 UTC 偏移 (秒)
+fn zone_offset(obs: &[Observance], t: NaiveDateTime) -> i32 {
+    obs.iter()
+        .filter_map(|o| o.last_onset(t).map(|at| (at, o.offset_to)))
+        .max_by_key(|(at, _)| *at)
+        .map(|(_, off)| off)
+        .or_else(|| obs.iter().min_by_key(|o| o.start).map(|o| o.offset_from))
+        .unwrap_or(0)
+}
+
+impl Calendar {
+    fn zone<'a>(&'a self, zone: &'a Zone) -> &'a Zone {
+        match zone {
+            Zone::Named(id) if !self.zones.contains_key(id) => {
+                if matches!(id.as_str(), "UTC" | "GMT" | "Etc/UTC" | "Etc/GMT" | "Z") {
+                    &Zone::Utc
+                } else {
+                    &Zone::Floating
+                }
+            }
+            z => z,
+        }
+    }
+
+    fn to_utc(&self, local: NaiveDateTime, zone: &Zone) -> Option<DateTime<Utc>> {
+        match self.zone(zone) {
+            Zone::Utc => Some(Utc.from_utc_datetime(&local)),
+            Zone::Named(id) => {
+                let off = zone_offset(&self.zones[id], local);
+                Some(Utc.from_utc_datetime(&(local - Duration::seconds(i64::from(off)))))
+            }
+            // 夏令时跳过的那一小时不存在，顺延一小时
+            Zone::Floating => local
+                .and_local_timezone(Local)
+                .earliest()
+                .or_else(|| (local + Duration::hours(1)).and_local_timezone(Local).earliest())
+                .map(|t| t.with_timezone(&Utc)),
+        }
+    }
+
+    fn to_wall(&self, at: DateTime<Utc>, zone: &Zone) -> NaiveDateTime {
+        match self.zone(zone) {
+            Zone::Utc => at.naive_utc(),
+            Zone::Named(id) => {
+                let obs = &self.zones[id];
+                let utc = at.naive_utc();
+                let guess = utc + Duration::seconds(i64::from(zone_offset(obs, utc)));
+                utc + Duration::seconds(i64::from(zone_offset(obs, guess)))
+            }
+            Zone::Floating => at.with_timezone(&Local).naive_local(),
+        }
+    }
+
+    fn when_utc(&self, w: &When) -> Option<DateTime<Utc>> {
+        self.to_utc(w.local, &w.zone)
+    }
+
+    // [now, now + horizon) 内还没开始的事件，以及今天仍在进行的全天事件；按开始时间排序
+    pub fn upcoming(&self, now: DateTime<Utc>, horizon: Duration) -> Vec<Occurrence> {
+        let end_of_window = now + horizon;
+        // 被单独改期 (RECURRENCE-ID) 的那几次不再按原规则出现
+        let moved: Vec<(&str, DateTime<Utc>)> = self
+            .events
+            .iter()
+            .filter_map(|e| Some((e.uid.as_str(), self.when_utc(e.recurrence_id.as_ref()?)?)))
+            .collect();
+
+        let mut out = Vec::new();
+        for ev in self.events.iter().filter(|e| !e.cancelled) {
+            let Some(start) = &ev.start else { continue };
+            let length = match (&ev.end, ev.duration) {
+                (Some(end), _) => self.when_utc(end).zip(self.when_utc(start)).map(|(e, s)| e - s),
+                (None, Some(d)) => Some(d),
+                (None, None) => None,
+            }
+            .filter(|d| *d >= Duration::zero())
+            .unwrap_or(if start.all_day { Duration::days(1) } else { Duration::zero() });
+
+            let mut push = |at: DateTime<Utc>| {
+                let wanted = if start.all_day { at + length > now } else { at >= now };
+                if wanted && at < end_of_window {
+                    out.push(Occurrence {
+                        uid: ev.uid.clone(),
+                        summary: ev.summary.clone(),
+                        start: at,
+                        all_day: start.all_day,
+                    });
+                }
+            };
+
+            match (&ev.rule, &ev.recurrence_id) {
+                (Some(rule), None) => {
+                    let exdates: Vec<DateTime<Utc>> = ev.exdates.iter().filter_map(|w| self.when_utc(w)).collect();
+                    let until = rule.until.as_ref().and_then(|u| match u.zone {
+                        Zone::Utc => Some(self.to_wall(self.when_utc(u)?, &start.zone)),
+                        _ if u.all_day => u.local.date().and_hms_opt(23, 59, 59),
+                        _ => Some(u.local),
+                    });
+                    // 先用墙上时间粗筛掉早已结束的，少做时区换算
+                    let skip_before = self.to_wall(now, &start.zone) - length - Duration::days(2);
+                    expand(start.local, rule, until, |t| {
+                        if t < skip_before {
+                            return true;
+                        }
+                        let Some(at) = self.to_utc(t, &start.zone) else { return true };
+                        if at >= end_of_window {
+                            return false;
+                        }
+                        if !exdates.contains(&at) && !moved.contains(&(ev.uid.as_str(), at)) {
+                            push(at);
+                        }
+                        true
+                    });
+                }
+                _ => {
+                    if let Some(at) = self.when_utc(start) {
+                        push(at);
+                    }
+                }
+            }
+        }
+        out.sort_by_key(|o| o.start);
+        out
+    }
+}
+
+// ==========================================
+// 🖥️ 显示: 今天 "14:30 STANDUP"，一周内 "TUE 09:00 STANDUP"，更远 "10-28 09:00 STANDUP"
+// 全天事件: "TODAY BIRTHDAY" / "TUE BIRTHDAY" / "10-28 BIRTHDAY"
+// ==========================================
+// 列表里挑要显示的: 今天还有定时事件就先显示它，否则按时间顺序第一个
+pub fn next<'a, Tz: TimeZone>(items: &'a [Occurrence], now: &DateTime<Tz>) -> Option<&'a Occurrence> {
+    let today = now.date_naive();
+    items
+        .iter()
+        .find(|o| !o.all_day && o.start.with_timezone(&now.timezone()).date_naive() == today)
+        .or_else(|| items.first())
+}
+
+pub fn display<Tz: TimeZone>(occ: &Occurrence, now: &DateTime<Tz>) -> String
+where
+    Tz::Offset: std::fmt::Display,
+{
+    let start = occ.start.with_timezone(&now.timezone());
+    let (day, today) = (start.date_naive(), now.date_naive());
+    let date = if occ.all_day && day <= today {
+        "TODAY".to_string()
+    } else if day == today {
+        String::new()
+    } else if day < today + Duration::days(7) {
+        start.format("%a").to_string().to_uppercase()
+    } else {
+        start.format("%m-%d").to_string()
+    };
+    let mut text = date;
+    if !occ.all_day {
+        if !text.is_empty() {
+            text.push(' ');
+        }
+        text.push_str(&start.format("%H:%M").to_string());
+    }
+    let title = occ.summary.to_uppercase();
+    if title.is_empty() {
+        text
+    } else {
+        format!("{} {}", text, title)
+    }
+}
+
+// ==========================================
+// ⏰ 会前提醒: 开始前 N 分钟内插播一次 (同一次事件只提醒一次)
+// ==========================================
+#[derive(Default)]
+pub struct AgendaAlarm {
+    fired: HashMap<String, DateTime<Utc>>, // "uid@开始时间戳" -> 开始时间
+}
+
+impl AgendaAlarm {
+    pub fn update<Tz: TimeZone>(&mut self, items: &[Occurrence], now: &DateTime<Tz>, minutes: u32) -> Vec<String>
+    where
+        Tz::Offset: std::fmt::Display,
+    {
+        let utc_now = now.with_timezone(&Utc);
+        self.fired.retain(|_, start| *start > utc_now - Duration::days(1));
+        items
+            .iter()
+            .filter(|o| !o.all_day && o.start >= utc_now && o.start - Duration::minutes(i64::from(minutes)) <= utc_now)
+            .filter(|o| self.fired.insert(format!("{}@{}", o.uid, o.start.timestamp()), o.start).is_none())
+            .map(|o| display(o, now))
+            .collect()
+    }
+}
+
+// ==========================================
+// 🧪 单元测试
+// ==========================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::FixedOffset;
+
+    const ICS: &str = "BEGIN:VCALENDAR\r
+VERSION:2.0\r
+BEGIN:VTIMEZONE\r
+TZID:America/New_York\r
+BEGIN:DAYLIGHT\r
+TZOFFSETFROM:-0500\r
+TZOFFSETTO:-0400\r
+DTSTART:20070311T020000\r
+RRULE:FREQ=YEARLY;BYMONTH=3;BYDAY=2SU\r
+END:DAYLIGHT\r
+BEGIN:STANDARD\r
+TZOFFSETFROM:-0400\r
+TZOFFSETTO:-0500\r
+DTSTART:20071104T020000\r
+RRULE:FREQ=YEARLY;BYMONTH=11;BYDAY=1SU\r
+END:STANDARD\r
+END:VTIMEZONE\r
+BEGIN:VEVENT\r
+UID:standup@x\r
+SUMMARY:Standup\r
+DTSTART;TZID=America/New_York:20260302T093000\r
+DTEND;TZID=America/New_York:20260302T094500\r
+RRULE:FREQ=WEEKLY;BYDAY=MO,WE,FR;UNTIL=20260320T133000Z\r
+EXDATE;TZID=America/New_York:20260304T093000\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:standup@x\r
+RECURRENCE-ID;TZID=America/New_York:20260311T093000\r
+SUMMARY:Standup (moved)\r
+DTSTART;TZID=America/New_York:20260311T110000\r
+DURATION:PT15M\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:review\r
+SUMMARY:Quarterly plan\r
+ ning review\\, Q1\r
+DTSTART:20260130T170000Z\r
+RRULE:FREQ=MONTHLY;BYDAY=-1FR;COUNT=3\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:bday\r
+SUMMARY:Mom's birthday\r
+DTSTART;VALUE=DATE:20200305\r
+RRULE:FREQ=YEARLY\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:trip\r
+SUMMARY:Trip\r
+DTSTART;VALUE=DATE:20260308\r
+DTEND;VALUE=DATE:20260311\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+UID:gone\r
+SUMMARY:Cancelled\r
+STATUS:CANCELLED\r
+DTSTART:20260303T120000Z\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+
+    fn utc(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    fn starts(items: &[Occurrence], uid: &str) -> Vec<String> {
+        items.iter().filter(|o| o.uid == uid).map(|o| o.start.format("%m-%d %H:%M").to_string()).collect()
+    }
+
+    #[test]
+    fn recurrence_timezones_and_exceptions() {
+        let cal = parse(ICS);
+        let items = cal.upcoming(utc("2026-03-01T00:00:00Z"), Duration::days(60));
+        // 3/8 起纽约进入夏令时: 09:30 从 14:30Z 变为 13:30Z；3/4 被排除，3/11 改到 11:00
+        assert_eq!(
+            starts(&items, "standup@x"),
+            ["03-02 14:30", "03-06 14:30", "03-09 13:30", "03-11 15:00", "03-13 13:30", "03-16 13:30", "03-18 13:30", "03-20 13:30"]
+        );
+
+        // 每月最后一个星期五，共 3 次 (1/30、2/27 已过去，4 月起不再有)
+        assert_eq!(starts(&items, "review"), ["03-27 17:00"]);
+        assert!(items.iter().any(|o| o.summary == "Quarterly planning review, Q1"));
+
+        // 全天事件按本地日期，每年重复
+        let bday: Vec<_> = items.iter().filter(|o| o.uid == "bday").collect();
+        assert_eq!(bday.len(), 1);
+        assert!(bday[0].all_day);
+        assert_eq!(bday[0].start.with_timezone(&Local).date_naive(), NaiveDate::from_ymd_opt(2026, 3, 5).unwrap());
+
+        assert!(items.iter().all(|o| o.uid != "gone"));
+        assert!(items.windows(2).all(|w| w[0].start <= w[1].start));
+    }
+
+    #[test]
+    fn parsing_helpers() {
+        assert_eq!(parse_offset("+0530"), Some(19800));
+        assert_eq!(parse_offset("-0400"), Some(-14400));
+        assert_eq!(parse_duration("PT1H30M"), Some(Duration::minutes(90)));
+        assert_eq!(parse_duration("P1W"), Some(Duration::days(7)));
+        assert_eq!(parse_duration("-PT15M"), Some(Duration::minutes(-15)));
+        let (name, params, value) = split_prop(r#"DTSTART;TZID="GMT+1:00 Berlin":20260302T093000"#).unwrap();
+        assert_eq!((name.as_str(), param(&params, "TZID"), value), ("DTSTART", Some("GMT+1:00 Berlin"), "20260302T093000"));
+        assert!(parse_rule("FREQ=HOURLY").is_none());
+        let r = parse_rule("FREQ=MONTHLY;BYMONTHDAY=31").unwrap();
+        let mut got = Vec::new();
+        expand(NaiveDate::from_ymd_opt(2026, 1, 31).unwrap().and_hms_opt(8, 0, 0).unwrap(), &r, None, |t| {
+            got.push(t.date().to_string());
+            got.len() < 3
+        });
+        // 没有 31 号的月份跳过
+        assert_eq!(got, ["2026-01-31", "2026-03-31", "2026-05-31"]);
+    }
+
+    #[test]
+    fn display_and_reminders() {
+        let cal = parse(ICS);
+        let ny = FixedOffset::west_opt(4 * 3600).unwrap();
+        let now = ny.with_ymd_and_hms(2026, 3, 9, 9, 20, 0).unwrap();
+        let items = cal.upcoming(now.with_timezone(&Utc), Duration::days(30));
+        // 多日全天事件 (3/8~3/10) 仍在进行，但今天还有定时事件时先显示定时的
+        let trip = items.iter().find(|o| o.uid == "trip").unwrap();
+        assert_eq!(display(trip, &now), "TODAY TRIP");
+        assert_eq!(display(next(&items, &now).unwrap(), &now), "09:30 STANDUP");
+        let moved = items.iter().find(|o| o.summary == "Standup (moved)").unwrap();
+        assert_eq!(display(moved, &now), "WED 11:00 STANDUP (MOVED)");
+        let far = items.iter().find(|o| o.uid == "review").unwrap();
+        assert_eq!(display(far, &now), "03-27 13:00 QUARTERLY PLANNING REVIEW, Q1");
+
+        let mut alarm = AgendaAlarm::default();
+        assert_eq!(alarm.update(&items, &now, 5), Vec::<String>::new());
+        assert_eq!(alarm.update(&items, &now, 10), ["09:30 STANDUP"]);
+        assert_eq!(alarm.update(&items, &now, 10), Vec::<String>::new()); // 同一次只提醒一次
+
+        // 全天事件当天显示 TODAY
+        let bday = Occurrence {
+            uid: "b".into(),
+            summary: "Mom's birthday".into(),
+            start: utc("2026-03-05T05:00:00Z"),
+            all_day: true,
+        };
+        let morning = ny.with_ymd_and_hms(2026, 3, 5, 8, 0, 0).unwrap();
+        assert_eq!(display(&bday, &morning), "TODAY MOM'S BIRTHDAY");
+        assert_eq!(display(&bday, &ny.with_ymd_and_hms(2026, 3, 2, 8, 0, 0).unwrap()), "THU MOM'S BIRTHDAY");
+    }
+}
@@ -131,8 +131,8 @@ pub fn spawn_button_listener(
                     }
                 }
                 // 2️⃣ 一直按着没松手，检查是否达到长按阈值 (2 秒)
-                else if !long_press_handled {
-                    if press_start.unwrap().elapsed() >= Duration::from_secs(2) {
+                else if let Some(start) = press_start {
+                    if !long_press_handled && start.elapsed() >= Duration::from_secs(2) {
                         #[cfg(debug_assertions)]
                         println!("🌙 [硬件交互] 检测到长按 2 秒！发送息屏/亮屏切换指令！");
 
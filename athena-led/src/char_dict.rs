@@ -78,6 +78,8 @@ pub static CHAR_DICT: Lazy<HashMap<char, Vec<u8>>> = Lazy::new(|| {
     dict.insert(';', vec![0b00000000, 0b00000000, 0b00000000]);
     // [新增] 紧凑版冒号！专供 time_sec 模式使用，只有一列宽！
     dict.insert('^', vec![0b00001010]);
+    // [v2.6.0 暗冒号] 系统时间未校准 (NTP 未同步) 时代替冒号，宽度同 ':'
+    dict.insert('·', vec![0b00000000, 0b00000100, 0b00000000]);
     dict.insert('℃', vec![
         // 1. 左上角的“点” (2个像素高，实心)
         // 0b00000011 表示最上面两颗灯亮，看起来像个点
@@ -90,6 +92,9 @@ pub static CHAR_DICT: Lazy<HashMap<char, Vec<u8>>> = Lazy::new(|| {
         0b00001110, 0b00010001, 0b00010001 
     ]);
 
+    // [v2.6.0] 华氏度 '℉' (--weather-unit f)：同 ℃ 的点 + 大写 F
+    dict.insert('℉', vec![0b00000011, 0b00000000, 0b00011111, 0b00000101, 0b00000101]);
+
     // [新增] 波浪号 '~' (修复 1624 粘连问题)
     dict.insert('~', vec![0b00001000, 0b00010000, 0b00001000]);
     
@@ -196,5 +201,16 @@ pub static CHAR_DICT: Lazy<HashMap<char, Vec<u8>>> = Lazy::new(|| {
     // 两条错开的横线，模拟雾气弥漫
     dict.insert('🌫', vec![0b00001010, 0b00001010, 0b00001010, 0b00001010, 0b00001010]);
 
+    // 🌙 7. 月相 (sun:moon 模块) — 北半球视角，亮面从右向左长
+    // 新月只画轮廓，其余只点亮被照亮的部分
+    dict.insert('🌑', vec![0b00001110, 0b00010001, 0b00010001, 0b00010001, 0b00001110]); // 新月 (轮廓)
+    dict.insert('🌒', vec![0b00000000, 0b00000000, 0b00000000, 0b00010001, 0b00001110]); // 蛾眉月
+    dict.insert('🌓', vec![0b00000000, 0b00000000, 0b00011111, 0b00011111, 0b00001110]); // 上弦月
+    dict.insert('🌔', vec![0b00000000, 0b00001110, 0b00011111, 0b00011111, 0b00001110]); // 盈凸月
+    dict.insert('🌕', vec![0b00001110, 0b00011111, 0b00011111, 0b00011111, 0b00001110]); // 满月
+    dict.insert('🌖', vec![0b00001110, 0b00011111, 0b00011111, 0b00001110, 0b00000000]); // 亏凸月
+    dict.insert('🌗', vec![0b00001110, 0b00011111, 0b00011111, 0b00000000, 0b00000000]); // 下弦月
+    dict.insert('🌘', vec![0b00001110, 0b00010001, 0b00000000, 0b00000000, 0b00000000]); // 残月
+
     dict
 });
\ No newline at end of file
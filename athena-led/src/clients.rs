@@ -0,0 +1,327 @@
+// ==========================================
+// 🏆 clients.rs — 局域网终端流量排行 (top 模块)
+// 数据来源: /proc/net/nf_conntrack 的逐连接字节计数 (需 nf_conntrack_acct=1)
+// 名字来源: /tmp/dhcp.leases 主机名，查不到时用 MAC 尾段 (经 /proc/net/arp 反查)
+// 纯本地读取，两次采样做差得到每台设备的实时速率。
+// 🌟 大连接表读一次要几十毫秒，交给 poller.rs 固定每 2 秒在后台采样，渲染路径只读快照
+// ==========================================
+use crate::poller::Poller;
+use std::collections::HashMap;
+use std::fs;
+use std::time::{Duration, Instant};
+
+// 一条连接的唯一标识: 协议 + 原方向四元组
+type FlowKey = (String, String, String, String, String);
+
+// 单条连接: 发起方 IP (局域网设备) + 双向累计字节
+#[derive(Debug, Clone, PartialEq)]
+struct Flow {
+    client: String,
+    bytes: u64,
+}
+
+/// 排行榜中的一台设备
+#[derive(Debug, Clone, PartialEq)]
+pub struct Talker {
+    pub name: String,
+    pub rate: f64, // 字节/秒 (上下行合计)
+}
+
+// 解析 /proc/net/nf_conntrack 文本 -> 连接表。
+// 行格式: "ipv4 2 tcp 6 431999 ESTABLISHED src=A dst=B sport=x dport=y packets=n bytes=N src=B dst=C ... bytes=M ..."
+// 没开 acct 时行内没有 bytes= 字段，整行跳过
+fn parse_conntrack(content: &str) -> HashMap<FlowKey, Flow> {
+    let mut flows = HashMap::new();
+    for line in content.lines() {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.len() < 4 {
+            continue;
+        }
+        let proto = parts[2].to_string();
+
+        // 只取第一组 (原方向) 的四元组，字节数两个方向相加
+        let (mut src, mut dst, mut sport, mut dport) = (None, None, None, None);
+        let mut bytes = 0u64;
+        let mut has_bytes = false;
+        for field in &parts[3..] {
+            if let Some((k, v)) = field.split_once('=') {
+                match k {
+                    "src" if src.is_none() => src = Some(v),
+                    "dst" if dst.is_none() => dst = Some(v),
+                    "sport" if sport.is_none() => sport = Some(v),
+                    "dport" if dport.is_none() => dport = Some(v),
+                    "bytes" => {
+                        bytes += v.parse::<u64>().unwrap_or(0);
+                        has_bytes = true;
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        if let (Some(src), Some(dst), true) = (src, dst, has_bytes) {
+            let key = (
+                proto,
+                src.to_string(),
+                dst.to_string(),
+                sport.unwrap_or("").to_string(),
+                dport.unwrap_or("").to_string(),
+            );
+            flows.insert(key, Flow { client: src.to_string(), bytes });
+        }
+    }
+    flows
+}
+
+// 两次采样做差 -> 每个发起方 IP 的速率 (字节/秒)。
+// 只统计 is_client 认可的 IP (局域网设备)；
+// 上次没见过的新连接按全部字节计入，已消失的连接不再贡献
+fn client_rates(
+    prev: &HashMap<FlowKey, Flow>,
+    cur: &HashMap<FlowKey, Flow>,
+    secs: f64,
+    is_client: impl Fn(&str) -> bool,
+) -> HashMap<String, f64> {
+    let mut rates: HashMap<String, f64> = HashMap::new();
+    if secs <= 0.0 {
+        return rates;
+    }
+    for (key, flow) in cur {
+        if !is_client(&flow.client) {
+            continue;
+        }
+        let before = prev.get(key).map(|f| f.bytes).unwrap_or(0);
+        // 计数器回绕或连接被复用时差值可能为负，按 0 处理
+        let delta = flow.bytes.saturating_sub(before);
+        *rates.entry(flow.client.clone()).or_insert(0.0) += delta as f64 / secs;
+    }
+    rates
+}
+
+// 解析 /tmp/dhcp.leases: "过期时间 MAC IP 主机名 客户端ID" -> IP -> (MAC, 主机名)
+fn parse_leases(content: &str) -> HashMap<String, (String, String)> {
+    let mut map = HashMap::new();
+    for line in content.lines() {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.len() >= 4 {
+            let host = if parts[3] == "*" { "" } else { parts[3] };
+            map.insert(parts[2].to_string(), (parts[1].to_lowercase(), host.to_string()));
+        }
+    }
+    map
+}
+
+// 解析 /proc/net/arp: IP -> MAC (只要 MAC 非全零的条目)
+fn parse_arp(content: &str) -> HashMap<String, String> {
+    let mut map = HashMap::new();
+    for line in content.lines().skip(1) {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.len() >= 4 && parts[3] != "00:00:00:00:00:00" {
+            map.insert(parts[0].to_string(), parts[3].to_lowercase());
+        }
+    }
+    map
+}
+
+// 屏显名字: 租约主机名 (截 6 字符) > MAC 尾两段 "EE:FF" > IP 末段
+fn display_name(ip: &str, leases: &HashMap<String, (String, String)>, arp: &HashMap<String, String>) -> String {
+    if let Some((_, host)) = leases.get(ip) {
+        if !host.is_empty() {
+            return host.chars().take(6).collect::<String>().to_uppercase();
+        }
+    }
+    let mac = leases
+        .get(ip)
+        .map(|(m, _)| m.as_str())
+        .or_else(|| arp.get(ip).map(|m| m.as_str()));
+    match mac {
+        Some(m) if m.len() >= 5 => m[m.len() - 5..].to_uppercase(),
+        _ => ip.rsplit(['.', ':']).next().unwrap_or(ip).to_string(),
+    }
+}
+
+/// 按速率降序排出前 n 名 (速率相同按名字排序，保证显示稳定)
+fn rank(rates: HashMap<String, f64>, n: usize, name_of: impl Fn(&str) -> String) -> Vec<Talker> {
+    let mut list: Vec<Talker> = rates
+        .into_iter()
+        .map(|(ip, rate)| Talker { name: name_of(&ip), rate })
+        .collect();
+    list.sort_by(|a, b| b.rate.total_cmp(&a.rate).then_with(|| a.name.cmp(&b.name)));
+    list.truncate(n);
+    list
+}
+
+// 有连接但没有一条带 bytes= 字段 = 没开 acct；空表 (网络空闲 / 模块没加载) 不算
+fn acct_missing(content: &str) -> bool {
+    content.contains("src=") && !content.contains("bytes=")
+}
+
+// 一轮采样的结果
+#[derive(Debug, Clone, PartialEq)]
+pub enum Board {
+    Wait,                // 还只有一次采样，算不出速率
+    NoAcct,              // conntrack 没开字节计数
+    Ranked(Vec<Talker>), // 按速率降序的全部设备
+}
+
+// 采样状态: 上一轮的连接表与时间，只在后台任务里推进 (间隔固定，速率总是最近 2 秒的)
+#[derive(Default)]
+struct Sampler {
+    last: Option<(HashMap<FlowKey, Flow>, Instant)>,
+}
+
+impl Sampler {
+    fn step(&mut self, conntrack: &str, leases: &str, arp: &str, now: Instant) -> Board {
+        if acct_missing(conntrack) {
+            self.last = None;
+            return Board::NoAcct;
+        }
+        let prev = self.last.replace((parse_conntrack(conntrack), now));
+        let (Some((prev, t)), Some((cur, _))) = (prev, self.last.as_ref()) else { return Board::Wait };
+        let (leases, arp) = (parse_leases(leases), parse_arp(arp));
+        let secs = now.duration_since(t).as_secs_f64();
+        // 只有出现在租约或 ARP 表里的 IP 才算局域网设备 (排除路由器自身发起的连接)
+        let rates = client_rates(&prev, cur, secs, |ip| leases.contains_key(ip) || arp.contains_key(ip));
+        let n = rates.len();
+        Board::Ranked(rank(rates, n, |ip| display_name(ip, &leases, &arp)))
+    }
+}
+
+// ==========================================
+// 🧠 排行读取器 (由 SystemMonitor 持有): 首次用到时起后台采样任务，之后只读快照
+// ==========================================
+pub struct ClientTracker {
+    poller: Poller<Board>,
+}
+
+impl ClientTracker {
+    pub fn new() -> Self {
+        Self { poller: Poller::new("TOP", Duration::from_secs(5), Duration::from_secs(2)) }
+    }
+
+    /// 前 n 名的屏显文本列表: ["NAS 1.2M", "PHONE 300K", ...]
+    pub fn lines(&mut self, n: usize) -> Vec<String> {
+        let board = self.poller.get("conntrack", || {
+            let mut sampler = Sampler::default();
+            move || {
+                let read = |path: &str| fs::read_to_string(path).unwrap_or_default();
+                Some(sampler.step(&read("/proc/net/nf_conntrack"), &read("/tmp/dhcp.leases"), &read("/proc/net/arp"), Instant::now()))
+            }
+        });
+        board_lines(board, n)
+    }
+}
+
+fn board_lines(board: Option<Option<Board>>, n: usize) -> Vec<String> {
+    match board {
+        None | Some(Some(Board::Wait)) => vec!["TOP:Wait".to_string()],
+        Some(None) => vec!["TOP:Err".to_string()],
+        Some(Some(Board::NoAcct)) => vec!["TOP:NoAcct".to_string()],
+        Some(Some(Board::Ranked(top))) if top.is_empty() => vec!["TOP:Idle".to_string()],
+        Some(Some(Board::Ranked(top))) => top
+            .iter()
+            .take(n)
+            .map(|t| format!("{} {}", t.name, crate::monitor::format_bytes_speed(t.rate)))
+            .collect(),
+    }
+}
+
+// ==========================================
+// 🧪 单元测试
+// ==========================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CT1: &str = "\
+ipv4     2 tcp      6 431999 ESTABLISHED src=192.168.1.10 dst=1.1.1.1 sport=5000 dport=443 packets=10 bytes=1000 src=1.1.1.1 dst=100.64.0.2 sport=443 dport=5000 packets=12 bytes=9000 [ASSURED] mark=0 zone=0 use=2
+ipv4     2 udp      17 60 src=192.168.1.20 dst=8.8.8.8 sport=6000 dport=53 packets=1 bytes=60 src=8.8.8.8 dst=100.64.0.2 sport=53 dport=6000 packets=1 bytes=120 mark=0 zone=0 use=2
+ipv4     2 tcp      6 100 ESTABLISHED src=100.64.0.2 dst=9.9.9.9 sport=7000 dport=80 packets=1 bytes=500 src=9.9.9.9 dst=100.64.0.2 sport=80 dport=7000 packets=1 bytes=500 mark=0 use=2
+";
+
+    const CT2: &str = "\
+ipv4     2 tcp      6 431999 ESTABLISHED src=192.168.1.10 dst=1.1.1.1 sport=5000 dport=443 packets=30 bytes=3000 src=1.1.1.1 dst=100.64.0.2 sport=443 dport=5000 packets=40 bytes=27000 [ASSURED] mark=0 zone=0 use=2
+ipv4     2 tcp      6 431999 ESTABLISHED src=192.168.1.20 dst=2.2.2.2 sport=6001 dport=443 packets=5 bytes=2000 src=2.2.2.2 dst=100.64.0.2 sport=443 dport=6001 packets=5 bytes=2000 mark=0 zone=0 use=2
+ipv4     2 tcp      6 100 ESTABLISHED src=100.64.0.2 dst=9.9.9.9 sport=7000 dport=80 packets=9 bytes=90000 src=9.9.9.9 dst=100.64.0.2 sport=80 dport=7000 packets=9 bytes=90000 mark=0 use=2
+";
+
+    fn lan(ip: &str) -> bool {
+        ip.starts_with("192.168.")
+    }
+
+    #[test]
+    fn conntrack_parsing() {
+        let flows = parse_conntrack(CT1);
+        assert_eq!(flows.len(), 3);
+        let key = (
+            "tcp".to_string(),
+            "192.168.1.10".to_string(),
+            "1.1.1.1".to_string(),
+            "5000".to_string(),
+            "443".to_string(),
+        );
+        assert_eq!(flows[&key], Flow { client: "192.168.1.10".to_string(), bytes: 10000 });
+        // 未开启 acct (无 bytes= 字段) 时整行跳过
+        let no_acct = "ipv4 2 tcp 6 100 ESTABLISHED src=192.168.1.10 dst=1.1.1.1 sport=1 dport=2 src=1.1.1.1 dst=192.168.1.10 sport=2 dport=1 mark=0 use=1";
+        assert!(parse_conntrack(no_acct).is_empty());
+    }
+
+    #[test]
+    fn rates_from_two_samples() {
+        let prev = parse_conntrack(CT1);
+        let cur = parse_conntrack(CT2);
+        let rates = client_rates(&prev, &cur, 2.0, lan);
+        // .10: 10000 -> 30000, 2 秒 = 10000 B/s
+        assert_eq!(rates["192.168.1.10"], 10000.0);
+        // .20: 旧 DNS 连接消失不计，新连接 4000 字节全部计入 = 2000 B/s
+        assert_eq!(rates["192.168.1.20"], 2000.0);
+        // 路由器自身发起的连接不算局域网设备
+        assert!(!rates.contains_key("100.64.0.2"));
+    }
+
+    #[test]
+    fn names_and_ranking() {
+        let leases = parse_leases(
+            "1700000000 aa:bb:cc:dd:ee:01 192.168.1.10 nas-server 01:aa:bb\n\
+             1700000000 aa:bb:cc:dd:ee:02 192.168.1.20 * 01:aa:bb\n",
+        );
+        let arp = parse_arp(
+            "IP address       HW type     Flags       HW address            Mask     Device\n\
+             192.168.1.30     0x1         0x2         11:22:33:44:55:66     *        br-lan\n",
+        );
+        assert_eq!(display_name("192.168.1.10", &leases, &arp), "NAS-SE");
+        assert_eq!(display_name("192.168.1.20", &leases, &arp), "EE:02");
+        assert_eq!(display_name("192.168.1.30", &leases, &arp), "55:66");
+        assert_eq!(display_name("192.168.1.40", &leases, &arp), "40");
+
+        let mut rates = HashMap::new();
+        rates.insert("192.168.1.10".to_string(), 100.0);
+        rates.insert("192.168.1.20".to_string(), 5000.0);
+        rates.insert("192.168.1.30".to_string(), 300.0);
+        let top = rank(rates, 2, |ip| display_name(ip, &leases, &arp));
+        assert_eq!(top.len(), 2);
+        assert_eq!(top[0].name, "EE:02");
+        assert_eq!(top[1].name, "55:66");
+    }
+
+    // 后台采样: 第一轮只记基线；之后是相邻两轮的速率；空表是空闲而不是没开 acct
+    #[test]
+    fn sampler_states() {
+        let leases = "1700000000 aa:bb:cc:dd:ee:01 192.168.1.10 nas 01:aa:bb\n\
+                      1700000000 aa:bb:cc:dd:ee:02 192.168.1.20 phone 01:aa:bb\n";
+        let t0 = Instant::now();
+        let mut s = Sampler::default();
+        assert_eq!(s.step(CT1, leases, "", t0), Board::Wait);
+        let Board::Ranked(top) = s.step(CT2, leases, "", t0 + Duration::from_secs(2)) else { panic!() };
+        assert_eq!(top[0], Talker { name: "NAS".to_string(), rate: 10000.0 });
+        let lines = board_lines(Some(Some(Board::Ranked(top))), 1);
+        assert_eq!(lines.len(), 1);
+
+        assert_eq!(s.step("", leases, "", t0 + Duration::from_secs(4)), Board::Ranked(vec![]));
+        assert_eq!(board_lines(Some(Some(Board::Ranked(vec![]))), 3), vec!["TOP:Idle"]);
+
+        let no_acct = "ipv4 2 tcp 6 100 ESTABLISHED src=192.168.1.10 dst=1.1.1.1 sport=1 dport=2 src=1.1.1.1 dst=192.168.1.10 sport=2 dport=1 mark=0 use=1";
+        assert_eq!(s.step(no_acct, leases, "", t0 + Duration::from_secs(6)), Board::NoAcct);
+        assert_eq!(board_lines(None, 3), vec!["TOP:Wait"]);
+    }
+}
@@ -109,7 +109,7 @@ fn handle_command(
             let secs = parts.next().and_then(|s| s.parse::<u64>().ok());
             let text = parts.next().unwrap_or("").trim();
             match (secs, text.is_empty()) {
-                (Some(secs), false) if secs >= 1 && secs <= 300 => {
+                (Some(secs), false) if (1..=300).contains(&secs) => {
                     if let Ok(mut st) = state.lock() {
                         st.pending_show = Some((text.to_string(), secs));
                     }
@@ -0,0 +1,223 @@
+// ==========================================
+// 💽 disk.rs — 存储空间监控 (disk 模块 + 空间不足告警)
+// 用 statvfs 直接问内核，不依赖 df 的输出格式。
+// 先查 /proc/self/mounts 确认路径真是挂载点: U 盘拔掉后 /mnt/sda1 只是 overlay 上的空目录，
+// statvfs 会报出 overlay 的空间，显示和告警都会看错文件系统，这种情况按无数据 (D:--) 处理。
+// 模块参数: "disk:/overlay" (已用百分比) / "disk:/mnt/sda1,free" (剩余字节)
+// 告警参数: --disk-alert "/overlay=10%,/mnt/sda1=2G" (剩余低于百分比或字节数时告警)
+// ==========================================
+use crate::monitor::format_bytes_total;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DiskUsage {
+    pub used: u64,
+    pub avail: u64, // 普通用户可用 (与 df 的 Available 一致，不含 root 保留块)
+}
+
+impl DiskUsage {
+    // 与 df 的 Use% 算法一致: used / (used + avail)，向上取整
+    pub fn used_pct(&self) -> u64 {
+        let denom = self.used + self.avail;
+        if denom == 0 { 0 } else { (self.used * 100).div_ceil(denom) }
+    }
+}
+
+// 告警阈值: 剩余百分比 或 剩余字节数
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Threshold {
+    FreePct(u64),
+    FreeBytes(u64),
+}
+
+impl Threshold {
+    pub fn is_low(&self, u: &DiskUsage) -> bool {
+        match *self {
+            Threshold::FreePct(p) => 100 - u.used_pct().min(100) < p,
+            Threshold::FreeBytes(b) => u.avail < b,
+        }
+    }
+}
+
+// 解析 "10%" / "2G" / "500M" / "1T" / 纯数字 (字节)
+fn parse_threshold(s: &str) -> Option<Threshold> {
+    let s = s.trim();
+    if let Some(p) = s.strip_suffix('%') {
+        return p.trim().parse().ok().map(Threshold::FreePct);
+    }
+    let upper = s.to_ascii_uppercase();
+    let (num, mul) = match upper.chars().last()? {
+        'K' => (&upper[..upper.len() - 1], 1u64 << 10),
+        'M' => (&upper[..upper.len() - 1], 1 << 20),
+        'G' => (&upper[..upper.len() - 1], 1 << 30),
+        'T' => (&upper[..upper.len() - 1], 1 << 40),
+        _ => (upper.as_str(), 1),
+    };
+    let v: f64 = num.trim().parse().ok()?;
+    Some(Threshold::FreeBytes((v * mul as f64) as u64))
+}
+
+// 解析告警配置 "/overlay=10%,/mnt/sda1=2G" (逗号或空格分隔，格式错误的项跳过)
+pub fn parse_alert_spec(spec: &str) -> Vec<(String, Threshold)> {
+    spec.split([',', ' '])
+        .filter_map(|item| {
+            let (mount, th) = item.trim().split_once('=')?;
+            let mount = mount.trim();
+            if mount.is_empty() {
+                return None;
+            }
+            Some((mount.to_string(), parse_threshold(th)?))
+        })
+        .collect()
+}
+
+// 模块参数 "挂载点[,free]"，挂载点缺省 /overlay
+pub fn parse_param(param: &str) -> (&str, bool) {
+    let (mount, mode) = param.split_once(',').unwrap_or((param, ""));
+    let mount = if mount.trim().is_empty() { "/overlay" } else { mount.trim() };
+    (mount, mode.trim() == "free")
+}
+
+pub fn display(u: Option<DiskUsage>, free_mode: bool) -> String {
+    match (u, free_mode) {
+        (Some(u), true) => format!("F:{}", format_bytes_total(u.avail)),
+        (Some(u), false) => format!("D:{}%", u.used_pct()),
+        (None, _) => "D:--".to_string(),
+    }
+}
+
+// 告警文本: "LOW /overlay 1.2M"
+pub fn alert_text(mount: &str, u: &DiskUsage) -> String {
+    format!("LOW {} {}", mount, format_bytes_total(u.avail))
+}
+
+// /proc/self/mounts 第二列是挂载点 (空格等字符被转义成 \040 这样的八进制)
+fn is_mountpoint(mounts: &str, path: &str) -> bool {
+    let path = match path.trim_end_matches('/') {
+        "" => "/",
+        p => p,
+    };
+    mounts.lines().filter_map(|l| l.split_whitespace().nth(1)).any(|m| unescape_mount(m) == path)
+}
+
+fn unescape_mount(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(i) = rest.find('\\') {
+        out.push_str(&rest[..i]);
+        let code = rest.get(i + 1..i + 4).and_then(|o| u8::from_str_radix(o, 8).ok());
+        match code {
+            Some(c) => {
+                out.push(c as char);
+                rest = &rest[i + 4..];
+            }
+            None => {
+                out.push('\\');
+                rest = &rest[i + 1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(unix)]
+pub fn usage(mount: &str) -> Option<DiskUsage> {
+    // 没有 /proc (非 Linux 调试环境) 时不做挂载点检查
+    if let Ok(mounts) = std::fs::read_to_string("/proc/self/mounts") {
+        if !is_mountpoint(&mounts, mount) {
+            return None;
+        }
+    }
+    let path = std::ffi::CString::new(mount).ok()?;
+    let mut st: libc::statvfs = unsafe { std::mem::zeroed() };
+    // SAFETY: path 是合法的 C 字符串，st 为本地栈变量，statvfs 只写入 st
+    if unsafe { libc::statvfs(path.as_ptr(), &mut st) } != 0 {
+        return None;
+    }
+    let frsize = st.f_frsize as u64;
+    Some(DiskUsage {
+        used: (st.f_blocks as u64).saturating_sub(st.f_bfree as u64) * frsize,
+        avail: st.f_bavail as u64 * frsize,
+    })
+}
+
+#[cfg(not(unix))]
+pub fn usage(_mount: &str) -> Option<DiskUsage> {
+    None
+}
+
+// ==========================================
+// 🧪 单元测试
+// ==========================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const G: u64 = 1 << 30;
+
+    #[test]
+    fn used_percent_like_df() {
+        let u = DiskUsage { used: 63, avail: 37 };
+        assert_eq!(u.used_pct(), 63);
+        // 向上取整: 1/3 -> 34%
+        assert_eq!(DiskUsage { used: 1, avail: 2 }.used_pct(), 34);
+        assert_eq!(DiskUsage { used: 0, avail: 0 }.used_pct(), 0);
+    }
+
+    #[test]
+    fn alert_spec_parsing() {
+        let spec = parse_alert_spec("/overlay=10%, /mnt/sda1=2G bad /tmp=512m =5%");
+        assert_eq!(
+            spec,
+            vec![
+                ("/overlay".to_string(), Threshold::FreePct(10)),
+                ("/mnt/sda1".to_string(), Threshold::FreeBytes(2 * G)),
+                ("/tmp".to_string(), Threshold::FreeBytes(512 << 20)),
+            ]
+        );
+        assert!(parse_alert_spec("").is_empty());
+    }
+
+    #[test]
+    fn thresholds() {
+        let u = DiskUsage { used: 95 * G, avail: 5 * G };
+        assert!(Threshold::FreePct(10).is_low(&u));
+        assert!(!Threshold::FreePct(5).is_low(&u));
+        assert!(Threshold::FreeBytes(6 * G).is_low(&u));
+        assert!(!Threshold::FreeBytes(5 * G).is_low(&u));
+    }
+
+    #[test]
+    fn params_and_display() {
+        assert_eq!(parse_param(""), ("/overlay", false));
+        assert_eq!(parse_param("/mnt/sda1,free"), ("/mnt/sda1", true));
+        let u = DiskUsage { used: 3 * G, avail: G + G / 2 };
+        assert_eq!(display(Some(u), false), "D:67%");
+        assert_eq!(display(Some(u), true), "F:1.50G");
+        assert_eq!(display(None, true), "D:--");
+        assert_eq!(alert_text("/overlay", &u), "LOW /overlay 1.50G");
+    }
+
+    // U 盘拔掉后 /mnt/sda1 不在挂载表里，不能退回父目录的 overlay
+    #[test]
+    fn mountpoint_check() {
+        let mounts = "\
+/dev/root /rom squashfs ro,relatime 0 0
+overlayfs:/overlay / overlay rw,noatime 0 0
+/dev/sda1 /mnt/usb\\040disk ext4 rw,relatime 0 0
+";
+        assert!(is_mountpoint(mounts, "/"));
+        assert!(is_mountpoint(mounts, "/rom/"));
+        assert!(is_mountpoint(mounts, "/mnt/usb disk"));
+        assert!(!is_mountpoint(mounts, "/mnt/sda1"));
+        assert!(!is_mountpoint(mounts, "/mnt"));
+        assert_eq!(unescape_mount(r"a\134b\011c\x"), "a\\b\tc\\x");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn statvfs_root() {
+        assert!(usage("/").is_some());
+        assert!(usage("/definitely/not/here").is_none());
+    }
+}
@@ -0,0 +1,317 @@
+// ==========================================
+// 🔎 dns.rs — DNS 解析健康探测 (dns 模块 + 解析故障告警)
+// "网很慢" 的投诉多半是 DNS: 同一个域名分别问本机 dnsmasq (127.0.0.1:53) 和上游
+// 解析器 (--dns-upstream)，记录耗时和失败原因。手写最小 A 记录查询报文，
+// 不走系统 resolver (它会被 dnsmasq 缓存/hosts 干扰，也测不出是哪一环坏了)。
+// 参数: "dns[:模式]"，模式 local(默认) / up / both
+//   local -> "DNS:3ms"   up -> "UP:28ms"   both -> "3/28ms"   失败 -> "DNS:NX" / "UP:TO"
+// ==========================================
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU16, Ordering};
+use std::time::{Duration, Instant};
+use tokio::net::UdpSocket;
+
+pub const LOCAL_SERVER: &str = "127.0.0.1:53";
+const TIMEOUT: Duration = Duration::from_secs(2);
+const TRIES: usize = 2; // UDP 丢一个包很正常，重发一次再判失败
+const FAILS_TO_ALERT: u32 = 2; // 连续失败几轮才算"坏了"，避免偶发超时刷屏
+
+// 查询 ID: 递增即可，用于丢弃上一次迟到的应答
+static QUERY_ID: AtomicU16 = AtomicU16::new(0x4154);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DnsMode {
+    Local,
+    Upstream,
+    Both,
+}
+
+pub fn parse_param(param: &str) -> DnsMode {
+    match param.trim() {
+        "up" | "upstream" => DnsMode::Upstream,
+        "both" => DnsMode::Both,
+        _ => DnsMode::Local,
+    }
+}
+
+// 失败原因 (屏幕上显示两个字母的缩写)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DnsError {
+    Timeout,  // TO: 没有应答
+    NxDomain, // NX: 域名不存在 (被劫持/配置错误时常见)
+    ServFail, // SF: 上游解析失败
+    Refused,  // RF: 拒绝服务 (ACL)
+    NoAnswer, // NA: 应答成功但没有记录
+    Io,       // IO: 套接字错误 (服务未监听 / 无路由)
+}
+
+impl DnsError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            DnsError::Timeout => "TO",
+            DnsError::NxDomain => "NX",
+            DnsError::ServFail => "SF",
+            DnsError::Refused => "RF",
+            DnsError::NoAnswer => "NA",
+            DnsError::Io => "IO",
+        }
+    }
+}
+
+// 一次查询的结果: 成功为耗时 (ms)
+pub type DnsResult = Result<f64, DnsError>;
+
+// ==========================================
+// 🧱 报文: 标准查询 (RD=1, QTYPE=A, QCLASS=IN) / 应答头解析
+// ==========================================
+pub fn build_query(id: u16, name: &str) -> Option<Vec<u8>> {
+    let mut pkt = Vec::with_capacity(18 + name.len());
+    pkt.extend_from_slice(&id.to_be_bytes());
+    pkt.extend_from_slice(&[0x01, 0x00, 0, 1, 0, 0, 0, 0, 0, 0]); // RD, QDCOUNT=1
+    for label in name.trim_end_matches('.').split('.') {
+        if label.is_empty() || label.len() > 63 {
+            return None;
+        }
+        pkt.push(label.len() as u8);
+        pkt.extend_from_slice(label.as_bytes());
+    }
+    pkt.extend_from_slice(&[0, 0, 1, 0, 1]);
+    Some(pkt)
+}
+
+// 只看头部: ID 对得上、QR=1 才算本次应答 (None = 不是我们的包，继续等)
+pub fn parse_reply(buf: &[u8], id: u16) -> Option<Result<(), DnsError>> {
+    if buf.len() < 12 || buf[..2] != id.to_be_bytes() || buf[2] & 0x80 == 0 {
+        return None;
+    }
+    let ancount = u16::from_be_bytes([buf[6], buf[7]]);
+    Some(match buf[3] & 0x0F {
+        0 if ancount > 0 => Ok(()),
+        0 => Err(DnsError::NoAnswer),
+        2 => Err(DnsError::ServFail),
+        3 => Err(DnsError::NxDomain),
+        5 => Err(DnsError::Refused),
+        _ => Err(DnsError::ServFail),
+    })
+}
+
+// "223.5.5.5" / "223.5.5.5:5353" / "[2400:3200::1]:53" -> SocketAddr (缺省端口 53)
+// 写主机名时走 tokio 的异步解析并限时 (本机解析坏了正是要探测的情况，不能卡住运行时)
+pub async fn server_addr(server: &str) -> Option<SocketAddr> {
+    let server = server.trim();
+    if let Ok(sa) = server.parse::<SocketAddr>() {
+        return Some(sa);
+    }
+    if let Ok(ip) = server.parse::<std::net::IpAddr>() {
+        return Some(SocketAddr::new(ip, 53));
+    }
+    let with_port = if server.contains(':') { server.to_string() } else { format!("{}:53", server) };
+    let mut addrs = tokio::time::timeout(TIMEOUT, tokio::net::lookup_host(with_port)).await.ok()?.ok()?;
+    addrs.next()
+}
+
+// ==========================================
+// 🚀 向指定服务器查询一次 (最多 TRIES 次，每次 TIMEOUT)
+// ==========================================
+pub async fn query(server: SocketAddr, name: &str) -> DnsResult {
+    let bind: SocketAddr = if server.is_ipv6() { "[::]:0" } else { "0.0.0.0:0" }.parse().unwrap();
+    let sock = UdpSocket::bind(bind).await.map_err(|_| DnsError::Io)?;
+    sock.connect(server).await.map_err(|_| DnsError::Io)?;
+    let mut buf = [0u8; 512];
+
+    for _ in 0..TRIES {
+        let id = QUERY_ID.fetch_add(1, Ordering::Relaxed);
+        let pkt = build_query(id, name).ok_or(DnsError::NxDomain)?;
+        let start = Instant::now();
+        // ICMP 端口不可达会让 send/recv 报错 (dnsmasq 没在跑)，直接判 IO 失败
+        sock.send(&pkt).await.map_err(|_| DnsError::Io)?;
+        let deadline = start + TIMEOUT;
+        loop {
+            let left = deadline.saturating_duration_since(Instant::now());
+            match tokio::time::timeout(left, sock.recv(&mut buf)).await {
+                Err(_) => break, // 本次超时，重发
+                Ok(Err(_)) => return Err(DnsError::Io),
+                Ok(Ok(n)) => match parse_reply(&buf[..n], id) {
+                    Some(r) => return r.map(|_| start.elapsed().as_secs_f64() * 1000.0),
+                    None => continue,
+                },
+            }
+        }
+    }
+    Err(DnsError::Timeout)
+}
+
+// ==========================================
+// 📊 最近一轮结果 (本机 + 上游)
+// ==========================================
+#[derive(Debug, Clone, Default)]
+pub struct DnsStatus {
+    pub local: Option<DnsResult>,
+    pub upstream: Option<DnsResult>,
+}
+
+impl DnsStatus {
+    pub fn display(&self, mode: DnsMode) -> String {
+        let one = |label: &str, r: &Option<DnsResult>| match r {
+            None => format!("{}Wait", label),
+            Some(Ok(ms)) => format!("{}{:.0}ms", label, ms),
+            Some(Err(e)) => format!("{}{}", label, e.code()),
+        };
+        match mode {
+            DnsMode::Local => one("DNS:", &self.local),
+            DnsMode::Upstream => one("UP:", &self.upstream),
+            DnsMode::Both => match (&self.local, &self.upstream) {
+                (Some(Ok(l)), Some(Ok(u))) => format!("{:.0}/{:.0}ms", l, u),
+                (Some(l), Some(u)) => {
+                    let part = |r: &DnsResult| match r {
+                        Ok(ms) => format!("{:.0}", ms),
+                        Err(e) => e.code().to_string(),
+                    };
+                    format!("{}/{}", part(l), part(u))
+                }
+                _ => "DNS:Wait".to_string(),
+            },
+        }
+    }
+}
+
+// ==========================================
+// 🚨 解析故障告警状态机
+// 只在默认路由仍在时计数 (断网本身由 --alert-wan 负责，不重复播报)；
+// 本机解析连续失败 FAILS_TO_ALERT 轮进入故障并播报一次，恢复时再播一次。
+// ==========================================
+#[derive(Debug, Default)]
+pub struct DnsAlarm {
+    fails: u32,
+    broken: bool,
+}
+
+impl DnsAlarm {
+    // 返回需要插播的文本 (blink, text)
+    pub fn update(&mut self, status: &DnsStatus, route_up: bool) -> Option<(bool, String)> {
+        if !route_up {
+            self.fails = 0;
+            return None;
+        }
+        match &status.local {
+            Some(Ok(_)) => {
+                self.fails = 0;
+                if self.broken {
+                    self.broken = false;
+                    return Some((false, "DNS OK".to_string()));
+                }
+                None
+            }
+            Some(Err(e)) => {
+                self.fails += 1;
+                if self.broken || self.fails < FAILS_TO_ALERT {
+                    return None;
+                }
+                self.broken = true;
+                // 上游还能解析 = 问题出在本机 dnsmasq
+                Some(match status.upstream {
+                    Some(Ok(_)) => (true, "DNSMASQ FAIL".to_string()),
+                    _ => (true, format!("DNS FAIL {}", e.code())),
+                })
+            }
+            None => None,
+        }
+    }
+}
+
+// ==========================================
+// 🧪 单元测试
+// ==========================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn query_packet() {
+        let q = build_query(0xBEEF, "www.baidu.com").unwrap();
+        assert_eq!(&q[..4], &[0xBE, 0xEF, 0x01, 0x00]);
+        assert_eq!(&q[12..16], &[3, b'w', b'w', b'w']);
+        assert_eq!(&q[q.len() - 5..], &[0, 0, 1, 0, 1]);
+        assert_eq!(q.len(), 12 + 15 + 4);
+        assert_eq!(build_query(1, "example.com."), build_query(1, "example.com"));
+        assert!(build_query(1, "bad..name").is_none());
+        assert!(build_query(1, &"x".repeat(64)).is_none());
+    }
+
+    #[test]
+    fn reply_header() {
+        let mut r = [0u8; 12];
+        r[..2].copy_from_slice(&7u16.to_be_bytes());
+        assert_eq!(parse_reply(&r, 7), None); // QR=0: 是查询不是应答
+        r[2] = 0x81;
+        r[3] = 0x80;
+        assert_eq!(parse_reply(&r, 7), Some(Err(DnsError::NoAnswer)));
+        r[7] = 1;
+        assert_eq!(parse_reply(&r, 7), Some(Ok(())));
+        assert_eq!(parse_reply(&r, 8), None);
+        r[3] = 0x83;
+        assert_eq!(parse_reply(&r, 7), Some(Err(DnsError::NxDomain)));
+        r[3] = 0x82;
+        assert_eq!(parse_reply(&r, 7), Some(Err(DnsError::ServFail)));
+        assert_eq!(parse_reply(&r[..11], 7), None);
+    }
+
+    #[tokio::test]
+    async fn servers_and_display() {
+        assert_eq!(server_addr("223.5.5.5").await, "223.5.5.5:53".parse().ok());
+        assert_eq!(server_addr("127.0.0.1:5353").await, "127.0.0.1:5353".parse().ok());
+        assert_eq!(server_addr("2400:3200::1").await, "[2400:3200::1]:53".parse().ok());
+        assert_eq!(parse_param("up"), DnsMode::Upstream);
+        assert_eq!(parse_param(""), DnsMode::Local);
+
+        let mut s = DnsStatus::default();
+        assert_eq!(s.display(DnsMode::Local), "DNS:Wait");
+        s.local = Some(Ok(3.2));
+        s.upstream = Some(Err(DnsError::Timeout));
+        assert_eq!(s.display(DnsMode::Local), "DNS:3ms");
+        assert_eq!(s.display(DnsMode::Upstream), "UP:TO");
+        assert_eq!(s.display(DnsMode::Both), "3/TO");
+        s.upstream = Some(Ok(27.6));
+        assert_eq!(s.display(DnsMode::Both), "3/28ms");
+    }
+
+    #[test]
+    fn alarm_state_machine() {
+        let fail = DnsStatus { local: Some(Err(DnsError::Timeout)), upstream: Some(Err(DnsError::Timeout)) };
+        let ok = DnsStatus { local: Some(Ok(2.0)), upstream: Some(Ok(20.0)) };
+        let mut a = DnsAlarm::default();
+
+        // 断网期间不计数
+        assert_eq!(a.update(&fail, false), None);
+        assert_eq!(a.update(&fail, false), None);
+        // 有路由: 第一轮不报，第二轮报，之后保持安静
+        assert_eq!(a.update(&fail, true), None);
+        assert_eq!(a.update(&fail, true), Some((true, "DNS FAIL TO".to_string())));
+        assert_eq!(a.update(&fail, true), None);
+        assert_eq!(a.update(&ok, true), Some((false, "DNS OK".to_string())));
+        assert_eq!(a.update(&ok, true), None);
+
+        // 上游正常、本机失败 = dnsmasq 的锅
+        let local_only = DnsStatus { local: Some(Err(DnsError::Io)), upstream: Some(Ok(20.0)) };
+        a.update(&local_only, true);
+        assert_eq!(a.update(&local_only, true), Some((true, "DNSMASQ FAIL".to_string())));
+    }
+
+    // 本地起一个假 DNS 服务器，回一个带 1 条记录的应答
+    #[tokio::test]
+    async fn query_against_stub_server() {
+        let server = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+        let addr = server.local_addr().unwrap();
+        std::thread::spawn(move || {
+            let mut buf = [0u8; 512];
+            let (n, peer) = server.recv_from(&mut buf).unwrap();
+            let mut reply = buf[..n].to_vec();
+            reply[2] = 0x81;
+            reply[3] = 0x80;
+            reply[7] = 1;
+            server.send_to(&reply, peer).unwrap();
+        });
+        let r = query(addr, "www.baidu.com").await;
+        assert!(matches!(r, Ok(ms) if ms >= 0.0), "{:?}", r);
+    }
+}
@@ -0,0 +1,106 @@
+// ==========================================
+// 📊 gauge.rs — 百分比指标的条形仪表 (gauge 模块)
+// 一个字母标签 + 横向进度条，比 "C:42%" 更适合远距离一眼看清；
+// gauge:cores 则每个 CPU 核心画一根 5 像素高的竖柱。
+// 只负责生成列点阵，标签字模由 led_screen::glyph_columns 提供。
+// ==========================================
+
+const FILLED: u8 = 0b01110; // 进度条已填充部分 (中间 3 行)
+const TRACK: u8 = 0b00100;  // 未填充部分只留一条细轨道
+const END_CAP: u8 = 0b11111; // 右端 100% 刻度
+
+// 仪表指标: 屏幕标签
+pub fn label_for(metric: &str) -> &'static str {
+    match metric {
+        "mem" => "M",
+        "conn" => "CT",
+        "quota" => "Q",
+        "cores" => "CPU",
+        _ => "C",
+    }
+}
+
+// 横向进度条 (width 列，最后一列是端点刻度)
+pub fn bar(pct: f64, width: usize) -> Vec<u8> {
+    if width == 0 {
+        return Vec::new();
+    }
+    let filled = ((pct.clamp(0.0, 100.0) / 100.0) * width as f64).round() as usize;
+    (0..width)
+        .map(|i| {
+            if i + 1 == width {
+                END_CAP
+            } else if i < filled {
+                FILLED
+            } else {
+                TRACK
+            }
+        })
+        .collect()
+}
+
+// 每核一根竖柱 (从底部往上 0~5 像素)，柱间空一列
+pub fn cores(usages: &[f64]) -> Vec<u8> {
+    let mut cols = Vec::with_capacity(usages.len() * 2);
+    for (i, u) in usages.iter().enumerate() {
+        if i > 0 {
+            cols.push(0);
+        }
+        let mut h = ((u.clamp(0.0, 100.0) / 100.0) * 5.0).round() as u32;
+        if h == 0 && *u > 0.0 {
+            h = 1;
+        }
+        cols.push(((0x1F_u32 << (5 - h)) & 0x1F) as u8);
+    }
+    cols
+}
+
+// 标签 + 空一列 + 主体，超出 27 列截断
+pub fn compose(label: &[u8], body: &[u8]) -> [u8; 27] {
+    let mut frame = [0u8; 27];
+    let cols = label.iter().chain(std::iter::once(&0)).chain(body.iter());
+    for (slot, c) in frame.iter_mut().zip(cols) {
+        *slot = *c;
+    }
+    frame
+}
+
+// 给定标签宽度后进度条可用的列数
+pub fn bar_width(label_len: usize) -> usize {
+    27usize.saturating_sub(label_len + 1)
+}
+
+// ==========================================
+// 🧪 单元测试
+// ==========================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bar_fill() {
+        let b = bar(50.0, 10);
+        assert_eq!(b.len(), 10);
+        assert_eq!(b.iter().filter(|c| **c == FILLED).count(), 5);
+        assert_eq!(b[9], END_CAP);
+        assert!(bar(0.0, 4).iter().take(3).all(|c| *c == TRACK));
+        assert!(bar(150.0, 4).iter().take(3).all(|c| *c == FILLED));
+        assert!(bar(50.0, 0).is_empty());
+    }
+
+    #[test]
+    fn core_columns() {
+        assert_eq!(cores(&[0.0, 1.0, 50.0, 100.0]), vec![0, 0, 0b10000, 0, 0b11100, 0, 0b11111]);
+    }
+
+    #[test]
+    fn compose_frame() {
+        let label = [0x1F, 0x11, 0x1F];
+        let f = compose(&label, &bar(100.0, bar_width(label.len())));
+        assert_eq!(&f[..4], &[0x1F, 0x11, 0x1F, 0]);
+        assert_eq!(f[4], FILLED);
+        assert_eq!(f[26], END_CAP);
+        // 主体过长时截断
+        assert_eq!(compose(&[], &[1; 40])[26], 1);
+    }
+}
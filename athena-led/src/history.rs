@@ -0,0 +1,173 @@
+// ==========================================
+// 📈 history.rs — 指标历史环形缓冲 + 迷你折线图 (spark 模块)
+// 每个指标保留最近 27 个采样 (正好对应屏幕 27 列)，最新的在最右边。
+// 本地指标由 SystemMonitor 每 SAMPLE_SECS 秒采一次 (搭灯光引擎 0.25 秒刷新的便车)，
+// 延迟由 net_agent 每轮探测时记录。缺失的采样记为 NaN，画成空列。
+// ==========================================
+use std::collections::{HashMap, VecDeque};
+
+pub const HISTORY_LEN: usize = 27;
+pub const SAMPLE_SECS: u64 = 5;
+
+// 固定长度环形缓冲
+#[derive(Debug, Clone, Default)]
+pub struct Ring(VecDeque<f64>);
+
+impl Ring {
+    pub fn push(&mut self, v: f64) {
+        if self.0.len() == HISTORY_LEN {
+            self.0.pop_front();
+        }
+        self.0.push_back(v);
+    }
+
+    pub fn values(&self) -> Vec<f64> {
+        self.0.iter().copied().collect()
+    }
+}
+
+// 按名字索引的一组历史 ("cpu" / "mem" / "temp4" / "rx" / "tx")
+#[derive(Debug, Default)]
+pub struct History(HashMap<String, Ring>);
+
+impl History {
+    pub fn record(&mut self, metric: &str, v: f64) {
+        self.0.entry(metric.to_string()).or_default().push(v);
+    }
+
+    pub fn values(&self, metric: &str) -> Vec<f64> {
+        self.0.get(metric).map(Ring::values).unwrap_or_default()
+    }
+}
+
+// 纵轴量程: 百分比类固定 0~100，速率/延迟从 0 自动缩放，温度按窗口内最小~最大自动缩放
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Scale {
+    Percent,
+    FromZero,
+    Auto,
+}
+
+// 把采样画成 27 列帧 (列字节 bit0 = 最上一行，与字模一致)
+// line = true 时每列只点亮一个像素 (折线)，否则从底部填充 (柱状)
+pub fn render(samples: &[f64], scale: Scale, line: bool) -> [u8; 27] {
+    let mut frame = [0u8; 27];
+    let valid = samples.iter().copied().filter(|v| v.is_finite());
+    let (lo, hi) = match scale {
+        Scale::Percent => (0.0, 100.0),
+        Scale::FromZero => (0.0, valid.fold(0.0, f64::max)),
+        Scale::Auto => valid.fold((f64::MAX, f64::MIN), |(lo, hi), v| (lo.min(v), hi.max(v))),
+    };
+
+    // 右对齐: 采样不足 27 个时左侧留空
+    let tail = &samples[samples.len().saturating_sub(27)..];
+    let offset = 27 - tail.len();
+    for (i, v) in tail.iter().enumerate() {
+        if !v.is_finite() {
+            continue;
+        }
+        let level = if hi > lo {
+            (((v - lo) / (hi - lo)).clamp(0.0, 1.0) * 5.0).round() as u32
+        } else if *v > 0.0 || scale == Scale::Auto {
+            3 // 窗口内数值恒定: 画在中间偏上，与"全 0"区分
+        } else {
+            0
+        };
+        // 非零数值至少点亮 1 格 (Auto 量程下最小值本身就在底线上)
+        let level = if level == 0 && (*v > lo || scale == Scale::Auto) { 1 } else { level };
+        frame[offset + i] = match (level, line) {
+            (0, _) => 0,
+            (h, false) => (0x1F << (5 - h)) & 0x1F,
+            (h, true) => 1 << (5 - h),
+        };
+    }
+    frame
+}
+
+// spark 参数: "指标[/目标][/line]"，如 "cpu" / "temp2/line" / "ping/1.1.1.1"
+// 返回 (指标, 延迟目标, 是否折线)
+pub fn parse_param(param: &str) -> (String, String, bool) {
+    let mut parts = param.split('/').map(str::trim);
+    let metric = parts.next().filter(|m| !m.is_empty()).unwrap_or("cpu").to_lowercase();
+    let mut target = String::new();
+    let mut line = false;
+    for p in parts {
+        match p {
+            "line" => line = true,
+            "bar" => line = false,
+            _ => target = p.to_string(),
+        }
+    }
+    (metric, target, line)
+}
+
+// 指标对应的纵轴量程
+pub fn scale_for(metric: &str) -> Scale {
+    match metric {
+        "cpu" | "mem" => Scale::Percent,
+        m if m.starts_with("temp") => Scale::Auto,
+        _ => Scale::FromZero,
+    }
+}
+
+// ==========================================
+// 🧪 单元测试
+// ==========================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ring_keeps_last_27() {
+        let mut r = Ring::default();
+        for i in 0..40 {
+            r.push(i as f64);
+        }
+        let v = r.values();
+        assert_eq!(v.len(), HISTORY_LEN);
+        assert_eq!((v[0], v[26]), (13.0, 39.0));
+    }
+
+    #[test]
+    fn render_bars_right_aligned() {
+        let f = render(&[0.0, 50.0, 100.0], Scale::Percent, false);
+        // 前 24 列空白
+        assert!(f[..24].iter().all(|c| *c == 0));
+        assert_eq!(f[24], 0);
+        // 50% -> 3 格 (四舍五入 2.5)，从底部 (bit4) 往上填
+        assert_eq!(f[25], 0b11100);
+        assert_eq!(f[26], 0b11111);
+    }
+
+    #[test]
+    fn render_line_and_gaps() {
+        let f = render(&[10.0, f64::NAN, 20.0], Scale::FromZero, true);
+        // 10/20 -> 2.5 -> 3 格高，单像素
+        assert_eq!(f[24], 0b00100);
+        assert_eq!(f[25], 0);
+        assert_eq!(f[26], 0b00001);
+    }
+
+    #[test]
+    fn spark_params() {
+        assert_eq!(parse_param(""), ("cpu".to_string(), String::new(), false));
+        assert_eq!(parse_param("temp2/line"), ("temp2".to_string(), String::new(), true));
+        assert_eq!(parse_param("ping/1.1.1.1"), ("ping".to_string(), "1.1.1.1".to_string(), false));
+        assert_eq!(parse_param("PING/line/8.8.8.8:53").1, "8.8.8.8:53");
+        assert_eq!(scale_for("temp4"), Scale::Auto);
+        assert_eq!(scale_for("rx"), Scale::FromZero);
+    }
+
+    #[test]
+    fn render_auto_scale() {
+        // 温度 50~54 摊满 5 行，最低值仍点亮底线
+        let f = render(&[50.0, 54.0], Scale::Auto, false);
+        assert_eq!(f[25], 0b10000);
+        assert_eq!(f[26], 0b11111);
+        // 平直曲线画在中间
+        let f = render(&[42.0, 42.0], Scale::Auto, true);
+        assert_eq!(f[26], 0b00100);
+        // 全 0 的流量不画
+        assert_eq!(render(&[0.0, 0.0], Scale::FromZero, false), [0u8; 27]);
+    }
+}
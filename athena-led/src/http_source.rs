@@ -0,0 +1,338 @@
+// ==========================================
+// 🔗 http_source.rs — 具名 HTTP 数据源 (http:<名字> 模块)
+// 旧的 http_custom 只有一个地址、一个缓存周期、原样截断返回内容；
+// 这里支持任意多个数据源，每个源各自的刷新间隔 / 请求头 / 提取规则 / 显示模板。
+//
+// 每个源通过 --http-source 传入一个 JSON 对象 (可重复)，init 脚本用 jshn 从 UCI 生成:
+//   {"name":"bili","url":"https://api.bilibili.com/x/relation/stat?vmid=1",
+//    "interval":300,"headers":["Cookie: a=b"],"format":"{$.data.follower} subs"}
+//
+// 模板占位符:
+//   {$.a.b[0]}  JSONPath (仅支持 .键 / [下标] / ['键'])
+//   {/a/b/0}    JSON Pointer (RFC 6901)
+//   {1} / {0}   正则捕获组 (需设置 "regex")
+//   {}          整个响应体 (去首尾空白)
+// 未设置模板时: 有正则显示第 1 个捕获组 (没有分组则整个匹配)，否则显示整个响应体。
+// ==========================================
+use regex::Regex;
+use crate::http_client::Http;
+use serde::Deserialize;
+use serde_json::Value;
+use std::time::{Duration, Instant};
+
+const MIN_INTERVAL: u64 = 10; // 刷新间隔下限 (秒)，防止把别人的 API 打爆
+const RETRY_SECS: u64 = 60; // 请求失败后的重试间隔
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct HttpSource {
+    pub name: String,
+    pub url: String,
+    // 刷新间隔 (秒)，缺省用 --http-cache-secs
+    #[serde(default)]
+    pub interval: Option<u64>,
+    // "Key: Value" 形式的请求头
+    #[serde(default)]
+    pub headers: Vec<String>,
+    #[serde(default)]
+    pub regex: Option<String>,
+    #[serde(default)]
+    pub format: Option<String>,
+    // 显示的最大字符数，缺省用 --http-length
+    #[serde(default)]
+    pub max_len: Option<usize>,
+}
+
+// 解析全部 --http-source，格式错误的跳过并打印原因
+pub fn parse_sources(specs: &[String]) -> Vec<HttpSource> {
+    specs
+        .iter()
+        .filter_map(|spec| match serde_json::from_str::<HttpSource>(spec) {
+            Ok(src) if !src.name.is_empty() && !src.url.is_empty() => Some(src),
+            Ok(_) => {
+                println!("⚠️ [HTTP 源] 缺少 name 或 url，已忽略: {}", spec);
+                None
+            }
+            Err(e) => {
+                println!("⚠️ [HTTP 源] 配置解析失败 ({}): {}", e, spec);
+                None
+            }
+        })
+        .collect()
+}
+
+// ==========================================
+// 🧭 取值: JSONPath (精简版) / JSON Pointer
+// ==========================================
+pub fn json_path<'a>(root: &'a Value, path: &str) -> Option<&'a Value> {
+    if path.starts_with('/') {
+        return root.pointer(path);
+    }
+    let mut rest = path.strip_prefix('$')?;
+    let mut cur = root;
+    while !rest.is_empty() {
+        if let Some(r) = rest.strip_prefix('.') {
+            let end = r.find(['.', '[']).unwrap_or(r.len());
+            cur = cur.get(&r[..end])?;
+            rest = &r[end..];
+        } else if let Some(r) = rest.strip_prefix('[') {
+            let end = r.find(']')?;
+            let key = r[..end].trim();
+            cur = match key.parse::<usize>() {
+                Ok(i) => cur.get(i)?,
+                Err(_) => cur.get(key.trim_matches(|c| c == '\'' || c == '"'))?,
+            };
+            rest = &r[end + 1..];
+        } else {
+            return None;
+        }
+    }
+    Some(cur)
+}
+
+// JSON 值转显示文本: 字符串去引号，null 显示 "--"，对象/数组原样紧凑输出
+fn value_text(v: &Value) -> String {
+    match v {
+        Value::String(s) => s.clone(),
+        Value::Null => "--".to_string(),
+        other => other.to_string(),
+    }
+}
+
+// ==========================================
+// 🧩 按提取规则与模板生成显示文本 (None = 提取失败)
+// ==========================================
+// re 为 src.regex 预先编译好的结果 (HttpFeed::new 时编译一次)
+pub fn render(src: &HttpSource, re: Option<&Regex>, body: &str, default_len: usize) -> Option<String> {
+    let body = body.trim();
+
+    let caps: Option<Vec<String>> = match re {
+        Some(re) => {
+            let c = re.captures(body)?;
+            Some(c.iter().map(|m| m.map_or(String::new(), |m| m.as_str().to_string())).collect())
+        }
+        None => None,
+    };
+
+    let default_tpl = match &caps {
+        Some(c) if c.len() > 1 => "{1}",
+        Some(_) => "{0}",
+        None => "{}",
+    };
+    let tpl = src.format.as_deref().filter(|f| !f.is_empty()).unwrap_or(default_tpl);
+
+    // 模板里用到 JSON 占位符时才解析响应体
+    let json: Option<Value> = if tpl.contains("{$") || tpl.contains("{/") {
+        Some(serde_json::from_str(body).ok()?)
+    } else {
+        None
+    };
+
+    let mut out = String::new();
+    let mut rest = tpl;
+    while let Some(open) = rest.find('{') {
+        out.push_str(&rest[..open]);
+        let after = &rest[open + 1..];
+        let Some(close) = after.find('}') else {
+            out.push_str(&rest[open..]);
+            rest = "";
+            break;
+        };
+        let key = after[..close].trim();
+        if key.is_empty() {
+            out.push_str(body);
+        } else if key.starts_with('$') || key.starts_with('/') {
+            out.push_str(&value_text(json_path(json.as_ref()?, key)?));
+        } else if let Ok(i) = key.parse::<usize>() {
+            out.push_str(caps.as_ref()?.get(i)?);
+        } else {
+            // 不认识的占位符原样保留
+            out.push_str(&rest[open..open + close + 2]);
+        }
+        rest = &after[close + 1..];
+    }
+    out.push_str(rest);
+
+    // 按字符截断 (防止切断中文)
+    Some(out.chars().take(src.max_len.unwrap_or(default_len)).collect())
+}
+
+// ==========================================
+// 🔄 单个数据源的刷新状态 (由 net_agent 持有)
+// ==========================================
+pub struct HttpFeed {
+    pub src: HttpSource,
+    pub text: Option<String>, // 最近一次成功的结果 (请求失败时继续显示旧值)
+    next_due: Option<Instant>,
+    re: Option<Regex>,
+    bad_regex: bool, // 正则写错: 启动时报一次，之后不再请求，一直显示 HTTP:Err
+}
+
+impl HttpFeed {
+    pub fn new(src: HttpSource) -> Self {
+        let (re, bad_regex) = match src.regex.as_deref().filter(|p| !p.is_empty()).map(Regex::new) {
+            Some(Ok(re)) => (Some(re), false),
+            Some(Err(e)) => {
+                println!("⚠️ [HTTP 源] {} 的正则无效，已停用 ({})", src.name, e);
+                (None, true)
+            }
+            None => (None, false),
+        };
+        let next_due = bad_regex.then(Instant::now);
+        Self { src, text: None, next_due, re, bad_regex }
+    }
+
+    pub fn is_due(&self) -> bool {
+        !self.bad_regex && self.next_due.is_none_or(|t| Instant::now() >= t)
+    }
+
+    pub async fn refresh(&mut self, http: &Http, default_interval: u64, default_len: usize) {
+        let result = fetch(http, &self.src)
+            .await
+            .and_then(|body| render(&self.src, self.re.as_ref(), &body, default_len));
+        let wait = match result {
+            Some(text) => {
+                self.text = Some(text);
+                self.src.interval.unwrap_or(default_interval).max(MIN_INTERVAL)
+            }
+            None => {
+                println!("⚠️ [HTTP 源] {} 获取或提取失败", self.src.name);
+                RETRY_SECS
+            }
+        };
+        self.next_due = Some(Instant::now() + Duration::from_secs(wait));
+    }
+
+    // 屏幕显示: 还没成功过时显示 Wait / Err
+    pub fn display(&self) -> String {
+        match (&self.text, self.next_due) {
+            (Some(t), _) => t.clone(),
+            (None, None) => "HTTP:Wait".to_string(),
+            (None, Some(_)) => "HTTP:Err".to_string(),
+        }
+    }
+}
+
+async fn fetch(http: &Http, src: &HttpSource) -> Option<String> {
+    // 超时 / 限流 / 去重由 Http 统一处理 (--http-timeout http=秒，默认 10 秒)
+    let mut req = http.request("http", &src.url);
+    for h in &src.headers {
+        if let Some((k, v)) = h.split_once(':') {
+            req = req.header(k.trim(), v.trim());
+        }
+    }
+    let resp = http.send("http", req).await.ok()?;
+    if !resp.is_success() {
+        return None;
+    }
+    Some(resp.text().to_string())
+}
+
+// ==========================================
+// 🧪 单元测试
+// ==========================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn src(regex: Option<&str>, format: Option<&str>) -> HttpSource {
+        HttpSource {
+            name: "t".into(),
+            url: "http://x".into(),
+            interval: None,
+            headers: vec![],
+            regex: regex.map(str::to_string),
+            format: format.map(str::to_string),
+            max_len: None,
+        }
+    }
+
+    // 与 HttpFeed 相同: 先编译正则再渲染
+    fn render_with(src: &HttpSource, body: &str, len: usize) -> Option<String> {
+        let feed = HttpFeed::new(src.clone());
+        render(src, feed.re.as_ref(), body, len)
+    }
+
+    const BODY: &str = r#"{"code":0,"data":{"count":1234,"list":[{"title":"hi"}],"ok":true,"x":null}}"#;
+
+    #[test]
+    fn json_paths() {
+        let v: Value = serde_json::from_str(BODY).unwrap();
+        assert_eq!(json_path(&v, "$.data.count"), Some(&Value::from(1234)));
+        assert_eq!(json_path(&v, "$.data.list[0].title"), Some(&Value::from("hi")));
+        assert_eq!(json_path(&v, "$['data']['ok']"), Some(&Value::from(true)));
+        assert_eq!(json_path(&v, "/data/list/0/title"), Some(&Value::from("hi")));
+        assert_eq!(json_path(&v, "$.data.missing"), None);
+        assert_eq!(json_path(&v, "$.data.list[5]"), None);
+        assert_eq!(json_path(&v, "data.count"), None);
+    }
+
+    #[test]
+    fn templates() {
+        assert_eq!(render_with(&src(None, Some("{$.data.count} subs")), BODY, 15).as_deref(), Some("1234 subs"));
+        assert_eq!(render_with(&src(None, Some("{/data/x}|{$.data.ok}")), BODY, 15).as_deref(), Some("--|true"));
+        // 路径不存在 = 提取失败
+        assert_eq!(render_with(&src(None, Some("{$.nope}")), BODY, 15), None);
+        // 不是 JSON 却用了 JSON 占位符
+        assert_eq!(render_with(&src(None, Some("{$.a}")), "plain", 15), None);
+        // 无模板: 整个响应体，按字符截断
+        assert_eq!(render_with(&src(None, None), "  你好世界 \n", 3).as_deref(), Some("你好世"));
+        // 不认识的占位符与不闭合的括号原样保留
+        assert_eq!(render_with(&src(None, Some("{x} {")), "b", 15).as_deref(), Some("{x} {"));
+    }
+
+    #[test]
+    fn regex_extract() {
+        let body = "<span id=\"temp\">23.5</span>";
+        assert_eq!(render_with(&src(Some(r#"temp">([\d.]+)<"#), None), body, 15).as_deref(), Some("23.5"));
+        assert_eq!(render_with(&src(Some(r#"([\d.]+)"#), Some("T:{1}C")), body, 15).as_deref(), Some("T:23.5C"));
+        assert_eq!(render_with(&src(Some(r"\d+"), None), body, 15).as_deref(), Some("23"));
+        assert_eq!(render_with(&src(Some("nomatch"), None), body, 15), None);
+        // 正则写错: 建 feed 时就停用，不再请求
+        let bad = HttpFeed::new(src(Some("(bad"), None));
+        assert!(!bad.is_due());
+        assert_eq!(bad.display(), "HTTP:Err");
+    }
+
+    #[test]
+    fn source_specs() {
+        let specs = vec![
+            r#"{"name":"a","url":"http://x","interval":300,"headers":["X-Key: 1"],"format":"{}"}"#.to_string(),
+            r#"{"name":"","url":"http://x"}"#.to_string(),
+            "not json".to_string(),
+        ];
+        let srcs = parse_sources(&specs);
+        assert_eq!(srcs.len(), 1);
+        assert_eq!(srcs[0].interval, Some(300));
+        assert_eq!(srcs[0].headers, vec!["X-Key: 1"]);
+
+        let feed = HttpFeed::new(srcs[0].clone());
+        assert!(feed.is_due());
+        assert_eq!(feed.display(), "HTTP:Wait");
+    }
+
+    // 本地起一个一次性 HTTP 服务器，确认请求头带上了、模板提取生效
+    #[tokio::test]
+    async fn refresh_from_local_server() {
+        use std::io::{Read, Write};
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = std::thread::spawn(move || {
+            let (mut conn, _) = listener.accept().unwrap();
+            let mut buf = [0u8; 2048];
+            let n = conn.read(&mut buf).unwrap();
+            let req = String::from_utf8_lossy(&buf[..n]).to_lowercase();
+            let resp = format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", BODY.len(), BODY);
+            conn.write_all(resp.as_bytes()).unwrap();
+            req.contains("x-token: abc")
+        });
+
+        let mut s = src(None, Some("{$.data.count} subs"));
+        s.url = format!("http://127.0.0.1:{}/stat", port);
+        s.headers = vec!["X-Token: abc".into()];
+        let mut feed = HttpFeed::new(s);
+        feed.refresh(&crate::mock_http::http(), 60, 15).await;
+        assert_eq!(feed.display(), "1234 subs");
+        assert!(!feed.is_due());
+        assert!(server.join().unwrap());
+    }
+}
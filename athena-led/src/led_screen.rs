@@ -85,6 +85,22 @@ pub fn detect_gpio_base() -> u64 {
     }
 }
 
+// ==========================================
+// 🔤 文本 -> 列点阵 (字间空一列，去掉末尾多余空列)
+// 这样 28 列的 "10:10:10" 就会瞬间变成 27 列！
+// ==========================================
+pub fn glyph_columns(text: &str) -> Vec<u8> {
+    let mut display_data = Vec::new();
+    for ch in text.chars() {
+        if let Some(bytes) = CHAR_DICT.get(&ch.to_ascii_uppercase()) {
+            display_data.extend_from_slice(bytes);
+            display_data.push(0x00); // 加空格
+        }
+    }
+    display_data.pop();
+    display_data
+}
+
 // ==========================================
 // 🌟 [新] 查找主控芯片的字符设备路径 (/dev/gpiochipN)
 // 优先选 label 含 pinctrl/tlmm 的芯片，否则选线数最多的
@@ -294,24 +310,7 @@ impl LedScreen {
 
     // 2. 这里也要加上 async 关键字
     pub async fn write_data(&mut self, text: &[u8], status: u8) -> Result<()> {
-        let mut display_data = Vec::new();
-
-        let content = std::str::from_utf8(text).unwrap_or("");
-
-        for ch in content.chars() {
-            let key = ch.to_ascii_uppercase();
-
-            if let Some(bytes) = CHAR_DICT.get(&key) {
-                display_data.extend_from_slice(bytes);
-                display_data.push(0x00); // 加空格
-            }
-        }
-
-        // 修复：砍掉最后一个多余的尾部空格！
-        // 这样 28 列的 "10:10:10" 就会瞬间变成 27 列！
-        if !display_data.is_empty() {
-            display_data.pop();
-        }
+        let display_data = glyph_columns(std::str::from_utf8(text).unwrap_or(""));
 
         // 判断逻辑完全不需要改，保持 27 即可
         if display_data.len() > 27 {
@@ -376,25 +375,16 @@ impl LedScreen {
         Ok(())
     }
 
+    // 📈 直接推送一帧 27 列原始点阵 (列字节 bit0 = 最上一行)，供图表类模块自行绘制
+    pub fn write_frame(&mut self, frame: &[u8; 27], status: u8) -> Result<()> {
+        self.do_write_data(frame, status)
+    }
+
     // 🌟 专为动态模块（天气、时间）设计的“强制静态、完美居中、零浪费”特化方法
     // (当前调度器未使用，保留给外部/未来模块调用)
     #[allow(dead_code)]
     pub async fn write_data_static(&mut self, text: &[u8], status: u8) -> Result<()> {
-        let mut display_data = Vec::new();
-        let content = std::str::from_utf8(text).unwrap_or("");
-
-        for ch in content.chars() {
-            let key = ch.to_ascii_uppercase();
-            if let Some(bytes) = CHAR_DICT.get(&key) {
-                display_data.extend_from_slice(bytes);
-                display_data.push(0x00);
-            }
-        }
-
-        if !display_data.is_empty() {
-            display_data.pop();
-        }
-
+        let display_data = glyph_columns(std::str::from_utf8(text).unwrap_or(""));
         self.static_display(&display_data, status)?;
         Ok(())
     }
@@ -13,6 +13,16 @@ pub fn detect_gpio_base() -> u64 {
     512
 }
 
+// 模拟文本转点阵 (无字库，每个字符画成 3 列方框占位)
+pub fn glyph_columns(text: &str) -> Vec<u8> {
+    let mut cols = Vec::new();
+    for _ in text.chars() {
+        cols.extend_from_slice(&[0x1F, 0x11, 0x1F, 0x00]);
+    }
+    cols.pop();
+    cols
+}
+
 impl LedScreen {
     // 模拟屏幕初始化 (参数: gpio_backend, gpio_base)
     pub fn new(_: &str, _: &str) -> Result<Self> {
@@ -40,6 +50,16 @@ impl LedScreen {
         Ok(())
     }
 
+    // 模拟原始帧写入: 把 27 列点阵画成 5 行字符画
+    pub fn write_frame(&mut self, frame: &[u8; 27], flag: u8) -> Result<()> {
+        println!("📺 [屏幕帧 | 状态灯:{}]", flag);
+        for row in 0..5 {
+            let line: String = frame.iter().map(|col| if col & (1 << row) != 0 { '█' } else { '·' }).collect();
+            println!("    {}", line);
+        }
+        Ok(())
+    }
+
     pub async fn play_animation(&mut self, file_name: &str, duration_secs: u64, status: u8) -> Result<()> {
         println!("🎬 [虚拟屏幕 | 状态灯:{}] 开始模拟播放动画: {} (时长: {}秒)", status, file_name, duration_secs);
 
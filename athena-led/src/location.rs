@@ -0,0 +1,310 @@
+// ==========================================
+// 📍 location.rs — 位置子系统: 城市名 + 经纬度 (天气 auto 城市 / 日出日落 / loc 模块)
+// 以前 NetAgent::ensure_location 写死两个 IP 定位接口，按 "city" 字符串切割取值，
+// 失败就当成北京。现在:
+//   - 手动配置优先: --location-city / --latitude / --longitude，配了就不再联网定位
+//   - IP 定位接口可配置 (--api-base geoip=...，可重复，按顺序尝试)，响应按 JSON 解析，
+//     兼容常见字段名与包一层 data/result 的接口，以及 ipinfo 的 "loc":"lat,lon"
+//   - 定位结果写入磁盘 (--location-file)，重启直接读缓存；7 天后后台重新定位 (搬家/换运营商)
+//   - --geoip-family 4 强制走 IPv4 出口 (多数 IP 库对 IPv6 的城市级定位很差)，6 强制 IPv6
+//   - 定位失败如实显示，不再冒充北京
+// ==========================================
+use crate::http_client::Http;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::time::{Duration, Instant};
+
+const RETRY_SECS: u64 = 600; // 定位失败 10 分钟内不重试
+const REFRESH_DAYS: i64 = 7; // 缓存多久后重新定位
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Location {
+    #[serde(default)]
+    pub city: String,
+    #[serde(default)]
+    pub coords: Option<(f64, f64)>,
+    // 结果来自哪个接口 (主机名)
+    #[serde(default)]
+    pub source: String,
+    // 定位时间 (unix 秒)
+    #[serde(default)]
+    pub updated: i64,
+}
+
+// ==========================================
+// 🔍 IP 定位响应解析
+// ==========================================
+// 在对象本身及其一层子对象 (data / result / location ...) 里找第一个匹配的键
+fn find<'a>(v: &'a Value, keys: &[&str]) -> Option<&'a Value> {
+    let obj = v.as_object()?;
+    keys.iter()
+        .find_map(|k| obj.get(*k))
+        .or_else(|| obj.values().filter(|c| c.is_object()).find_map(|c| find_flat(c, keys)))
+}
+
+fn find_flat<'a>(v: &'a Value, keys: &[&str]) -> Option<&'a Value> {
+    let obj = v.as_object()?;
+    keys.iter().find_map(|k| obj.get(*k))
+}
+
+fn number(v: &Value) -> Option<f64> {
+    v.as_f64().or_else(|| v.as_str().and_then(|s| s.trim().parse().ok()))
+}
+
+fn valid(lat: f64, lon: f64) -> Option<(f64, f64)> {
+    ((-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&lon) && (lat, lon) != (0.0, 0.0))
+        .then_some((lat, lon))
+}
+
+// (城市名, 经纬度)
+pub fn parse_geoip(v: &Value) -> (Option<String>, Option<(f64, f64)>) {
+    let city = find(v, &["city", "cityName", "city_name"])
+        .and_then(Value::as_str)
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty());
+
+    let lat = find(v, &["lat", "latitude"]).and_then(number);
+    let lon = find(v, &["lon", "lng", "longitude"]).and_then(number);
+    let coords = match (lat, lon) {
+        (Some(lat), Some(lon)) => valid(lat, lon),
+        // ipinfo.io: "loc": "30.2936,120.1614"
+        _ => find(v, &["loc"])
+            .and_then(Value::as_str)
+            .and_then(|s| s.split_once(','))
+            .and_then(|(a, b)| valid(a.trim().parse().ok()?, b.trim().parse().ok()?)),
+    };
+    (city, coords)
+}
+
+// ==========================================
+// 🧭 定位器 (由 net_agent 持有)
+// ==========================================
+pub struct LocatorConfig {
+    pub city: String,
+    pub lat: Option<f64>,
+    pub lon: Option<f64>,
+    pub urls: Vec<String>, // 见 Endpoints::geoip
+    pub file: String, // 空 = 不落盘
+}
+
+pub struct Locator {
+    manual_city: Option<String>,
+    manual_coords: Option<(f64, f64)>,
+    urls: Vec<String>,
+    file: String,
+    found: Location,
+    last_attempt: Option<Instant>,
+}
+
+impl Locator {
+    pub fn new(cfg: LocatorConfig) -> Self {
+        let manual_city = Some(cfg.city.trim().to_string()).filter(|c| !c.is_empty() && c != "auto");
+        let manual_coords = match (cfg.lat, cfg.lon) {
+            (Some(lat), Some(lon)) => valid(lat, lon),
+            _ => None,
+        };
+
+        // 读磁盘缓存 (文件不存在/损坏时当作没有)
+        let found: Location = fs::read_to_string(&cfg.file)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default();
+        if !found.city.is_empty() || found.coords.is_some() {
+            println!("📍 [定位] 读取缓存: {} {:?} (来自 {})", found.city, found.coords, found.source);
+        }
+
+        Self { manual_city, manual_coords, urls: cfg.urls, file: cfg.file, found, last_attempt: None }
+    }
+
+    pub fn city(&self) -> Option<String> {
+        self.manual_city.clone().or_else(|| Some(self.found.city.clone()).filter(|c| !c.is_empty()))
+    }
+
+    pub fn coords(&self) -> Option<(f64, f64)> {
+        self.manual_coords.or(self.found.coords)
+    }
+
+    pub fn location(&self) -> Location {
+        Location { city: self.city().unwrap_or_default(), coords: self.coords(), ..self.found.clone() }
+    }
+
+    fn is_stale(&self) -> bool {
+        chrono::Utc::now().timestamp() - self.found.updated > REFRESH_DAYS * 86400
+    }
+
+    // 缺城市或坐标 (或缓存过期) 时按顺序查询 IP 定位接口；手动配置齐全时永不联网
+    // 走 Http 的定位专用客户端 (--geoip-family 在那里生效)
+    pub async fn ensure(&mut self, http: &Http) {
+        let complete = self.city().is_some() && self.coords().is_some();
+        let manual = self.manual_city.is_some() && self.manual_coords.is_some();
+        if manual || (complete && !self.is_stale()) {
+            return;
+        }
+        if self.last_attempt.is_some_and(|t| t.elapsed() < Duration::from_secs(RETRY_SECS)) {
+            return;
+        }
+        self.last_attempt = Some(Instant::now());
+
+        let mut found = Location::default();
+        for url in &self.urls {
+            let json = match http.get("geoip", url).await {
+                Ok(resp) if resp.is_success() => resp.json::<Value>().ok(),
+                Ok(resp) => {
+                    println!("❌ [定位失败] {} 返回 {}", url, resp.status);
+                    None
+                }
+                Err(e) => {
+                    println!("❌ [定位失败] {} 请求报错: {}", url, e);
+                    None
+                }
+            };
+            let Some(json) = json else { continue };
+            let (city, coords) = parse_geoip(&json);
+            #[cfg(debug_assertions)]
+            println!("🌍 [定位调试] {} -> {:?} {:?}", url, city, coords);
+
+            if found.city.is_empty() {
+                if let Some(c) = city {
+                    found.city = c;
+                    found.source = host_of(url);
+                }
+            }
+            if found.coords.is_none() && coords.is_some() {
+                found.coords = coords;
+                if found.source.is_empty() {
+                    found.source = host_of(url);
+                }
+            }
+            // 城市和坐标都齐了就提前收工
+            if !found.city.is_empty() && found.coords.is_some() {
+                break;
+            }
+        }
+
+        if found.city.is_empty() && found.coords.is_none() {
+            return;
+        }
+        // 这次没拿到的部分沿用旧缓存
+        if found.city.is_empty() {
+            found.city = self.found.city.clone();
+        }
+        if found.coords.is_none() {
+            found.coords = self.found.coords;
+        }
+        found.updated = chrono::Utc::now().timestamp();
+        println!("✅ [定位] {} {:?} (来自 {})", found.city, found.coords, found.source);
+        self.found = found;
+        self.save();
+    }
+
+    // 先写临时文件再 rename，断电不留半截 JSON
+    fn save(&self) {
+        if self.file.is_empty() {
+            return;
+        }
+        if let Some(dir) = std::path::Path::new(&self.file).parent() {
+            let _ = fs::create_dir_all(dir);
+        }
+        let tmp = format!("{}.tmp", self.file);
+        let ok = serde_json::to_string(&self.found)
+            .ok()
+            .and_then(|json| fs::write(&tmp, json).ok())
+            .and_then(|_| fs::rename(&tmp, &self.file).ok())
+            .is_some();
+        if !ok {
+            println!("⚠️ [定位] 缓存写入失败: {}", self.file);
+        }
+    }
+}
+
+fn host_of(url: &str) -> String {
+    url.split("://").nth(1).unwrap_or(url).split(['/', '?']).next().unwrap_or("").to_string()
+}
+
+// ==========================================
+// 🖥️ loc 模块显示: 城市名 (LED 字库没有汉字，中文城市名退回显示经纬度)
+// 参数: "" 城市 / "coords" 经纬度
+// ==========================================
+pub fn display(loc: &Location, param: &str) -> String {
+    let coords = loc.coords.map(|(lat, lon)| format!("{:.2},{:.2}", lat, lon));
+    let city = Some(loc.city.clone()).filter(|c| !c.is_empty() && c.is_ascii()).map(|c| c.to_uppercase());
+    let text = match param.trim() {
+        "coords" => coords,
+        _ => city.or(coords),
+    };
+    text.unwrap_or_else(|| "LOC:--".to_string())
+}
+
+// ==========================================
+// 🧪 单元测试
+// ==========================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_http::{fixtures, http, MockServer, Reply};
+    use serde_json::json;
+
+    fn cfg(urls: Vec<String>, file: &str) -> LocatorConfig {
+        LocatorConfig { city: "auto".into(), lat: None, lon: None, urls, file: file.into() }
+    }
+
+    #[test]
+    fn geoip_payload_shapes() {
+        let v = json!({"status": "success", "city": "杭州市", "lat": 30.29, "lon": 120.16});
+        assert_eq!(parse_geoip(&v), (Some("杭州市".into()), Some((30.29, 120.16))));
+        let v = json!({"code": 200, "data": {"cityName": "Shanghai", "latitude": "31.2", "longitude": "121.5"}});
+        assert_eq!(parse_geoip(&v), (Some("Shanghai".into()), Some((31.2, 121.5))));
+        let v = json!({"ip": "1.2.3.4", "city": "Mountain View", "loc": "37.3860,-122.0838"});
+        assert_eq!(parse_geoip(&v).1, Some((37.386, -122.0838)));
+        // 空城市 / 0,0 坐标 (接口查不到时常见) 视为没有
+        assert_eq!(parse_geoip(&json!({"city": " ", "lat": 0, "lon": 0})), (None, None));
+        assert_eq!(host_of("http://ip-api.com/json/?x=1"), "ip-api.com");
+    }
+
+    #[test]
+    fn loc_display() {
+        let mut l = Location { city: "Hangzhou".into(), coords: Some((30.294, 120.166)), ..Default::default() };
+        assert_eq!(display(&l, ""), "HANGZHOU");
+        assert_eq!(display(&l, "coords"), "30.29,120.17");
+        l.city = "杭州市".into();
+        assert_eq!(display(&l, ""), "30.29,120.17");
+        assert_eq!(display(&Location::default(), ""), "LOC:--");
+    }
+
+    #[tokio::test]
+    async fn manual_config_wins_and_skips_network() {
+        let mut c = cfg(vec!["http://127.0.0.1:9/never".into()], "");
+        c.city = "Suzhou".into();
+        c.lat = Some(31.3);
+        c.lon = Some(120.6);
+        let mut l = Locator::new(c);
+        l.ensure(&http()).await;
+        assert_eq!((l.city(), l.coords()), (Some("Suzhou".into()), Some((31.3, 120.6))));
+        assert!(l.last_attempt.is_none());
+    }
+
+    #[tokio::test]
+    async fn lookup_persists_and_reloads() {
+        let m = MockServer::start().await;
+        m.route("/broken", Reply::Status(500));
+        m.route("/geoip", Reply::Body(fixtures::GEOIP));
+        let file = std::env::temp_dir().join(format!("athena-loc-{}.json", std::process::id()));
+        let file = file.to_string_lossy().to_string();
+        let _ = fs::remove_file(&file);
+        let urls = vec![format!("{}/broken", m.base), format!("{}/geoip", m.base)];
+
+        let mut l = Locator::new(cfg(urls.clone(), &file));
+        assert_eq!(l.city(), None); // 不再兜底北京
+        l.ensure(&http()).await;
+        assert_eq!((l.city(), l.coords()), (Some("杭州市".into()), Some((30.2943, 120.1663))));
+        assert_eq!((m.hits("/broken"), m.hits("/geoip")), (1, 1));
+
+        // 重启: 读缓存，不再联网
+        let mut again = Locator::new(cfg(urls, &file));
+        again.ensure(&http()).await;
+        assert_eq!(again.location().city, "杭州市");
+        assert_eq!(m.hits("/geoip"), 1);
+        let _ = fs::remove_file(&file);
+    }
+}
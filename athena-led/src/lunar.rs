@@ -124,6 +124,76 @@ pub fn to_lunar(date: NaiveDate) -> Option<(i32, u32, u32, bool)> {
     Some((year, month, (offset + 1) as u32, is_leap))
 }
 
+/// 农历 -> 公历 (to_lunar 的逆运算)。
+/// is_leap = true 表示该年的闰 month 月；月份/日期不存在 (如该年无此闰月、
+/// 小月的三十) 或超出 1900~2100 范围时返回 None
+pub fn from_lunar(year: i32, month: u32, day: u32, is_leap: bool) -> Option<NaiveDate> {
+    if !(1900..=2100).contains(&year) || !(1..=12).contains(&month) || day == 0 {
+        return None;
+    }
+    if is_leap && leap_month(year) != month {
+        return None;
+    }
+    let max_day = if is_leap { leap_days(year) } else { month_days(year, month) };
+    if day > max_day {
+        return None;
+    }
+
+    // 1. 累加之前各年的天数
+    let mut offset: i64 = (1900..year).map(|y| year_days(y) as i64).sum();
+
+    // 2. 累加本年之前各月 (闰月紧跟在其名义月之后)
+    let leap = leap_month(year);
+    for m in 1..month {
+        offset += month_days(year, m) as i64;
+        if m == leap {
+            offset += leap_days(year) as i64;
+        }
+    }
+    // 闰月排在同名普通月之后
+    if is_leap {
+        offset += month_days(year, month) as i64;
+    }
+
+    offset += (day - 1) as i64;
+    let base = NaiveDate::from_ymd_opt(1900, 1, 31)?;
+    Some(base + chrono::Duration::days(offset))
+}
+
+/// 某农历月份在该年的实际天数 (供倒数日把"三十"钳制到小月的廿九)。
+/// 闰月不存在时按普通月计算
+pub fn days_in_month(year: i32, month: u32, is_leap: bool) -> u32 {
+    if !(1900..=2100).contains(&year) || !(1..=12).contains(&month) {
+        return 0;
+    }
+    if is_leap && leap_month(year) == month {
+        leap_days(year)
+    } else {
+        month_days(year, month)
+    }
+}
+
+/// 农历日期 (月, 日, 是否闰月) 在 today 当天或之后的下一次公历日期。
+/// 闰月生日在没有该闰月的年份按普通月过 (民间习俗)；
+/// "三十" 遇小月按当月最后一天 (廿九) 过
+pub fn next_occurrence(today: NaiveDate, month: u32, day: u32, is_leap: bool) -> Option<NaiveDate> {
+    if !(1..=12).contains(&month) || !(1..=30).contains(&day) {
+        return None;
+    }
+    let (this_year, _, _, _) = to_lunar(today)?;
+    // 今年 (农历) 已过则顺延，最多看 3 年足够覆盖
+    for year in this_year..=(this_year + 2).min(2100) {
+        let leap = is_leap && leap_month(year) == month;
+        let day = day.min(days_in_month(year, month, leap));
+        if let Some(date) = from_lunar(year, month, day, leap) {
+            if date >= today {
+                return Some(date);
+            }
+        }
+    }
+    None
+}
+
 /// 屏显格式: "L:5.7" (五月初七) / "L:R6.15" (闰六月十五)
 pub fn lunar_string(date: NaiveDate) -> String {
     match to_lunar(date) {
@@ -201,4 +271,60 @@ mod tests {
         // 2025 闰六月期间的某天应带 R 前缀 (闰六月初一 = 2025-07-25)
         assert_eq!(lunar_string(d(2025, 7, 25)), "L:R6.1");
     }
+
+    #[test]
+    fn from_lunar_roundtrip() {
+        // 春节 / 中秋锚点反查
+        assert_eq!(from_lunar(2024, 1, 1, false), Some(d(2024, 2, 10)));
+        assert_eq!(from_lunar(2026, 1, 1, false), Some(d(2026, 2, 17)));
+        assert_eq!(from_lunar(2024, 8, 15, false), Some(d(2024, 9, 17)));
+        assert_eq!(from_lunar(2025, 8, 15, false), Some(d(2025, 10, 6)));
+        assert_eq!(from_lunar(1900, 1, 1, false), Some(d(1900, 1, 31)));
+        // 整段往返: 1990~2040 每隔 7 天抽查一次
+        let mut date = d(1990, 1, 1);
+        while date < d(2040, 1, 1) {
+            let (y, m, day, leap) = to_lunar(date).unwrap();
+            assert_eq!(from_lunar(y, m, day, leap), Some(date), "{}", date);
+            date += chrono::Duration::days(7);
+        }
+    }
+
+    #[test]
+    fn from_lunar_leap_months() {
+        // 2025 闰六月初一 = 2025-07-25; 普通六月初一 = 2025-06-25
+        assert_eq!(from_lunar(2025, 6, 1, true), Some(d(2025, 7, 25)));
+        assert_eq!(from_lunar(2025, 6, 1, false), Some(d(2025, 6, 25)));
+        // 2023 闰二月初一 = 2023-03-22; 闰月之后的三月初一 = 2023-04-20
+        assert_eq!(from_lunar(2023, 2, 1, true), Some(d(2023, 3, 22)));
+        assert_eq!(from_lunar(2023, 3, 1, false), Some(d(2023, 4, 20)));
+        // 2020 闰四月初一 = 2020-05-23
+        assert_eq!(from_lunar(2020, 4, 1, true), Some(d(2020, 5, 23)));
+        // 不存在的闰月 / 日期
+        assert_eq!(from_lunar(2024, 6, 1, true), None);
+        assert_eq!(from_lunar(2024, 13, 1, false), None);
+        assert_eq!(from_lunar(2024, 1, 31, false), None);
+    }
+
+    #[test]
+    fn next_occurrence_rolls_over() {
+        // 2026-07-07 = 农历五月廿三: 中秋还没到
+        assert_eq!(next_occurrence(d(2026, 7, 7), 8, 15, false), Some(d(2026, 9, 25)));
+        // 春节已过: 顺延到 2027 春节 (2027-02-06)
+        assert_eq!(next_occurrence(d(2026, 7, 7), 1, 1, false), Some(d(2027, 2, 6)));
+        // 当天即返回当天
+        assert_eq!(next_occurrence(d(2024, 9, 17), 8, 15, false), Some(d(2024, 9, 17)));
+        // 公历新年前后: 2025-01-10 仍属农历 2024 年腊月，春节在 2025-01-29
+        assert_eq!(next_occurrence(d(2025, 1, 10), 1, 1, false), Some(d(2025, 1, 29)));
+    }
+
+    #[test]
+    fn next_occurrence_leap_fallback() {
+        // 闰六月生日: 2025 有闰六月 -> 用闰月
+        assert_eq!(next_occurrence(d(2025, 7, 1), 6, 1, true), Some(d(2025, 7, 25)));
+        // 2026 无闰六月 -> 按普通六月初一 (2026-07-14) 过
+        assert_eq!(next_occurrence(d(2026, 1, 1), 6, 1, true), from_lunar(2026, 6, 1, false));
+        // 非法输入
+        assert_eq!(next_occurrence(d(2026, 1, 1), 13, 1, false), None);
+        assert_eq!(next_occurrence(d(2026, 1, 1), 1, 31, false), None);
+    }
 }
@@ -5,12 +5,32 @@
 //   led_screen_sim.rs — Windows 本地调试用虚拟屏幕
 //   char_dict.rs      — 点阵字模字典
 //   monitor.rs        — 本地系统数据采集 (CPU/内存/温度/网速...)
+//   clients.rs        — 局域网终端流量排行 (conntrack 计数 + DHCP 主机名)
+//   wifi.rs           — 无线射频状态 (终端数/信道繁忙度/信号强弱, 基于 iw)
+//   quota.rs          — 月度流量配额记账 (增量累加, 低频落盘)
+//   history.rs        — 指标历史环形缓冲与迷你折线图 (spark)
+//   gauge.rs          — 百分比条形仪表 / 每核 CPU 柱 (gauge)
+//   disk.rs           — 存储空间 (statvfs) 与空间不足告警
+//   wan.rs            — WAN 会话信息 (ubus / ifstatus JSON)
+//   poller.rs         — 后台定时采集 (spawn_blocking + 超时, 渲染路径只读快照)
+//   ping.rs           — ICMP/TCP 延迟探测与丢包/抖动统计
+//   dns.rs            — DNS 解析健康探测 (本机 dnsmasq vs 上游) 与故障告警
+//   http_client.rs    — 出站 HTTP 客户端统一构建 (代理/CA/出口绑定/超时)
+//   http_source.rs    — 具名 HTTP 数据源 (JSONPath/正则提取 + 显示模板)
+//   ticker.rs         — 多品种行情 (字段映射 / 当日涨跌幅 / 涨跌灯)
+//   rss.rs            — RSS / Atom 标题滚动 (去重 / 优先播新标题)
+//   agenda.rs         — iCalendar 日程 (重复规则 / VTIMEZONE 时区) 与会前提醒
+//   weather.rs        — 天气数据模型: 现象/图标映射、明日预报 / 空气质量 / 降雨告警
+//   weather_provider.rs — 天气数据源抽象 (uapis/wttr/心知/Open-Meteo) 与按序故障切换
+//   location.rs       — 位置子系统 (手动城市/经纬度 > 磁盘缓存 > IP 定位)
 //   net_agent.rs      — 后台网络数据代理 (天气/IP/股票/HTTP/延迟/日出日落)
 //   scheduler.rs      — 轮播调度引擎 (Profile 解析/渲染/休眠/插播)
 //   button.rs         — 物理按键监听 (长按/短按/双击)
 //   control.rs        — 运行时控制接口 (127.0.0.1 TCP)
 //   mqtt.rs           — MQTT 订阅上屏 (HA 集成)
 //   lunar.rs / sun.rs — 农历 / 日出日落 (纯本地计算)
+//   timesync.rs       — 系统时间校准状态 (NTP 未同步时暗冒号 / 日期模块暂停)
+//   tz.rs             — 时区 (POSIX TZ 规则解析 / zonename 查表 / 夏令时换算)
 // ==========================================
 #[cfg(unix)]
 mod led_screen;
@@ -20,14 +40,36 @@ mod led_screen;
 #[cfg(unix)]
 mod char_dict;
 
+mod agenda;
 mod button;
+mod clients;
 mod control;
+mod disk;
+mod dns;
+mod gauge;
+mod history;
+mod http_client;
+mod http_source;
+mod location;
 mod lunar;
+#[cfg(test)]
+mod mock_http;
 mod monitor;
 mod mqtt;
 mod net_agent;
+mod ping;
+mod poller;
+mod quota;
+mod rss;
 mod scheduler;
 mod sun;
+mod ticker;
+mod timesync;
+mod tz;
+mod wan;
+mod weather;
+mod weather_provider;
+mod wifi;
 
 use anyhow::{Context, Result};
 use clap::Parser;
@@ -112,6 +154,12 @@ pub struct Args {
     #[arg(long, default_value_t = 15)]
     pub http_length: usize,
 
+    // 🔗 [v2.6.0] 具名 HTTP 数据源 (可重复)，每个是一个 JSON 对象，profile 里用 "http:<name>" 引用
+    // 例: {"name":"subs","url":"https://...","interval":300,"format":"{$.data.count} subs"}
+    // 字段说明见 http_source.rs
+    #[arg(long)]
+    pub http_source: Vec<String>,
+
     #[arg(long, default_value = "auto")]
     pub weather_city: String,
 
@@ -122,10 +170,47 @@ pub struct Args {
     #[arg(long, default_value = "")]
     pub seniverse_key: String,
 
-    // 5. 股票接口 (预留，建议用返回简单文本的 API)
+    // 🔗 [v2.6.0] 覆盖天气/定位接口的基础地址 (可重复): "名字=地址"
+    // 名字: uapis / wttr / seniverse / openmeteo / openmeteo_geo / openmeteo_air / geoip (完整 URL，可多个)
+    #[arg(long)]
+    pub api_base: Vec<String>,
+
+    // 🌐 [v2.6.0] 出站请求: 代理 / 额外 CA / 出口网卡或源 IP / 各数据源超时 ("名字=秒"，可重复)
+    #[arg(long, default_value = "")]
+    pub http_proxy: String,
+
+    #[arg(long, default_value = "")]
+    pub http_ca_file: String,
+
+    #[arg(long, default_value = "")]
+    pub http_bind: String,
+
+    #[arg(long)]
+    pub http_timeout: Vec<String>,
+
+    // 5. 股票接口 (旧版单一品种，返回含 price/last/close 字段的 JSON)
     #[arg(long, default_value = "")]
     pub stock_url: String,
 
+    // 📈 [v2.6.0] 多品种行情 (可重复)，每个是一个 JSON 对象，字段说明见 ticker.rs
+    // 例: {"symbol":"BTC","url":"https://...","price":"$.last","prev_close":"$.prevClose"}
+    #[arg(long)]
+    pub ticker: Vec<String>,
+
+    // 📰 [v2.6.0] RSS / Atom 标题源 (可重复)，每个是一个 JSON 对象，profile 里用 "rss:<name>" 引用
+    // 例: {"name":"hn","url":"https://hnrss.org/frontpage","interval":600,"count":5}
+    // 字段说明见 rss.rs
+    #[arg(long)]
+    pub rss_feed: Vec<String>,
+
+    // 📅 [v2.6.0] iCalendar 日程源 (可重复): http(s) 地址或本地 .ics 文件，多个日历合并显示
+    #[arg(long)]
+    pub ics: Vec<String>,
+
+    // 日程刷新间隔 (秒，最少 60)
+    #[arg(long, default_value_t = 900)]
+    pub ics_interval: u64,
+
     #[arg(long, default_value = "4")]
     pub temp_flag: String, // 用于温度显示
 
@@ -136,9 +221,36 @@ pub struct Args {
     #[arg(long, default_value = "")]
     pub sleep_end: String,
 
+    // 🕒 [v2.6.0] 校时检查: auto (年份 + 内核 NTP 状态/校时跳变) / year (只看年份) / off
+    #[arg(long, default_value = "auto")]
+    pub time_check: String,
+
     #[arg(long, default_value = "simple")]
     pub weather_format: String,
 
+    // 🌡️ [v2.6.0] 天气显示单位: 温度 c / f，风速 kmh / ms / mph
+    #[arg(long, default_value = "c")]
+    pub weather_unit: String,
+
+    #[arg(long, default_value = "kmh")]
+    pub wind_unit: String,
+
+    // 📍 [v2.6.0] 位置: 手动城市/经纬度优先 (留空 = IP 自动定位)，定位结果缓存到磁盘
+    #[arg(long, default_value = "")]
+    pub location_city: String,
+
+    #[arg(long, allow_negative_numbers = true)]
+    pub latitude: Option<f64>,
+
+    #[arg(long, allow_negative_numbers = true)]
+    pub longitude: Option<f64>,
+
+    #[arg(long, default_value = "auto")]
+    pub geoip_family: String, // IP 定位走哪个协议族: auto / 4 / 6
+
+    #[arg(long, default_value = "/etc/athena_led/location.json")]
+    pub location_file: String,
+
     // [新增] 4 盏全局状态指示灯的独立开关
     #[arg(long)]
     pub disable_led_clock: bool, // 禁用时钟灯 (CPU)
@@ -173,6 +285,29 @@ pub struct Args {
     #[arg(long)]
     pub alert_ip: bool,     // 公网 IP 变化提醒
 
+    // 🔎 [v2.6.0] DNS 健康探测: 同一域名分别问本机 dnsmasq 与上游解析器 (dns 模块)
+    #[arg(long, default_value = "www.baidu.com")]
+    pub dns_name: String,
+
+    #[arg(long, default_value = "223.5.5.5")]
+    pub dns_upstream: String, // ip / ip:port / 主机名
+
+    #[arg(long)]
+    pub alert_dns: bool, // 默认路由在但本机解析连续失败时告警 (恢复时提示 DNS OK)
+
+    // ☔ [v2.6.0] 降雨提醒: 未来一小时降水概率 (%) 达到阈值时插播一次，0 = 关闭
+    #[arg(long, default_value_t = 0)]
+    pub alert_rain: u8,
+
+    // 📅 [v2.6.0] 日程提醒: 事件开始前 N 分钟插播一次 (需配置 --ics)，0 = 关闭
+    #[arg(long, default_value_t = 0)]
+    pub alert_agenda: u32,
+
+    // 💽 [v2.6.0] 空间不足告警: "挂载点=阈值"，逗号分隔，阈值为剩余百分比或字节数
+    // 例: "/overlay=10%,/mnt/sda1=2G"，空 = 关闭
+    #[arg(long, default_value = "")]
+    pub disk_alert: String,
+
     // 运行时控制接口端口 (监听 127.0.0.1，0 = 关闭)
     // 用法: echo "show 10 HELLO" | nc 127.0.0.1 <端口>
     #[arg(long, default_value_t = 0)]
@@ -190,28 +325,27 @@ pub struct Args {
 
     #[arg(long, default_value = "")]
     pub mqtt_pass: String,
-}
 
-// ==========================================
-// [终极时区修复] 直接读取 OpenWrt 底层配置，免装 zoneinfo 包
-// ==========================================
-fn set_timezone_from_config() -> Result<()> {
-    if let Ok(content) = std::fs::read_to_string("/etc/config/system") {
-        for line in content.lines() {
-            // 匹配 option timezone 'CST-8' 这种格式
-            if line.contains("option timezone") {
-                let parts: Vec<&str> = line.split('\'').collect();
-                if parts.len() >= 3 {
-                    let tz_str = parts[1];
-                    std::env::set_var("TZ", tz_str);
-                    return Ok(());
-                }
-            }
-        }
-    }
-    // 如果没读到，默认兜底使用北京时间
-    std::env::set_var("TZ", "CST-8");
-    Ok(())
+    // ==========================================
+    // 📦 [v2.6.0] 流量配额记账 (接口为空 = 关闭)。配合 "quota" 显示模块使用
+    // ==========================================
+    #[arg(long, default_value = "")]
+    pub quota_interface: String, // 记账网卡，如 "wan" / "pppoe-wan"
+
+    #[arg(long, default_value_t = 0)]
+    pub quota_gb: u64, // 每月配额 (GB)，0 = 不限额只记账
+
+    #[arg(long, default_value_t = 1)]
+    pub quota_billing_day: u32, // 结算日 (1-31)
+
+    #[arg(long, default_value_t = 80)]
+    pub quota_alert: u32, // 用量达到百分之多少时插播告警，0 = 关闭
+
+    #[arg(long, default_value = "/etc/athena_led/quota.json")]
+    pub quota_file: String,
+
+    #[arg(long, default_value_t = 30)]
+    pub quota_save_mins: u64, // 落盘间隔 (分钟)，照顾闪存寿命
 }
 
 #[tokio::main]
@@ -224,9 +358,13 @@ async fn main() -> Result<()> {
         println!("📝 [系统] 进程 PID ({}) 已写入 /var/run/athena-led.pid", pid);
     }
 
-    let _ = set_timezone_from_config();
+    // 🌐 [v2.6.0] 时区: 解析 /etc/config/system 的 timezone / zonename (POSIX TZ 规则，含夏令时)
+    tz::init_from_system();
     let args = Args::parse();
 
+    // 🕒 [v2.6.0] 断网重启后时间可能是错的: 未校准前时间模块显示暗冒号，日期相关模块暂停计算
+    timesync::spawn_watch(timesync::CheckMode::parse(&args.time_check));
+
     // ==========================================
     // 🌟 优雅退出的核心开关（有且只能有这一组！）
     // ==========================================
@@ -246,6 +384,7 @@ async fn main() -> Result<()> {
 
     // 初始化本地系统监控 (纯 /proc、/sys 读取，不会失败)
     let mut monitor = monitor::SystemMonitor::new(args.net_interface.clone());
+    monitor.enable_quota(&args);
 
     // 🌟 [v2.4.0] 共享控制状态 (按键双击 / 控制接口 / 告警队列共用)
     let control_state = control::new_shared();
@@ -317,10 +456,13 @@ async fn main() -> Result<()> {
     let _ = screen.write_data(b"        ", 0).await;
     let _ = screen.power(false, 0);
 
-    // 3. 稍微等一下（100ms），给后台线程“跳出循环并关闭文件”的时间
+    // 3. 流量账本落盘 (两次定时落盘之间的增量不丢)
+    monitor.save_quota();
+
+    // 4. 稍微等一下（100ms），给后台线程“跳出循环并关闭文件”的时间
     tokio::time::sleep(std::time::Duration::from_millis(100)).await;
 
-    // 4. 删掉 PID 文件（保持系统整洁）
+    // 5. 删掉 PID 文件（保持系统整洁）
     let _ = std::fs::remove_file("/var/run/athena-led.pid");
 
     println!("👋 [系统] Athena LED 服务已安全关闭。");
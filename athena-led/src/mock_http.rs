@@ -0,0 +1,126 @@
+// ==========================================
+// 🧪 mock_http.rs — 测试用本地 HTTP 桩 (仅 cfg(test) 编译)
+// 监听 127.0.0.1 随机端口，按路径 (不含查询串) 返回录制好的响应:
+// 正常 / 畸形 / 非 200 / 慢响应，并统计各路径的请求次数 (验证缓存与失败退避)。
+// 配合 weather_provider::Endpoints::all(&server.base) 把所有接口指到这里。
+// ==========================================
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+#[derive(Debug, Clone)]
+pub enum Reply {
+    Body(&'static str),
+    Status(u16),
+    Slow(Duration, &'static str),
+}
+
+#[derive(Default)]
+struct State {
+    routes: HashMap<String, Reply>,
+    hits: HashMap<String, usize>,
+}
+
+pub struct MockServer {
+    pub base: String,
+    state: Arc<Mutex<State>>,
+}
+
+impl MockServer {
+    pub async fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind mock server");
+        let base = format!("http://{}", listener.local_addr().expect("mock addr"));
+        let state = Arc::new(Mutex::new(State::default()));
+
+        let shared = state.clone();
+        tokio::spawn(async move {
+            while let Ok((mut sock, _)) = listener.accept().await {
+                let shared = shared.clone();
+                tokio::spawn(async move {
+                    // 只读请求头 (被测代码只发 GET)
+                    let mut buf = Vec::new();
+                    let mut chunk = [0u8; 1024];
+                    while !buf.windows(4).any(|w| w == b"\r\n\r\n") {
+                        match sock.read(&mut chunk).await {
+                            Ok(0) | Err(_) => return,
+                            Ok(n) => buf.extend_from_slice(&chunk[..n]),
+                        }
+                    }
+                    let head = String::from_utf8_lossy(&buf);
+                    let target = head.split_whitespace().nth(1).unwrap_or("/");
+                    let path = target.split('?').next().unwrap_or("/").to_string();
+
+                    let reply = {
+                        let mut st = shared.lock().unwrap();
+                        *st.hits.entry(path.clone()).or_default() += 1;
+                        st.routes.get(&path).cloned()
+                    };
+                    let (status, body) = match reply {
+                        Some(Reply::Body(b)) => (200, b),
+                        Some(Reply::Status(code)) => (code, "{}"),
+                        Some(Reply::Slow(delay, b)) => {
+                            tokio::time::sleep(delay).await;
+                            (200, b)
+                        }
+                        None => (404, "{}"),
+                    };
+                    let resp = format!(
+                        "HTTP/1.1 {} MOCK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        status,
+                        body.len(),
+                        body
+                    );
+                    let _ = sock.write_all(resp.as_bytes()).await;
+                    let _ = sock.shutdown().await;
+                });
+            }
+        });
+
+        Self { base, state }
+    }
+
+    pub fn route(&self, path: &str, reply: Reply) {
+        self.state.lock().unwrap().routes.insert(path.to_string(), reply);
+    }
+
+    pub fn hits(&self, path: &str) -> usize {
+        self.state.lock().unwrap().hits.get(path).copied().unwrap_or(0)
+    }
+}
+
+// 测试用短超时客户端 (慢响应用例靠它触发超时)
+pub fn client() -> reqwest::Client {
+    reqwest::Client::builder().timeout(Duration::from_millis(500)).build().unwrap()
+}
+
+// 同上，包成各数据源共用的出站句柄 (不限流)
+pub fn http() -> crate::http_client::Http {
+    crate::http_client::Http::for_test(client())
+}
+
+// ==========================================
+// 📼 录制的接口响应 (字段裁剪过，结构与线上一致)
+// ==========================================
+pub mod fixtures {
+    pub const UAPIS: &str = r#"{"province":"北京","city":"北京","adcode":"110000","weather":"小雨","weather_icon":"","temperature":18.0,"wind_direction":"东北","wind_power":"≤3","humidity":82,"report_time":"2026-10-19 14:30:00","temp_max":21,"temp_min":14}"#;
+
+    pub const WTTR: &str = r#"{"current_condition":[{"FeelsLikeC":"16","humidity":"63","temp_C":"17","weatherCode":"116","weatherDesc":[{"value":"Partly cloudy"}],"windspeedKmph":"11"}],"nearest_area":[],"weather":[{"date":"2026-10-19","maxtempC":"22","mintempC":"12"}]}"#;
+
+    pub const SENIVERSE_DAILY: &str = r#"{"results":[{"location":{"id":"WX4FBXXFKE4F","name":"北京"},"daily":[{"date":"2026-10-19","text_day":"Sunny","code_day":"0","high":"24","low":"10","humidity":"35","wind_speed":"8.5"},{"date":"2026-10-20","text_day":"Light rain","code_day":"13","high":"19","low":"12","humidity":"80","wind_speed":"15"}],"last_update":"2026-10-19T08:00:00+08:00"}]}"#;
+
+    pub const SENIVERSE_AIR: &str = r#"{"results":[{"location":{"name":"北京"},"air":{"city":{"aqi":"68","pm25":"41","pm10":"70","quality":"良"}}}]}"#;
+
+    pub const OM_GEO: &str = r#"{"results":[{"id":1816670,"name":"北京市","latitude":39.9075,"longitude":116.39723,"country_code":"CN","timezone":"Asia/Shanghai"}],"generationtime_ms":0.8}"#;
+
+    pub const OM_FORECAST: &str = r#"{"latitude":39.9,"longitude":116.4,"timezone":"Asia/Shanghai","current_weather":{"time":"2026-10-19T14:00","temperature":19.6,"windspeed":9.4,"winddirection":40,"weathercode":3},"current":{"time":"2026-10-19T14:00","relative_humidity_2m":55},"daily":{"time":["2026-10-19","2026-10-20"],"weathercode":[3,61],"temperature_2m_max":[23.1,18.4],"temperature_2m_min":[11.7,12.2]},"hourly":{"time":["2026-10-19T14:00","2026-10-19T15:00"],"precipitation_probability":[10,65]}}"#;
+
+    pub const OM_AIR: &str = r#"{"latitude":39.9,"longitude":116.4,"current":{"time":"2026-10-19T14:00","us_aqi":57,"pm2_5":16.3}}"#;
+
+    pub const GEOIP: &str = r#"{"status":"success","city":"杭州市","lat":30.2943,"lon":120.1663}"#;
+
+    // 畸形: 能解析的 JSON 但缺字段 / 干脆不是 JSON
+    pub const MISSING_FIELDS: &str = r#"{"code":0,"data":{}}"#;
+    pub const NOT_JSON: &str = "<html>502 Bad Gateway</html>";
+}
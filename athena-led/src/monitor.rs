@@ -4,7 +4,13 @@
 // 纯本地数据 (读 /proc、/sys)，全部瞬时返回，渲染循环可放心同步调用。
 // 🌟 网络类数据 (天气/IP/股票/HTTP/延迟) 已迁往 net_agent.rs 后台刷新
 // ==========================================
+use crate::clients::ClientTracker;
 use crate::control::Alert;
+use crate::disk;
+use crate::history::{History, SAMPLE_SECS};
+use crate::quota::{self, QuotaMeter, SharedQuota};
+use crate::wan::{self, WanMonitor};
+use crate::wifi::WifiMonitor;
 use crate::Args;
 use chrono::{Datelike, Local, NaiveDate};
 use std::collections::{HashMap, HashSet};
@@ -52,6 +58,29 @@ pub struct SystemMonitor {
     known_macs: HashSet<String>,
     arp_initialized: bool,
     last_arp_check: Option<Instant>,
+
+    // 🏆 局域网终端流量排行 (top 模块)
+    clients: ClientTracker,
+
+    // 📶 无线射频状态 (wifi / wifi_ch / wifi_rssi 模块)
+    wifi: WifiMonitor,
+
+    // 📦 流量配额记账 (未配置记账网卡时为 None)
+    quota: Option<SharedQuota>,
+
+    // 📈 指标历史 (spark 模块)，搭灯光引擎刷新的便车定时采样
+    history: History,
+    last_history_sample: Option<Instant>,
+
+    // 📊 每核 CPU 上一次采样 (gauge:cores)
+    last_core_stats: Vec<(u64, u64)>,
+
+    // 💽 空间不足告警 (每分钟检查; 挂载点 -> 上次播报时间，恢复后移除)
+    last_disk_check: Option<Instant>,
+    disk_low: HashMap<String, Instant>,
+
+    // 🌍 WAN 会话信息 (ubus / ifstatus)
+    wan: WanMonitor,
 }
 
 impl SystemMonitor {
@@ -89,6 +118,36 @@ impl SystemMonitor {
             known_macs: HashSet::new(),
             arp_initialized: false,
             last_arp_check: None,
+            clients: ClientTracker::new(),
+            wifi: WifiMonitor::new(),
+            quota: None,
+            history: History::default(),
+            last_history_sample: None,
+            last_core_stats: Vec::new(),
+            last_disk_check: None,
+            disk_low: HashMap::new(),
+            wan: WanMonitor::new(),
+        }
+    }
+
+    // 📦 开启流量配额记账 (读回闪存里的旧账本，采样由独立定时任务推进)
+    pub fn enable_quota(&mut self, args: &Args) {
+        if args.quota_interface.is_empty() {
+            return;
+        }
+        self.quota = Some(quota::spawn_ticker(QuotaMeter::new(
+            &args.quota_interface,
+            args.quota_gb,
+            args.quota_billing_day,
+            args.quota_alert,
+            &args.quota_file,
+            args.quota_save_mins,
+        )));
+    }
+
+    pub fn save_quota(&mut self) {
+        if let Some(mut q) = self.quota.as_ref().and_then(|q| q.lock().ok()) {
+            q.save();
         }
     }
 
@@ -110,7 +169,7 @@ impl SystemMonitor {
                 }
 
                 if self.temp_alert_on
-                    && self.last_temp_alert.map_or(true, |i| i.elapsed() >= Duration::from_secs(60))
+                    && self.last_temp_alert.is_none_or(|i| i.elapsed() >= Duration::from_secs(60))
                 {
                     alerts.push(Alert {
                         text: format!("HOT {:.0}C", t),
@@ -124,10 +183,10 @@ impl SystemMonitor {
 
         // --- 2. 🌐 WAN 断网/恢复提醒 (5 秒检测一次, 只在状态翻转时播报) ---
         if args.alert_wan
-            && self.last_wan_check.map_or(true, |i| i.elapsed() >= Duration::from_secs(5))
+            && self.last_wan_check.is_none_or(|i| i.elapsed() >= Duration::from_secs(5))
         {
             self.last_wan_check = Some(Instant::now());
-            let up = self.check_default_route();
+            let up = has_default_route();
             match self.wan_was_up {
                 Some(true) if !up => alerts.push(Alert {
                     text: "NET DOWN".to_string(),
@@ -146,7 +205,7 @@ impl SystemMonitor {
 
         // --- 3. 📱 新设备接入提醒 (30 秒扫一次 ARP, 首轮只记录不播报) ---
         if args.alert_newdev
-            && self.last_arp_check.map_or(true, |i| i.elapsed() >= Duration::from_secs(30))
+            && self.last_arp_check.is_none_or(|i| i.elapsed() >= Duration::from_secs(30))
         {
             self.last_arp_check = Some(Instant::now());
             let current = self.read_arp_macs();
@@ -156,9 +215,7 @@ impl SystemMonitor {
                 self.arp_initialized = true;
             } else {
                 // 每轮最多播报 3 个，防止批量上线刷屏
-                let mut count = 0;
-                for mac in current.difference(&self.known_macs) {
-                    if count >= 3 { break; }
+                for mac in current.difference(&self.known_macs).take(3) {
                     // 显示 MAC 后三段足以辨认: "NEW DD:EE:FF"
                     let tail = if mac.len() >= 8 { &mac[mac.len() - 8..] } else { mac.as_str() };
                     alerts.push(Alert {
@@ -166,13 +223,39 @@ impl SystemMonitor {
                         blink: false,
                         secs: 5,
                     });
-                    count += 1;
                 }
                 // 记入已知集合 (取并集: 设备离线再回来不重复播报)
                 self.known_macs.extend(current);
             }
         }
 
+        // --- 4. 💽 存储空间不足 (每分钟检查, 进入低空间时播报, 持续不足每小时再提醒一次) ---
+        if !args.disk_alert.is_empty()
+            && self.last_disk_check.is_none_or(|i| i.elapsed() >= Duration::from_secs(60))
+        {
+            self.last_disk_check = Some(Instant::now());
+            for (mount, threshold) in disk::parse_alert_spec(&args.disk_alert) {
+                let Some(u) = disk::usage(&mount) else { continue };
+                if !threshold.is_low(&u) {
+                    self.disk_low.remove(&mount);
+                    continue;
+                }
+                if self.disk_low.get(&mount).is_none_or(|i| i.elapsed() >= Duration::from_secs(3600)) {
+                    alerts.push(Alert {
+                        text: disk::alert_text(&mount, &u),
+                        blink: true,
+                        secs: 5,
+                    });
+                    self.disk_low.insert(mount, Instant::now());
+                }
+            }
+        }
+
+        // --- 5. 📦 流量配额 (每账期只播一次) ---
+        if let Some(text) = self.quota.as_ref().and_then(|q| q.lock().ok()?.poll_alert()) {
+            alerts.push(Alert { text, blink: true, secs: 5 });
+        }
+
         alerts
     }
 
@@ -193,35 +276,6 @@ impl SystemMonitor {
         macs
     }
 
-    // ==========================================
-    // [灯光大脑 1] 零流量底层路由探测 (用于奖牌灯)
-    // ==========================================
-    fn check_default_route(&self) -> bool {
-        // IPv4: Destination 为 00000000 代表存在默认网关
-        if let Ok(content) = fs::read_to_string("/proc/net/route") {
-            for line in content.lines().skip(1) {
-                let parts: Vec<&str> = line.split_whitespace().collect();
-                if parts.len() > 1 && parts[1] == "00000000" { return true; }
-            }
-        }
-        // 🌟 IPv6: 目的前缀 ::/0 (全零 + 前缀长度 00) 即默认路由
-        // 修复 IPv6-only 拨号环境下奖牌灯误判“断网”的问题
-        if let Ok(content) = fs::read_to_string("/proc/net/ipv6_route") {
-            for line in content.lines() {
-                let parts: Vec<&str> = line.split_whitespace().collect();
-                // 格式: dest(32hex) plen(2hex) src srclen nexthop metric refcnt use flags ifname
-                if parts.len() >= 10
-                    && parts[0] == "00000000000000000000000000000000"
-                    && parts[1] == "00"
-                    && parts[9] != "lo"
-                {
-                    return true;
-                }
-            }
-        }
-        false
-    }
-
     // ==========================================
     // [灯光大脑 2] 综合输出当前 100ms 的 4 灯状态 (状态隔离版)
     // ==========================================
@@ -255,6 +309,12 @@ impl SystemMonitor {
             }
 
             self.led_last_time = now;
+
+            // 3. 📈 顺手记录指标历史 (每 SAMPLE_SECS 秒一格)
+            if self.last_history_sample.is_none_or(|t| t.elapsed() >= Duration::from_secs(SAMPLE_SECS)) {
+                self.last_history_sample = Some(now);
+                self.record_history();
+            }
         }
 
         // 🕒 1. 时钟灯 (Bit 0, Val 1): 绑定专属 CPU 负载
@@ -267,7 +327,7 @@ impl SystemMonitor {
 
         // 🏅 2. 奖牌灯 (Bit 1, Val 2): 绑定路由连通性 (每2秒查一次)
         if now.duration_since(self.led_medal_timer).as_secs() >= 2 {
-            self.led_medal_state = self.check_default_route();
+            self.led_medal_state = has_default_route();
             self.led_medal_timer = now;
         }
         if self.led_medal_state { flag |= 2; }
@@ -330,25 +390,8 @@ impl SystemMonitor {
     // 读取 /proc/stat 获取 CPU 数据
     fn read_cpu_stats(&self) -> (u64, u64) {
         let content = fs::read_to_string("/proc/stat").unwrap_or_default();
-        if let Some(line) = content.lines().next() { // 第一行通常是 total cpu
-            if line.starts_with("cpu ") {
-                let parts: Vec<&str> = line.split_whitespace().collect();
-                // parts[1]..parts[4] = user, nice, system, idle; parts[5].. = iowait, irq, softirq
-                if parts.len() >= 5 {
-                    let user: u64 = parts[1].parse().unwrap_or(0);
-                    let nice: u64 = parts[2].parse().unwrap_or(0);
-                    let system: u64 = parts[3].parse().unwrap_or(0);
-                    let idle: u64 = parts[4].parse().unwrap_or(0);
-                    let iowait: u64 = parts.get(5).and_then(|s| s.parse().ok()).unwrap_or(0);
-                    let irq: u64 = parts.get(6).and_then(|s| s.parse().ok()).unwrap_or(0);
-                    let softirq: u64 = parts.get(7).and_then(|s| s.parse().ok()).unwrap_or(0);
-
-                    let total = user + nice + system + idle + iowait + irq + softirq;
-                    return (total, idle);
-                }
-            }
-        }
-        (0, 0)
+        // 第一行通常是 total cpu
+        content.lines().next().filter(|l| l.starts_with("cpu ")).and_then(parse_cpu_line).unwrap_or((0, 0))
     }
 
     // ==========================================
@@ -389,7 +432,7 @@ impl SystemMonitor {
         let duration = now.duration_since(*last_time).as_secs_f64();
 
         // 防抖与异常防护
-        if duration < 0.1 || duration > 30.0 || *last_rx == 0 {
+        if !(0.1..=30.0).contains(&duration) || *last_rx == 0 {
             // 更新记忆，返回 0
             self.net_speed_cache.insert(target_iface.to_string(), (curr_rx, curr_tx, now));
             return format_bytes_speed(0.0);
@@ -503,7 +546,7 @@ impl SystemMonitor {
     }
 
     // --- 内存监控 (读取 /proc/meminfo) ---
-    pub fn get_mem_string(&self) -> String {
+    fn read_mem_percent(&self) -> Option<f64> {
         let content = fs::read_to_string("/proc/meminfo").unwrap_or_default();
         let mut total = 0.0;
         let mut available = 0.0;
@@ -518,11 +561,13 @@ impl SystemMonitor {
             }
         }
 
-        if total > 0.0 {
-            let usage_percent = 100.0 * (1.0 - (available / total));
-            format!("M:{:.0}%", usage_percent)
-        } else {
-            "M:Err".to_string()
+        if total > 0.0 { Some(100.0 * (1.0 - (available / total))) } else { None }
+    }
+
+    pub fn get_mem_string(&self) -> String {
+        match self.read_mem_percent() {
+            Some(usage_percent) => format!("M:{:.0}%", usage_percent),
+            None => "M:Err".to_string(),
         }
     }
 
@@ -564,7 +609,7 @@ impl SystemMonitor {
         let mut results = Vec::new();
 
         // 1. 分割 ID 字符串 (支持空格或逗号分隔)
-        let id_list: Vec<&str> = ids.split(|c| c == ' ' || c == ',')
+        let id_list: Vec<&str> = ids.split([' ', ','])
                                     .filter(|s| !s.is_empty())
                                     .collect();
 
@@ -647,6 +692,7 @@ impl SystemMonitor {
     // ==========================================
     // 🌟 [新功能模块] 倒数日 (countdown)
     // param: "YYYY-MM-DD" (一次性) 或 "MM-DD" (每年循环，如生日/节日)
+    //        或 "L08-15" (农历每年循环) / "LR06-15" (农历闰六月十五)
     // 显示: D-123 (还有123天) / D-DAY (就是今天) / D+5 (已过5天)
     // ==========================================
     pub fn get_countdown(&self, param: &str) -> String {
@@ -681,6 +727,134 @@ impl SystemMonitor {
         }
         "CT:Err".to_string()
     }
+
+    // ==========================================
+    // 🏆 局域网终端流量排行 (top)
+    // 返回前 n 名的屏显文本 (如 "NAS 1.2M")，由调度器在模块时长内轮流显示
+    // ==========================================
+    pub fn get_top_clients(&mut self, n: usize) -> Vec<String> {
+        self.clients.lines(n)
+    }
+
+    // ==========================================
+    // 📦 流量配额 (param: day / month / left / pct)
+    // ==========================================
+    pub fn get_quota_string(&mut self, param: &str) -> String {
+        match self.quota.as_ref().and_then(|q| q.lock().ok()) {
+            Some(q) => q.display(param),
+            None => "Q:Off".to_string(),
+        }
+    }
+
+    // ==========================================
+    // 📈 指标历史 (spark 模块)
+    // CPU 复用灯光引擎的 0.25 秒测量值；温度记录所有存在的 thermal_zone (temp0~temp9)
+    // ==========================================
+    fn record_history(&mut self) {
+        self.history.record("cpu", self.led_cpu_usage);
+        self.history.record("mem", self.read_mem_percent().unwrap_or(f64::NAN));
+        self.history.record("rx", self.led_rx_speed);
+        self.history.record("tx", self.led_tx_speed);
+        for id in 0..10 {
+            if let Some(t) = self.get_temp_value(&id.to_string()) {
+                self.history.record(&format!("temp{}", id), t);
+            }
+        }
+    }
+
+    pub fn get_history(&self, metric: &str) -> Vec<f64> {
+        self.history.values(metric)
+    }
+
+    // ==========================================
+    // 💽 存储空间 (disk)，param = "挂载点[,free]"
+    // ==========================================
+    pub fn get_disk_string(&self, param: &str) -> String {
+        let (mount, free_mode) = disk::parse_param(param);
+        disk::display(disk::usage(mount), free_mode)
+    }
+
+    // ==========================================
+    // 🌍 WAN 会话信息 (wan)，param = "接口[/模式]"
+    // ==========================================
+    pub fn get_wan_string(&mut self, param: &str) -> String {
+        let (iface, mode) = wan::parse_param(param);
+        match self.wan.status(iface) {
+            Some(info) => wan::display(info.as_ref(), mode),
+            None => "W:--".to_string(),
+        }
+    }
+
+    // ==========================================
+    // 📊 条形仪表 (gauge): 各百分比指标的数值
+    // CPU 复用灯光引擎的 0.25 秒测量值；conn = 连接数 / 连接表上限
+    // ==========================================
+    pub fn get_gauge_percent(&mut self, metric: &str) -> Option<f64> {
+        match metric {
+            "mem" => self.read_mem_percent(),
+            "conn" => {
+                let read = |path: &str| -> Option<f64> { fs::read_to_string(path).ok()?.trim().parse().ok() };
+                let count = read("/proc/sys/net/netfilter/nf_conntrack_count")?;
+                let max = read("/proc/sys/net/netfilter/nf_conntrack_max").filter(|m| *m > 0.0)?;
+                Some(100.0 * count / max)
+            }
+            "quota" => self.quota.as_ref()?.lock().ok()?.percent(),
+            _ => Some(self.led_cpu_usage),
+        }
+    }
+
+    // 每核 CPU 占用率 (首次调用只建立基线，全部返回 0)
+    pub fn get_core_usages(&mut self) -> Vec<f64> {
+        let curr = parse_core_stats(&fs::read_to_string("/proc/stat").unwrap_or_default());
+        let usages = curr
+            .iter()
+            .enumerate()
+            .map(|(i, c)| self.last_core_stats.get(i).map_or(0.0, |p| usage_between(*p, *c)))
+            .collect();
+        self.last_core_stats = curr;
+        usages
+    }
+
+    // ==========================================
+    // 📶 无线射频状态 (参数为射频编号 N，对应 phyN)
+    // ==========================================
+    pub fn get_wifi_string(&mut self, kind: &str, radio: &str) -> String {
+        match kind {
+            "wifi_ch" => self.wifi.channel(radio),
+            "wifi_rssi" => self.wifi.rssi(radio),
+            _ => self.wifi.stations(radio),
+        }
+    }
+}
+
+// ==========================================
+// [灯光大脑 1] 零流量底层路由探测 (用于奖牌灯)
+// 也供 WAN 断网告警与 net_agent 的 DNS 告警判断"路由还在"
+// ==========================================
+pub fn has_default_route() -> bool {
+    // IPv4: Destination 为 00000000 代表存在默认网关
+    if let Ok(content) = fs::read_to_string("/proc/net/route") {
+        for line in content.lines().skip(1) {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() > 1 && parts[1] == "00000000" { return true; }
+        }
+    }
+    // 🌟 IPv6: 目的前缀 ::/0 (全零 + 前缀长度 00) 即默认路由
+    // 修复 IPv6-only 拨号环境下奖牌灯误判“断网”的问题
+    if let Ok(content) = fs::read_to_string("/proc/net/ipv6_route") {
+        for line in content.lines() {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            // 格式: dest(32hex) plen(2hex) src srclen nexthop metric refcnt use flags ifname
+            if parts.len() >= 10
+                && parts[0] == "00000000000000000000000000000000"
+                && parts[1] == "00"
+                && parts[9] != "lo"
+            {
+                return true;
+            }
+        }
+    }
+    false
 }
 
 // 🌟 倒数日的纯函数实现 (与"今天"解耦，方便单元测试)
@@ -690,7 +864,16 @@ fn countdown_for(today: NaiveDate, param: &str) -> String {
         return "NO DATE".to_string();
     }
 
-    let target = if param.len() <= 5 {
+    let target = if let Some(lunar) = param.strip_prefix(['L', 'l']) {
+        // "L08-15" / "LR06-15": 农历生日，换算成下一次的公历日期
+        let (is_leap, md) = match lunar.strip_prefix(['R', 'r']) {
+            Some(rest) => (true, rest),
+            None => (false, lunar),
+        };
+        md.split_once('-')
+            .and_then(|(m, d)| Some((m.parse::<u32>().ok()?, d.parse::<u32>().ok()?)))
+            .and_then(|(m, d)| crate::lunar::next_occurrence(today, m, d, is_leap))
+    } else if param.len() <= 5 {
         // "MM-DD" 格式: 自动找最近的下一次 (今年已过就算明年的)
         NaiveDate::parse_from_str(&format!("{}-{}", today.year(), param), "%Y-%m-%d")
             .ok()
@@ -721,8 +904,41 @@ fn countdown_for(today: NaiveDate, param: &str) -> String {
     }
 }
 
+// 解析 /proc/stat 的一行 cpu 统计，返回 (总时间片, 空闲时间片)
+// parts[1]..parts[4] = user, nice, system, idle; parts[5].. = iowait, irq, softirq
+fn parse_cpu_line(line: &str) -> Option<(u64, u64)> {
+    let parts: Vec<&str> = line.split_whitespace().collect();
+    if parts.len() < 5 {
+        return None;
+    }
+    let field = |i: usize| -> u64 { parts.get(i).and_then(|s| s.parse().ok()).unwrap_or(0) };
+    let idle = field(4);
+    let total = field(1) + field(2) + field(3) + idle + field(5) + field(6) + field(7);
+    Some((total, idle))
+}
+
+// 每个核心一行 "cpu0 ..." / "cpu1 ..." (跳过汇总行 "cpu ")
+fn parse_core_stats(content: &str) -> Vec<(u64, u64)> {
+    content
+        .lines()
+        .filter(|l| l.starts_with("cpu") && l.as_bytes().get(3).is_some_and(u8::is_ascii_digit))
+        .filter_map(parse_cpu_line)
+        .collect()
+}
+
+// 两次采样之间的占用率 (%)
+fn usage_between(prev: (u64, u64), curr: (u64, u64)) -> f64 {
+    let diff_total = curr.0.saturating_sub(prev.0);
+    let diff_idle = curr.1.saturating_sub(prev.1);
+    if diff_total == 0 {
+        0.0
+    } else {
+        100.0 * (1.0 - diff_idle as f64 / diff_total as f64)
+    }
+}
+
 // 辅助格式化函数
-fn format_bytes_speed(bytes_per_sec: f64) -> String {
+pub fn format_bytes_speed(bytes_per_sec: f64) -> String {
     if bytes_per_sec > 1_048_576.0 {
         format!("{:.1}M", bytes_per_sec / 1_048_576.0)
     } else if bytes_per_sec > 1024.0 {
@@ -732,7 +948,7 @@ fn format_bytes_speed(bytes_per_sec: f64) -> String {
     }
 }
 
-fn format_bytes_total(bytes: u64) -> String {
+pub fn format_bytes_total(bytes: u64) -> String {
     let b = bytes as f64;
     if b > 1_099_511_627_776.0 { // 1TB
         format!("{:.2}T", b / 1_099_511_627_776.0)
@@ -773,6 +989,19 @@ mod tests {
         assert_eq!(countdown_for(today, "01-01"), "D-178");
     }
 
+    #[test]
+    fn countdown_lunar() {
+        let today = d(2026, 7, 7);
+        // 2026 中秋 (八月十五) = 2026-09-25
+        assert_eq!(countdown_for(today, "L08-15"), "D-80");
+        // 农历正月初一已过: 顺延到 2027-02-06
+        assert_eq!(countdown_for(today, "L01-01"), "D-214");
+        // 闰月生日: 2025 有闰六月 (闰六月十五 = 2025-08-08)
+        assert_eq!(countdown_for(d(2025, 8, 1), "LR06-15"), "D-7");
+        assert_eq!(countdown_for(today, "L13-01"), "D:Err");
+        assert_eq!(countdown_for(today, "Lxx"), "D:Err");
+    }
+
     #[test]
     fn countdown_invalid() {
         let today = d(2026, 7, 7);
@@ -781,6 +1010,19 @@ mod tests {
         assert_eq!(countdown_for(today, "hello"), "D:Err");
     }
 
+    #[test]
+    fn cpu_stat_parsing() {
+        let stat = "cpu  400 0 100 1500 0 0 0 0 0 0\n\
+                    cpu0 100 0 25 375 0 0 0 0 0 0\n\
+                    cpu1 300 0 75 125 0 0 0 0 0 0\n\
+                    intr 12345\n";
+        assert_eq!(parse_cpu_line(stat.lines().next().unwrap()), Some((2000, 1500)));
+        let cores = parse_core_stats(stat);
+        assert_eq!(cores, vec![(500, 375), (500, 125)]);
+        assert_eq!(usage_between((0, 0), cores[1]), 75.0);
+        assert_eq!(usage_between(cores[0], cores[0]), 0.0);
+    }
+
     #[test]
     fn bytes_formatting() {
         assert_eq!(format_bytes_speed(500.0), "500B");
@@ -7,129 +7,59 @@
 // 现在所有网络请求由本模块的后台任务定时刷新，写入共享快照；
 // 渲染层只读快照，永不等待网络。
 // ==========================================
+use crate::agenda::{AgendaAlarm, Calendar, Occurrence};
 use crate::control::{Alert, SharedControl};
+use crate::dns::{DnsAlarm, DnsStatus};
+use crate::history::Ring;
+use crate::http_client::{Http, HttpOptions};
+use crate::http_source::HttpFeed;
+use crate::location::{Location, Locator, LocatorConfig};
+use crate::ping::PingStats;
+use crate::rss::{Headlines, RssFeed};
+use crate::ticker::TickerFeed;
+use crate::weather::{RainAlarm, Units, WeatherExtras, WeatherReport};
+use crate::weather_provider::{Endpoints, WeatherChain};
 use crate::Args;
 use regex::Regex;
-use reqwest::Client;
-use serde::Deserialize;
-use serde_json::Value;
 use std::collections::HashMap;
 use std::sync::{Arc, RwLock};
 use std::time::{Duration, Instant};
 
-// --- uapis.cn 天气结构体 ---
-#[derive(Deserialize, Debug)]
-struct WeatherResponse {
-    // 天气现象 (例如: "多云", "晴", "小雨")
-    weather: String,
-    // 当前温度
-    temperature: f64,
-    // 最高温 (仅 forecast=true 时返回)
-    #[serde(default)]
-    temp_max: Option<f64>,
-    // 最低温 (仅 forecast=true 时返回)
-    #[serde(default)]
-    temp_min: Option<f64>,
-}
-
-// --- 心知天气结构体 ---
-#[derive(Deserialize, Debug)]
-struct SeniverseResponse {
-    results: Vec<SeniverseResult>,
-}
-#[derive(Deserialize, Debug)]
-struct SeniverseResult {
-    daily: Vec<SeniverseDaily>,
-}
-#[derive(Deserialize, Debug)]
-struct SeniverseDaily {
-    high: String,
-    low: String,
-    code_day: String,
-}
-
-#[derive(Deserialize, Debug)]
-struct WttrResult {
-    current_condition: Vec<WttrCurrent>,
-    weather: Vec<WttrDaily>,
-}
-
-#[derive(Deserialize, Debug)]
-#[allow(non_snake_case)]
-struct WttrCurrent {
-    temp_C: String,
-    weatherDesc: Vec<WttrValue>,
-}
-
-#[derive(Deserialize, Debug)]
-#[allow(non_snake_case)]
-struct WttrDaily {
-    maxtempC: String,
-    mintempC: String,
-}
-
-#[derive(Deserialize, Debug)]
-struct WttrValue {
-    value: String,
-}
-
-// --- Open-Meteo 结构体 ---
-#[derive(Deserialize, Debug)]
-struct OmGeoResponse {
-    results: Option<Vec<OmLocation>>,
-}
-
-#[derive(Deserialize, Debug)]
-#[allow(dead_code)]
-struct OmLocation {
-    name: String,
-    latitude: f64,
-    longitude: f64,
-}
-
-#[derive(Deserialize, Debug)]
-struct OmWeatherResponse {
-    current_weather: OmCurrentWeather,
-    // 当天最高/最低温 (可选，接口异常时不影响当前温度显示)
-    #[serde(default)]
-    daily: Option<OmDaily>,
-}
-
-#[derive(Deserialize, Debug)]
-struct OmCurrentWeather {
-    temperature: f64,
-    weathercode: u8,
-}
-
-#[derive(Deserialize, Debug)]
-struct OmDaily {
-    #[serde(default)]
-    temperature_2m_max: Vec<f64>,
-    #[serde(default)]
-    temperature_2m_min: Vec<f64>,
-}
-
 // ==========================================
 // 📸 共享快照：后台任务写，渲染层读 (临界区极短，无网络等待)
 // ==========================================
 struct NetSnapshot {
-    weather: String,
+    weather: Result<WeatherReport, String>, // 当前天气 (失败/未就绪时为屏幕提示 "W:Err(U)" / "Wait...")
+    weather_extras: WeatherExtras, // 明日预报 / 空气质量 / 逐小时降水概率
     ip: String,
     http_text: String,
-    stock: String,
-    pings: HashMap<String, String>, // 目标 -> "P:23ms"
-    sun: String,                    // "6:02~19:23" (由 IP 定位经纬度计算)
+    http_named: HashMap<String, String>, // 具名 HTTP 源 -> 显示文本 (http:<名字> 模块)
+    quotes: Vec<(String, String, u8)>, // 行情: (代号, 显示文本, 涨跌灯位)，按配置顺序
+    rss: HashMap<String, Headlines>, // RSS 源 -> 最新标题与已播标记 (rss:<名字> 模块)
+    agenda: Result<Vec<Occurrence>, String>, // 即将开始的日程 (未就绪/失败时为 "CAL:Wait" / "CAL:Err")
+    pings: HashMap<String, PingStats>, // 目标 -> 滚动统计 (丢包/抖动/最小/平均/最大)
+    ping_hist: HashMap<String, Ring>, // 目标 -> 最近 27 次延迟 (ms，失败记 NaN，spark 模块用)
+    dns: DnsStatus,                 // 本机 dnsmasq / 上游解析器最近一轮结果
+    sun: String,                    // "6:02~19:23" (由定位经纬度计算)
+    location: Location,             // 城市 + 经纬度 (loc 模块 / 月出月落/晨昏蒙影等模式本地计算用)
 }
 
 impl Default for NetSnapshot {
     fn default() -> Self {
         Self {
-            weather: "Wait...".to_string(),
+            weather: Err("Wait...".to_string()),
+            weather_extras: WeatherExtras::default(),
             ip: "IP:Wait".to_string(),
             http_text: String::new(),
-            stock: String::new(),
+            http_named: HashMap::new(),
+            quotes: Vec::new(),
+            rss: HashMap::new(),
+            agenda: Err("CAL:Wait".to_string()),
             pings: HashMap::new(),
+            ping_hist: HashMap::new(),
+            dns: DnsStatus::default(),
             sun: "SUN:--".to_string(),
+            location: Location::default(),
         }
     }
 }
@@ -139,8 +69,16 @@ impl Default for NetSnapshot {
 pub struct NetHandle(Arc<RwLock<NetSnapshot>>);
 
 impl NetHandle {
-    pub fn weather(&self) -> String {
-        self.0.read().map(|s| s.weather.clone()).unwrap_or_else(|_| "Wait...".into())
+    pub fn weather(&self) -> Result<WeatherReport, String> {
+        self.0.read().map(|s| s.weather.clone()).unwrap_or_else(|_| Err("Wait...".into()))
+    }
+    // weather:temp / hilo / humidity / wind；没有数据时显示错误提示
+    pub fn weather_field(&self, field: crate::weather::Field, units: Units) -> String {
+        self.weather().map(|r| r.field(field, units)).unwrap_or_else(|e| e)
+    }
+    pub fn weather_extra(&self, mode: crate::weather::ExtraMode, units: Units) -> String {
+        let now = chrono::Local::now().naive_local();
+        self.0.read().map(|s| s.weather_extras.display(mode, units, now)).unwrap_or_else(|_| "W:Wait".into())
     }
     pub fn ip(&self) -> String {
         self.0.read().map(|s| s.ip.clone()).unwrap_or_else(|_| "IP:Wait".into())
@@ -148,31 +86,84 @@ impl NetHandle {
     pub fn http_text(&self) -> String {
         self.0.read().map(|s| s.http_text.clone()).unwrap_or_default()
     }
-    pub fn stock(&self) -> String {
-        self.0.read().map(|s| s.stock.clone()).unwrap_or_default()
+    pub fn http(&self, name: &str) -> String {
+        self.0.read()
+            .ok()
+            .and_then(|s| s.http_named.get(name).cloned())
+            .unwrap_or_else(|| "HTTP:None".to_string())
     }
-    pub fn ping(&self, target: &str) -> String {
+    // symbol 为空 = 全部品种 (轮播用)，否则只取该代号 (不区分大小写)
+    pub fn quotes(&self, symbol: &str) -> Vec<(String, u8)> {
+        self.0.read()
+            .map(|s| {
+                s.quotes
+                    .iter()
+                    .filter(|(sym, _, _)| symbol.is_empty() || sym.eq_ignore_ascii_case(symbol))
+                    .map(|(_, text, leds)| (text.clone(), *leds))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+    // 取下一条要滚动的标题 (会标记为已播，所以要写锁)；没有标题时返回状态提示
+    pub fn rss_next(&self, name: &str) -> String {
+        let Ok(mut s) = self.0.write() else { return "RSS:Wait".into() };
+        match s.rss.get_mut(name) {
+            Some(h) => h.next().unwrap_or_else(|| h.status().to_string()),
+            None => "RSS:None".to_string(),
+        }
+    }
+    // 下一个日程，见 agenda.rs
+    pub fn agenda(&self) -> String {
+        let now = chrono::Local::now();
+        match self.0.read().map(|s| s.agenda.clone()) {
+            Ok(Ok(items)) => crate::agenda::next(&items, &now)
+                .map(|o| crate::agenda::display(o, &now))
+                .unwrap_or_else(|| "CAL:None".to_string()),
+            Ok(Err(e)) => e,
+            Err(_) => "CAL:Wait".to_string(),
+        }
+    }
+    // param = "目标[/模式]"，见 ping.rs
+    pub fn ping(&self, param: &str) -> String {
+        let (target, mode) = crate::ping::parse_param(param);
         self.0.read()
             .ok()
-            .and_then(|s| s.pings.get(target).cloned())
+            .and_then(|s| s.pings.get(target).map(|st| st.display(mode)))
             .unwrap_or_else(|| "P:Wait".to_string())
     }
+    pub fn ping_history(&self, target: &str) -> Vec<f64> {
+        self.0.read()
+            .ok()
+            .and_then(|s| s.ping_hist.get(target).map(Ring::values))
+            .unwrap_or_default()
+    }
+    // param = "local|up|both"，见 dns.rs
+    pub fn dns(&self, param: &str) -> String {
+        let mode = crate::dns::parse_param(param);
+        self.0.read().map(|s| s.dns.display(mode)).unwrap_or_else(|_| "DNS:Wait".into())
+    }
     pub fn sun(&self) -> String {
         self.0.read().map(|s| s.sun.clone()).unwrap_or_else(|_| "SUN:--".into())
     }
+    pub fn coords(&self) -> Option<(f64, f64)> {
+        self.0.read().ok().and_then(|s| s.location.coords)
+    }
+    // param = ""|"coords"，见 location.rs
+    pub fn location(&self, param: &str) -> String {
+        self.0.read().map(|s| crate::location::display(&s.location, param)).unwrap_or_else(|_| "LOC:--".into())
+    }
 }
 
-// 从 JSON 文本中提取数字字段 (轻量解析，无需完整反序列化)
-fn extract_json_number(text: &str, key: &str) -> Option<f64> {
-    let pattern = format!("\"{}\"", key);
-    let after = text.split(&pattern).nth(1)?;
-    let after_colon = after.split(':').nth(1)?;
-    let num_str: String = after_colon
-        .trim_start()
-        .chars()
-        .take_while(|c| c.is_ascii_digit() || *c == '.' || *c == '-')
-        .collect();
-    num_str.parse().ok()
+// 一轮 DNS 探测 (两个服务器并发查询，互不拖累)
+async fn probe_dns(name: &str, upstream: &str) -> DnsStatus {
+    async fn ask(server: &str, name: &str) -> crate::dns::DnsResult {
+        match crate::dns::server_addr(server).await {
+            Some(addr) => crate::dns::query(addr, name).await,
+            None => Err(crate::dns::DnsError::Io),
+        }
+    }
+    let (local, upstream) = tokio::join!(ask(crate::dns::LOCAL_SERVER, name), ask(upstream, name));
+    DnsStatus { local: Some(local), upstream: Some(upstream) }
 }
 
 // ==========================================
@@ -184,11 +175,20 @@ pub fn spawn_net_agent(args: Args, control: SharedControl) -> NetHandle {
 
     // 扫描 profile 中出现的模块，确定需要刷新哪些网络数据 (省 API 配额)
     let mut want_weather = false;
+    // 明日/空气质量/降水概率 (weather:tomorrow 等) 与降雨告警共用一份扩展数据
+    let mut want_extras = args.alert_rain > 0;
     let mut want_ip = false;
     let mut want_http = false;
     let mut want_stock = false;
     let mut want_sun = false;
+    let mut want_loc = false;
+    // 日程提醒开着时即使 profile 里没有 agenda 模块也要拉取
+    let mut want_agenda = args.alert_agenda > 0;
+    // 解析故障告警开着时即使 profile 里没有 dns 模块也要探测
+    let mut want_dns = args.alert_dns;
     let mut ping_targets: Vec<String> = Vec::new();
+    let mut http_names: Vec<String> = Vec::new();
+    let mut rss_names: Vec<String> = Vec::new();
 
     for p_str in &args.profile {
         for token in p_str.split_whitespace() {
@@ -198,51 +198,172 @@ pub fn spawn_net_agent(args: Args, control: SharedControl) -> NetHandle {
                 None => (name_with_param, ""),
             };
             match name {
+                "weather" if crate::weather::parse_extra(param).is_some() => want_extras = true,
                 "weather" => want_weather = true,
                 "ip" => want_ip = true,
                 "http_custom" => want_http = true,
-                "stock" => want_stock = true,
-                // sun 带手动经纬度参数时本地直算，无需代理；仅无参数时走 IP 定位
+                "http" if !http_names.iter().any(|n| n == param) => http_names.push(param.to_string()),
+                "rss" if !rss_names.iter().any(|n| n == param) => rss_names.push(param.to_string()),
+                "stock" | "ticker" => want_stock = true,
+                "dns" => want_dns = true,
+                "loc" => want_loc = true,
+                "agenda" => want_agenda = true,
+                // sun 带手动经纬度参数时本地直算，无需代理；仅无坐标时走 IP 定位
+                // (月相与位置无关，也不需要定位)
                 "sun" => {
-                    if param.is_empty() {
+                    let (mode, manual) = crate::sun::parse_param(param);
+                    if manual.is_none() && mode != crate::sun::SunMode::Moon {
                         want_sun = true;
                     }
                 }
                 "ping" => {
-                    let t = param.to_string();
+                    let t = crate::ping::parse_param(param).0.to_string();
                     if !ping_targets.contains(&t) {
                         ping_targets.push(t);
                     }
                 }
+                // 延迟曲线也需要后台探测
+                "spark" => {
+                    let (metric, t, _) = crate::history::parse_param(param);
+                    if metric == "ping" && !ping_targets.contains(&t) {
+                        ping_targets.push(t);
+                    }
+                }
                 _ => {}
             }
         }
     }
 
+    // 只刷新 profile 里引用到的具名 HTTP 源
+    let http_feeds: Vec<HttpFeed> = crate::http_source::parse_sources(&args.http_source)
+        .into_iter()
+        .filter(|src| http_names.contains(&src.name))
+        .map(HttpFeed::new)
+        .collect();
+    for name in &http_names {
+        if http_feeds.iter().any(|f| &f.src.name == name) {
+            if let Ok(mut s) = snapshot.write() { s.http_named.insert(name.clone(), "HTTP:Wait".into()); }
+        } else {
+            println!("⚠️ [HTTP 源] profile 引用了未配置的数据源: http:{}", name);
+        }
+    }
+
+    // RSS 同理: 只拉取 profile 里引用到的源
+    let rss_feeds: Vec<RssFeed> = crate::rss::parse_specs(&args.rss_feed)
+        .into_iter()
+        .filter(|f| rss_names.contains(&f.name))
+        .map(RssFeed::new)
+        .collect();
+    for name in &rss_names {
+        if rss_feeds.iter().any(|f| &f.spec.name == name) {
+            if let Ok(mut s) = snapshot.write() { s.rss.insert(name.clone(), Headlines::default()); }
+        } else {
+            println!("⚠️ [RSS] profile 引用了未配置的源: rss:{}", name);
+        }
+    }
+
+    if want_agenda && args.ics.is_empty() {
+        println!("⚠️ [日程] 用到了 agenda 模块或日程提醒，但没有配置 --ics");
+        want_agenda = false;
+    }
+
+    let tickers: Vec<TickerFeed> = if want_stock {
+        crate::ticker::parse_specs(&args.ticker, &args.stock_url).into_iter().map(TickerFeed::new).collect()
+    } else {
+        Vec::new()
+    };
+    if want_stock && tickers.is_empty() {
+        println!("⚠️ [行情] 用到了 stock/ticker 模块，但没有配置 --ticker / --stock-url，该模块将被跳过");
+    }
+
+    // 天气数据源按配置顺序故障切换 (天气与扩展数据共用)
+    let endpoints = Endpoints::with_overrides(&args.api_base);
+    let weather_chain = WeatherChain::new(&args.weather_source, &args.seniverse_key, &endpoints);
+    if want_weather || want_extras {
+        println!("🌤️ [天气] 数据源顺序: {}", weather_chain.names().join(" -> "));
+    }
+
     println!(
-        "🛰️ [网络代理] 启动后台刷新 (weather={}, ip={}, http={}, stock={}, sun={}, ping×{})",
-        want_weather, want_ip, want_http, want_stock, want_sun, ping_targets.len()
+        "🛰️ [网络代理] 启动后台刷新 (weather={}/{}, ip={}, http={}+{}, rss×{}, ticker×{}, sun={}, loc={}, agenda={}, dns={}, ping×{})",
+        want_weather, want_extras, want_ip, want_http, http_feeds.len(), rss_feeds.len(), tickers.len(), want_sun, want_loc, want_agenda, want_dns, ping_targets.len()
     );
 
-    tokio::spawn(async move {
-        let mut agent = NetAgent::new();
-        let mut last_stock: Option<Instant> = None;
-        let mut last_ping: Option<Instant> = None;
-        let mut last_sun_day: Option<chrono::NaiveDate> = None;
-        // 🚨 [v2.5.0] 公网 IP 变化提醒: 记录上一次的有效 IP
-        let mut last_good_ip: Option<String> = None;
-
-        loop {
-            // 各数据源的节流策略在 agent 方法内部 (天气 30min 缓存 + 120s 失败退避、
-            // IP 1h 缓存、HTTP 按用户配置)，这里放心高频调用，返回都是秒回
-            if want_weather {
-                let text = agent
-                    .get_smart_weather(&args.weather_city, &args.weather_source, &args.seniverse_key)
-                    .await;
-                if let Ok(mut s) = snapshot.write() { s.weather = text; }
+    // 🌐 出站请求统一入口: 共用客户端 + 代理/CA/出口绑定/超时 + 按主机限流退避 (见 http_client.rs)
+    let http = Http::new(HttpOptions::new(
+        &args.http_proxy,
+        &args.http_ca_file,
+        &args.http_bind,
+        &args.http_timeout,
+        &args.geoip_family,
+    ));
+
+    // 📍 定位: 手动配置 > 磁盘缓存 > IP 定位 (天气 auto 城市 / 日出日落 / loc 模块共用)
+    let locator: SharedLocator = Arc::new(tokio::sync::Mutex::new(Locator::new(LocatorConfig {
+        city: args.location_city.clone(),
+        lat: args.latitude,
+        lon: args.longitude,
+        urls: endpoints.geoip.clone(),
+        file: args.location_file.clone(),
+    })));
+
+    // ==========================================
+    // 🧵 [v2.6.0] 每类数据一个独立任务，各按自己的节奏刷新、互不等待:
+    // 以前所有刷新串行在同一个循环里，一个接口卡 30 秒超时，ping/IP/DNS 全部跟着停摆。
+    // 各任务的节流策略仍在各自内部 (天气 30min 缓存 + 120s 失败退避、IP 1h 缓存、
+    // HTTP 按用户配置)，循环里放心高频调用，返回都是秒回。
+    // ==========================================
+    let args = Arc::new(args);
+
+    // 绑定的出口网卡换了地址 (PPPoE 重拨) 时重建客户端
+    {
+        let http = http.clone();
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(Duration::from_secs(60)).await;
+                http.refresh().await;
+            }
+        });
+    }
+
+    // 🌤️ 天气 + 扩展天气数据 (共用数据源链与定位)
+    if want_weather || want_extras {
+        let (args, snapshot, control) = (Arc::clone(&args), Arc::clone(&snapshot), control.clone());
+        let mut agent = WeatherAgent::new(http.clone(), Arc::clone(&locator));
+        tokio::spawn(async move {
+            let mut rain_alarm = RainAlarm::default();
+            loop {
+                if want_weather {
+                    let report = agent.get_smart_weather(&args.weather_city, &weather_chain).await;
+                    if let Ok(mut s) = snapshot.write() { s.weather = report; }
+                }
+
+                // 🌦️ 扩展天气数据 (同样 30min 缓存 + 120s 失败退避)
+                if want_extras {
+                    let extras = agent.get_weather_extras(&args.weather_city, &weather_chain).await;
+                    // ☔ 降雨提醒: 未来一小时降水概率达到阈值时播报一次
+                    if args.alert_rain > 0 {
+                        let pct = extras.rain_next_hour(chrono::Local::now().naive_local());
+                        if let Some(text) = rain_alarm.update(pct, args.alert_rain) {
+                            println!("🚨 [告警] {}", text);
+                            if let Ok(mut st) = control.lock() {
+                                st.pending_alerts.push(Alert { text, blink: false, secs: 6 });
+                            }
+                        }
+                    }
+                    if let Ok(mut s) = snapshot.write() { s.weather_extras = extras; }
+                }
+                tokio::time::sleep(Duration::from_secs(5)).await;
             }
+        });
+    }
 
-            if want_ip {
+    if want_ip {
+        let (args, snapshot, control) = (Arc::clone(&args), Arc::clone(&snapshot), control.clone());
+        let mut agent = IpAgent::new(http.clone());
+        tokio::spawn(async move {
+            // 🚨 [v2.5.0] 公网 IP 变化提醒: 记录上一次的有效 IP
+            let mut last_good_ip: Option<String> = None;
+            loop {
                 let text = agent.get_public_ip(&args.ip_url).await;
 
                 // 🚨 [v2.5.0] 公网 IP 变化提醒 (仅在两次都是有效 IP 且不同时播报;
@@ -264,39 +385,199 @@ pub fn spawn_net_agent(args: Args, control: SharedControl) -> NetHandle {
                 }
 
                 if let Ok(mut s) = snapshot.write() { s.ip = text; }
+                tokio::time::sleep(Duration::from_secs(5)).await;
             }
+        });
+    }
 
-            if want_http {
+    if want_http {
+        let (args, snapshot) = (Arc::clone(&args), Arc::clone(&snapshot));
+        let mut agent = HttpTextAgent::new(http.clone());
+        tokio::spawn(async move {
+            loop {
                 let text = agent
                     .get_http_text(&args.custom_http_url, "", args.http_length, args.http_cache_secs)
                     .await;
                 if let Ok(mut s) = snapshot.write() { s.http_text = text; }
+                tokio::time::sleep(Duration::from_secs(5)).await;
+            }
+        });
+    }
+
+    // 🔗 具名 HTTP 源: 每个源一个任务，各自按自己的间隔刷新 (失败 60 秒后重试，期间保留旧值)
+    for mut feed in http_feeds {
+        let (args, snapshot, http) = (Arc::clone(&args), Arc::clone(&snapshot), http.clone());
+        tokio::spawn(async move {
+            loop {
+                if feed.is_due() {
+                    feed.refresh(&http, args.http_cache_secs, args.http_length).await;
+                    if let Ok(mut s) = snapshot.write() {
+                        s.http_named.insert(feed.src.name.clone(), feed.display());
+                    }
+                }
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+        });
+    }
+
+    // 📰 RSS: 每个源一个任务，按各自间隔拉取 (失败 2 分钟后重试，期间保留已有标题)
+    for mut feed in rss_feeds {
+        let (snapshot, http) = (Arc::clone(&snapshot), http.clone());
+        tokio::spawn(async move {
+            loop {
+                if feed.is_due() {
+                    let fresh = feed.refresh(&http).await;
+                    if let Ok(mut s) = snapshot.write() {
+                        let h = s.rss.entry(feed.spec.name.clone()).or_default();
+                        match fresh {
+                            Some(items) => h.merge(items, feed.keep()),
+                            None => h.mark_failed(),
+                        }
+                    }
+                }
+                tokio::time::sleep(Duration::from_secs(1)).await;
             }
+        });
+    }
+
+    // 📅 日程: 各日历按 --ics-interval 拉取 (失败 2 分钟后重试，期间沿用上次解析结果)，
+    // 每 30 秒重算一次即将开始的事件并检查会前提醒
+    if want_agenda {
+        let (args, snapshot, control, http) = (Arc::clone(&args), Arc::clone(&snapshot), control.clone(), http.clone());
+        tokio::spawn(async move {
+            let mut calendars: Vec<(Option<Calendar>, Option<Instant>)> = vec![(None, None); args.ics.len()];
+            let mut alarm = AgendaAlarm::default();
+            loop {
+                for (url, (cal, next_due)) in args.ics.iter().zip(calendars.iter_mut()) {
+                    if next_due.is_some_and(|t| Instant::now() < t) {
+                        continue;
+                    }
+                    let wait = match http.fetch_text("ics", url).await {
+                        Some(text) => {
+                            *cal = Some(crate::agenda::parse(&text));
+                            args.ics_interval.max(60)
+                        }
+                        None => {
+                            println!("⚠️ [日程] 获取失败: {}", url);
+                            120
+                        }
+                    };
+                    *next_due = Some(Instant::now() + Duration::from_secs(wait));
+                }
 
-            // 股票无内部缓存，代理层节流 30 秒
-            if want_stock && last_stock.map_or(true, |t| t.elapsed() >= Duration::from_secs(30)) {
-                let (text, _) = agent.get_stock_trend(&args.stock_url).await;
-                if let Ok(mut s) = snapshot.write() { s.stock = text; }
-                last_stock = Some(Instant::now());
+                let now = chrono::Local::now();
+                let loaded: Vec<&Calendar> = calendars.iter().filter_map(|(c, _)| c.as_ref()).collect();
+                let result = if loaded.is_empty() {
+                    Err("CAL:Err".to_string())
+                } else {
+                    let mut items: Vec<Occurrence> = loaded
+                        .iter()
+                        .flat_map(|c| c.upcoming(now.with_timezone(&chrono::Utc), chrono::Duration::days(60)))
+                        .collect();
+                    items.sort_by_key(|o| o.start);
+                    items.truncate(20);
+                    Ok(items)
+                };
+
+                if let Ok(items) = &result {
+                    if args.alert_agenda > 0 && crate::timesync::synced() {
+                        for text in alarm.update(items, &now, args.alert_agenda) {
+                            println!("🚨 [告警] 日程提醒: {}", text);
+                            if let Ok(mut st) = control.lock() {
+                                st.pending_alerts.push(Alert { text, blink: false, secs: 8 });
+                            }
+                        }
+                    }
+                }
+                if let Ok(mut s) = snapshot.write() { s.agenda = result; }
+                tokio::time::sleep(Duration::from_secs(30)).await;
             }
+        });
+    }
 
-            // 延迟探测每 10 秒一轮
-            if !ping_targets.is_empty()
-                && last_ping.map_or(true, |t| t.elapsed() >= Duration::from_secs(10))
-            {
-                for target in &ping_targets {
-                    let result = agent.get_tcp_ping(target).await;
+    // 📈 行情: 每个品种一个任务 (默认 30 秒)，快照里按配置顺序占好位置，各自只更新自己那一格
+    if let Ok(mut s) = snapshot.write() {
+        s.quotes = tickers
+            .iter()
+            .map(|t| {
+                let (text, leds) = t.status();
+                (t.spec.symbol.clone(), text, leds)
+            })
+            .collect();
+    }
+    for (idx, mut t) in tickers.into_iter().enumerate() {
+        let (snapshot, http) = (Arc::clone(&snapshot), http.clone());
+        tokio::spawn(async move {
+            loop {
+                if t.is_due() {
+                    t.refresh(&http).await;
+                    let (text, leds) = t.status();
                     if let Ok(mut s) = snapshot.write() {
-                        s.pings.insert(target.clone(), result);
+                        if let Some(q) = s.quotes.get_mut(idx) {
+                            q.1 = text;
+                            q.2 = leds;
+                        }
+                    }
+                }
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+        });
+    }
+
+    // 延迟探测: 每个目标一个任务，每 10 秒一轮 (每轮多次 ICMP 探测，见 ping.rs)
+    for target in ping_targets {
+        let snapshot = Arc::clone(&snapshot);
+        tokio::spawn(async move {
+            loop {
+                let round = crate::ping::probe_round(&target).await;
+                if let Ok(mut s) = snapshot.write() {
+                    let stats = s.pings.entry(target.clone()).or_default();
+                    stats.push_round(&round);
+                    let last = stats.last().unwrap_or(f64::NAN);
+                    s.ping_hist.entry(target.clone()).or_default().push(last);
+                }
+                tokio::time::sleep(Duration::from_secs(10)).await;
+            }
+        });
+    }
+
+    // 🔎 DNS 健康探测每 15 秒一轮: 本机与上游各查一次同一个域名
+    if want_dns {
+        let (args, snapshot, control) = (Arc::clone(&args), Arc::clone(&snapshot), control.clone());
+        tokio::spawn(async move {
+            let mut dns_alarm = DnsAlarm::default();
+            loop {
+                let status = probe_dns(&args.dns_name, &args.dns_upstream).await;
+                if args.alert_dns {
+                    let route_up = crate::monitor::has_default_route();
+                    if let Some((blink, text)) = dns_alarm.update(&status, route_up) {
+                        println!("🚨 [告警] {}", text);
+                        if let Ok(mut st) = control.lock() {
+                            st.pending_alerts.push(Alert { text, blink, secs: 5 });
+                        }
                     }
                 }
-                last_ping = Some(Instant::now());
+                if let Ok(mut s) = snapshot.write() { s.dns = status; }
+                tokio::time::sleep(Duration::from_secs(15)).await;
             }
+        });
+    }
 
-            // 🌅 日出日落: 拿到 IP 定位坐标后本地计算，跨天自动重算
-            if want_sun {
-                agent.ensure_location().await;
-                if let Some((lat, lon)) = agent.coords {
+    // 📍 定位 (已定位且缓存未过期时秒回) + 🌅 日出日落: 拿到坐标后本地计算，跨天自动重算
+    if want_sun || want_loc {
+        let snapshot = Arc::clone(&snapshot);
+        tokio::spawn(async move {
+            let mut last_sun_day: Option<chrono::NaiveDate> = None;
+            loop {
+                let location = {
+                    let mut loc = locator.lock().await;
+                    loc.ensure(&http).await;
+                    loc.location()
+                };
+                if let Ok(mut s) = snapshot.write() { s.location = location.clone(); }
+
+                // 时间未校准时不算 (否则会按错误日期算一天，直到日期变化才更正)
+                if let (true, true, Some((lat, lon))) = (want_sun, crate::timesync::synced(), location.coords) {
                     let today = chrono::Local::now().date_naive();
                     if last_sun_day != Some(today) {
                         let text = crate::sun::today_string(lat, lon);
@@ -304,140 +585,33 @@ pub fn spawn_net_agent(args: Args, control: SharedControl) -> NetHandle {
                         last_sun_day = Some(today);
                     }
                 }
+                tokio::time::sleep(Duration::from_secs(5)).await;
             }
-
-            tokio::time::sleep(Duration::from_secs(5)).await;
-        }
-    });
+        });
+    }
 
     handle
 }
 
-// ==========================================
-// 🧠 内部代理：持有 HTTP 客户端与各类缓存/节流状态
-// (以下方法整体从旧 SystemMonitor 迁移而来，逻辑不变)
-// ==========================================
-struct NetAgent {
-    http_client: Client,
+const WEATHER_TTL_SECS: u64 = 1800;
+const WEATHER_RETRY_SECS: u64 = 120;
 
-    cached_weather: String,
-    last_weather_time: Instant,
-    // 上次“尝试”请求天气的时间 (无论成败)，用于失败退避；None = 从未尝试过
-    last_weather_attempt: Option<Instant>,
-
-    cached_ip: String,
-    last_ip_time: Instant,
-    // 🌟 [修复] IP 查询失败退避 (以前断网时每 5 秒 tick 都发起 30 秒超时请求，
-    // 串行拖慢 ping/日出日落等所有后台刷新)
-    last_ip_attempt: Option<Instant>,
+// 定位在天气任务 (auto 城市) 与日出日落任务之间共享
+type SharedLocator = Arc<tokio::sync::Mutex<Locator>>;
 
+// ==========================================
+// 🧠 各任务的内部代理：持有各自的缓存/节流状态
+// (方法整体从旧 SystemMonitor 迁移而来；v2.6.0 按任务拆成三个，逻辑不变)
+// ==========================================
+struct HttpTextAgent {
+    http: Http,
     http_cache_text: String,
     http_cache_time: Instant,
-
-    // 天气 api 获取定位城市
-    auto_location: String,
-    // 🌟 [v2.4.0] IP 定位经纬度 (供日出日落计算)
-    coords: Option<(f64, f64)>,
-    // 定位失败的重试节流
-    last_geo_attempt: Option<Instant>,
-
-    last_stock_price: f64,
 }
 
-impl NetAgent {
-    fn new() -> Self {
-        let client = Client::builder()
-            .user_agent("Mozilla/5.0 (Athena-LED Router)")
-            .timeout(Duration::from_secs(30))
-            .build()
-            .unwrap_or_default();
-
-        Self {
-            http_client: client,
-            cached_weather: "Wait...".to_string(),
-            last_weather_time: Instant::now(),
-            last_weather_attempt: None,
-            cached_ip: "IP:Err".to_string(),
-            last_ip_time: Instant::now(),
-            last_ip_attempt: None,
-            http_cache_text: String::new(),
-            http_cache_time: Instant::now(),
-            auto_location: String::new(),
-            coords: None,
-            last_geo_attempt: None,
-            last_stock_price: 0.0,
-        }
-    }
-
-    // ==========================================
-    // 🌍 IP 自动定位 (双源: 紫辰 主 / ip-api.com 备)
-    // 同时提取城市名 (天气用) 与经纬度 (日出日落用)；
-    // 失败后 10 分钟内不重试，防止无谓请求
-    // ==========================================
-    async fn ensure_location(&mut self) {
-        // 城市和坐标都拿到了就不再请求
-        if !self.auto_location.is_empty() && self.coords.is_some() {
-            return;
-        }
-        if let Some(last) = self.last_geo_attempt {
-            if last.elapsed() < Duration::from_secs(600) {
-                return;
-            }
-        }
-        self.last_geo_attempt = Some(Instant::now());
-
-        let client = reqwest::Client::builder()
-            .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) Athena-LED/2.0")
-            .timeout(std::time::Duration::from_secs(5))
-            .build()
-            .unwrap_or_default();
-
-        let geo_sources = [
-            "http://app.zichen.zone/api/geoip/api.php",
-            "http://ip-api.com/json/?lang=zh-CN&fields=city,lat,lon",
-        ];
-        for geo_url in geo_sources {
-            match client.get(geo_url).send().await {
-                Ok(resp) => {
-                    if let Ok(text) = resp.text().await {
-                        #[cfg(debug_assertions)]
-                        println!("🌍 [定位调试] {} 原始返回: {}", geo_url, text);
-
-                        // 提取 city (两个接口都是 JSON 且都有 "city" 字段)
-                        if self.auto_location.is_empty() {
-                            if let Some(city_part) = text.split("\"city\"").nth(1) {
-                                if let Some(city) = city_part.split('"').nth(1) {
-                                    let trimmed = city.trim().to_string();
-                                    if !trimmed.is_empty() {
-                                        self.auto_location = trimmed;
-                                        #[cfg(debug_assertions)]
-                                        println!("✅ [定位成功] 城市: {}", self.auto_location);
-                                    }
-                                }
-                            }
-                        }
-
-                        // 🌟 提取经纬度 (ip-api 一定有; 其他源有则取)
-                        if self.coords.is_none() {
-                            if let (Some(lat), Some(lon)) = (
-                                extract_json_number(&text, "lat"),
-                                extract_json_number(&text, "lon"),
-                            ) {
-                                if (-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&lon) {
-                                    self.coords = Some((lat, lon));
-                                    println!("✅ [定位] 坐标: {:.2},{:.2}", lat, lon);
-                                }
-                            }
-                        }
-                    }
-                }
-                Err(e) => println!("❌ [定位失败] {} 请求报错: {}", geo_url, e),
-            }
-            // 城市和坐标都齐了就提前收工
-            if !self.auto_location.is_empty() && self.coords.is_some() {
-                break;
-            }
-        }
+impl HttpTextAgent {
+    fn new(http: Http) -> Self {
+        Self { http, http_cache_text: String::new(), http_cache_time: Instant::now() }
     }
 
     // --- 通用 HTTP 文本获取 ---
@@ -450,28 +624,18 @@ impl NetAgent {
             return self.http_cache_text.clone();
         }
 
-        // 设置超时，防止卡死
-        let client = reqwest::Client::builder()
-            .timeout(std::time::Duration::from_secs(3))
-            .build()
-            .unwrap_or(self.http_client.clone());
-
-        match client.get(url).send().await {
+        // 短超时 (默认 3 秒)，防止卡死
+        match self.http.get("http_custom", url).await {
             Ok(resp) => {
-                match resp.text().await {
-                    Ok(text) => {
-                        let clean_text = text.trim();
+                let clean_text = resp.text().trim();
 
-                        // [关键修复] 使用 chars() 迭代器进行安全的字符截断
-                        let truncated: String = clean_text.chars().take(max_len).collect();
+                // [关键修复] 使用 chars() 迭代器进行安全的字符截断
+                let truncated: String = clean_text.chars().take(max_len).collect();
 
-                        let result = format!("{}{}", prefix, truncated);
-                        self.http_cache_text = result.clone();
-                        self.http_cache_time = Instant::now();
-                        result
-                    }
-                    Err(_) => format!("{}Err", prefix),
-                }
+                let result = format!("{}{}", prefix, truncated);
+                self.http_cache_text = result.clone();
+                self.http_cache_time = Instant::now();
+                result
             }
             Err(_) => {
                 // 请求失败时回退旧缓存
@@ -484,12 +648,26 @@ impl NetAgent {
         }
     }
 
+}
+
+struct IpAgent {
+    http: Http,
+    cached_ip: String,
+    last_ip_time: Instant,
+    // 🌟 [修复] IP 查询失败退避 (以前断网时每 5 秒 tick 都发起 30 秒超时请求，
+    // 串行拖慢 ping/日出日落等所有后台刷新)
+    last_ip_attempt: Option<Instant>,
+}
+
+impl IpAgent {
+    fn new(http: Http) -> Self {
+        Self { http, cached_ip: "IP:Err".to_string(), last_ip_time: Instant::now(), last_ip_attempt: None }
+    }
+
     async fn get_public_ip(&mut self, ip_url: &str) -> String {
         // [缓存策略] IP 变化很少，缓存 60 分钟
-        if self.last_ip_time.elapsed() < Duration::from_secs(3600) {
-            if !self.cached_ip.contains("Err") {
-                return self.cached_ip.clone();
-            }
+        if self.last_ip_time.elapsed() < Duration::from_secs(3600) && !self.cached_ip.contains("Err") {
+            return self.cached_ip.clone();
         }
 
         // 🌟 [修复] 失败退避: 距上次尝试不足 120 秒不再重复请求 (与天气策略一致)
@@ -505,16 +683,14 @@ impl NetAgent {
 
         let mut new_ip = "IP:Err".to_string();
 
-        match self.http_client.get(ip_url).send().await {
+        match self.http.get("ip", ip_url).await {
             Ok(resp) => {
-                if let Ok(text) = resp.text().await {
-                    let re = Regex::new(r"\b(?:\d{1,3}\.){3}\d{1,3}\b").unwrap();
-                    if let Some(mat) = re.find(&text) {
-                        new_ip = format!("IP:{}", mat.as_str());
-                    }
+                let re = Regex::new(r"\b(?:\d{1,3}\.){3}\d{1,3}\b").unwrap();
+                if let Some(mat) = re.find(resp.text()) {
+                    new_ip = format!("IP:{}", mat.as_str());
                 }
             }
-            Err(e) => println!("IP Request error: {:?}", e),
+            Err(e) => println!("IP Request error: {}", e),
         }
 
         // [更新缓存]
@@ -530,325 +706,229 @@ impl NetAgent {
         }
     }
 
-    async fn get_stock_trend(&mut self, url: &str) -> (String, u8) {
-        if url.is_empty() { return (String::new(), 0); }
+}
 
-        match self.http_client.get(url).send().await {
-            Ok(resp) => {
-                if let Ok(json_val) = resp.json::<Value>().await {
-                    // 尝试找 price/last/close 字段
-                    let price_opt = json_val["price"].as_f64()
-                        .or_else(|| json_val["price"].as_str().and_then(|s| s.parse::<f64>().ok()))
-                        .or_else(|| json_val["last"].as_f64())
-                        .or_else(|| json_val["close"].as_f64());
-
-                    if let Some(current_price) = price_opt {
-                        let mut flag = 2;
-
-                        if self.last_stock_price > 0.0 {
-                            if current_price > self.last_stock_price {
-                                flag = 4; // 涨 -> 上箭头
-                            } else if current_price < self.last_stock_price {
-                                flag = 8; // 跌 -> 下箭头
-                            }
-                        }
+struct WeatherAgent {
+    http: Http,
 
-                        self.last_stock_price = current_price;
+    cached_weather: Option<WeatherReport>,
+    weather_error: String, // 从未成功过时显示的错误提示 (初始为占位 "Wait...")
+    last_weather_time: Instant,
+    // 上次“尝试”请求天气的时间 (无论成败)，用于失败退避；None = 从未尝试过
+    last_weather_attempt: Option<Instant>,
 
-                        let text = if current_price > 1000.0 {
-                            format!("{:.0}", current_price)
-                        } else {
-                            format!("{:.2}", current_price)
-                        };
+    // 🌦️ 扩展天气数据 (缓存/退避策略同上)
+    cached_extras: WeatherExtras,
+    last_extras_time: Option<Instant>,
+    last_extras_attempt: Option<Instant>,
 
-                        return (text, flag);
-                    }
-                }
-            }
-            Err(_) => {}
-        }
-        ("Err".to_string(), 0)
-    }
+    // 📍 [v2.6.0] 定位 (天气 auto 城市，见 location.rs)
+    locator: SharedLocator,
 
-    // --- 网络延迟 (TCP 连接耗时近似) ---
-    async fn get_tcp_ping(&self, target: &str) -> String {
-        let target = target.trim();
-        let addr = if target.is_empty() {
-            "223.5.5.5:80".to_string()
-        } else if target.contains(':') {
-            target.to_string()
-        } else {
-            format!("{}:80", target)
-        };
+    // 天气与扩展数据的缓存时长 / 失败退避间隔 (默认 30min / 120s)
+    weather_ttl: Duration,
+    weather_retry: Duration,
+}
 
-        let start = Instant::now();
-        match tokio::time::timeout(
-            Duration::from_secs(2),
-            tokio::net::TcpStream::connect(&addr),
-        ).await {
-            Ok(Ok(_stream)) => format!("P:{}ms", start.elapsed().as_millis()),
-            _ => "P:Err".to_string(),
+impl WeatherAgent {
+    fn new(http: Http, locator: SharedLocator) -> Self {
+        Self {
+            http,
+            cached_weather: None,
+            weather_error: "Wait...".to_string(),
+            last_weather_time: Instant::now(),
+            last_weather_attempt: None,
+            cached_extras: WeatherExtras::default(),
+            last_extras_time: None,
+            last_extras_attempt: None,
+            locator,
+            weather_ttl: Duration::from_secs(WEATHER_TTL_SECS),
+            weather_retry: Duration::from_secs(WEATHER_RETRY_SECS),
         }
     }
 
-    // 天气结果是否为有效数据: 所有失败分支都以 "W:" 开头 (W:Err/W:NoCity/W:GeoNet/W:NoKey...)，
-    // 正常数据以天气图标开头 ("☀ 25℃ 20-30")；初始占位为 "Wait..."
-    // 🌟 [修复] 以前只查 Err/Wait 关键词，W:NoCity 这类错误会被当有效数据缓存 30 分钟
-    fn weather_is_good(text: &str) -> bool {
-        !text.starts_with("W:") && !text.contains("Wait")
-    }
-
-    // --- [入口] 统一智能天气接口 ---
-    async fn get_smart_weather(&mut self, location: &str, source: &str, key: &str) -> String {
+    // --- [入口] 统一智能天气接口: 按配置顺序逐个尝试数据源 (见 weather_provider.rs) ---
+    async fn get_smart_weather(
+        &mut self,
+        location: &str,
+        chain: &WeatherChain,
+    ) -> Result<WeatherReport, String> {
         // 1. [缓存检查] 缓存有效且未过期 (30分钟)，直接返回
-        let cache_good = Self::weather_is_good(&self.cached_weather);
-        if cache_good && self.last_weather_time.elapsed() < Duration::from_secs(1800) {
-            return self.cached_weather.clone();
+        if let Some(r) = &self.cached_weather {
+            if self.last_weather_time.elapsed() < self.weather_ttl {
+                return Ok(r.clone());
+            }
         }
 
         // 2. 失败退避：距上次尝试不足 120 秒时不再重复请求，
         // 防止持续失败时被服务端风控/封禁 (“天气一天后消失”的元凶)
         if let Some(last_try) = self.last_weather_attempt {
-            if last_try.elapsed() < Duration::from_secs(120) {
-                return self.cached_weather.clone();
+            if last_try.elapsed() < self.weather_retry {
+                return self.cached_weather.clone().ok_or_else(|| self.weather_error.clone());
             }
         }
         self.last_weather_attempt = Some(Instant::now());
 
-        // ==========================================
-        // 🌟 IP 自动定位 (双源加固版, 与日出日落共享 ensure_location)
-        // ==========================================
-        let mut target_location = location.to_string();
-        if target_location.to_lowercase() == "auto" || target_location.is_empty() {
-            self.ensure_location().await;
-
-            // 兜底策略
-            target_location = if self.auto_location.is_empty() {
-                #[cfg(debug_assertions)]
-                println!("⚠️ [定位兜底] 启用默认城市: 北京");
-                "北京".to_string()
-            } else {
-                self.auto_location.clone()
-            };
-        }
-
-        // 默认数据源接管：用户没选或填了 auto，强制使用 uapis
-        let target_source = if source.is_empty() || source == "auto" {
-            "uapis"
-        } else {
-            source
-        };
-
-        let result = match target_source {
-            "seniverse" => self.get_weather_from_seniverse(&target_location, key).await,
-            "openmeteo" => self.get_weather_from_open_meteo(&target_location).await,
-            "uapis" => self.get_weather_from_uapis(&target_location).await,
-            "wttr" => self.get_weather_from_wttr(&target_location).await,
-            _ => self.get_weather_from_uapis(&target_location).await,
+        let Some(target_location) = self.resolve_city(location).await else {
+            // 没配城市且定位失败: 如实提示 (以前会悄悄显示北京的天气)
+            self.weather_error = "W:NoLoc".to_string();
+            return self.cached_weather.clone().ok_or_else(|| self.weather_error.clone());
         };
 
         // 3. [更新缓存]
-        if Self::weather_is_good(&result) {
-            self.cached_weather = result.clone();
-            self.last_weather_time = Instant::now();
-            result
-        } else if cache_good {
-            // 请求失败但手里有旧数据：回退显示旧数据 (宁可旧，不可无)
-            println!("⚠️ [天气] 刷新失败 ({})，暂时沿用上次数据: {}", result, self.cached_weather);
-            self.cached_weather.clone()
-        } else {
-            // 从未成功过 (刚开机就断网等)，只能如实返回错误提示
-            result
+        match chain.current(&self.http, &target_location).await {
+            Ok((report, source)) => {
+                println!(
+                    "🌤️ [天气] {} ({}): {} 湿度 {:?}% 风速 {:?}km/h",
+                    target_location, source, report.display(Units::default()), report.humidity, report.wind
+                );
+                self.cached_weather = Some(report.clone());
+                self.last_weather_time = Instant::now();
+                Ok(report)
+            }
+            Err(e) => {
+                self.weather_error = e.clone();
+                match &self.cached_weather {
+                    // 请求失败但手里有旧数据：回退显示旧数据 (宁可旧，不可无)
+                    Some(old) => {
+                        println!("⚠️ [天气] 刷新失败 ({})，暂时沿用上次数据: {}", e, old.display(Units::default()));
+                        Ok(old.clone())
+                    }
+                    // 从未成功过 (刚开机就断网等)，只能如实返回错误提示
+                    None => Err(e),
+                }
+            }
         }
     }
 
-    // --- [通道1] uapis.cn (适合国内，支持中文名) ---
-    async fn get_weather_from_uapis(&self, city: &str) -> String {
-        let url = format!("https://uapis.cn/api/v1/misc/weather?city={}&forecast=true", city);
-
-        match self.http_client.get(&url).send().await {
-            Ok(resp) => {
-                if let Ok(data) = resp.json::<WeatherResponse>().await {
-                    let temp = data.temperature;
-                    let max = data.temp_max.unwrap_or(temp);
-                    let min = data.temp_min.unwrap_or(temp);
-
-                    let desc = data.weather;
-                    let icon = if desc.contains("雨") { "☂" }
-                    else if desc.contains("雪") { "❄" }
-                    else if desc.contains("云") || desc.contains("阴") || desc.contains("雾") || desc.contains("霾") { "☁" }
-                    else { "☀" };
-
-                    return format!("{} {:.0}℃ {:.0}-{:.0}", icon, temp, min, max);
-                }
-            }
-            Err(_) => {}
+    // ==========================================
+    // 🌟 天气城市: 配了具体城市直接用，auto/留空走定位子系统 (失败返回 None)
+    // ==========================================
+    async fn resolve_city(&mut self, location: &str) -> Option<String> {
+        if !location.eq_ignore_ascii_case("auto") && !location.is_empty() {
+            return Some(location.to_string());
         }
-        "W:Err(U)".to_string()
+        let mut loc = self.locator.lock().await;
+        loc.ensure(&self.http).await;
+        loc.city()
     }
 
-    // --- [通道2] Wttr ---
-    async fn get_weather_from_wttr(&self, city: &str) -> String {
-        let url = format!("https://wttr.in/{}?format=j1", city);
-        #[cfg(debug_assertions)]
-        println!("DEBUG: Requesting Wttr: {}", url);
+    // ==========================================
+    // 🌦️ [v2.6.0] 扩展天气数据: 明日预报 / 空气质量 / 逐小时降水概率
+    // 按数据源顺序逐个补齐 (心知有 key 时提供，免费版缺逐小时)，最后由 Open-Meteo (免 key) 兜底
+    // ==========================================
+    async fn get_weather_extras(&mut self, location: &str, chain: &WeatherChain) -> WeatherExtras {
+        if !self.cached_extras.is_empty()
+            && self.last_extras_time.is_some_and(|t| t.elapsed() < self.weather_ttl)
+        {
+            return self.cached_extras.clone();
+        }
+        if self.last_extras_attempt.is_some_and(|t| t.elapsed() < self.weather_retry) {
+            return self.cached_extras.clone();
+        }
+        self.last_extras_attempt = Some(Instant::now());
 
-        match self.http_client.get(&url).send().await {
-            Ok(resp) => {
-                // 检查 HTTP 状态码 (wttr 经常封 IP 返回 429 或 503)
-                if !resp.status().is_success() {
-                    #[cfg(debug_assertions)]
-                    println!("DEBUG: Wttr failed status: {}", resp.status());
-                    return format!("W:Err({})", resp.status().as_u16());
-                }
+        let Some(city) = self.resolve_city(location).await else {
+            return self.cached_extras.clone();
+        };
+        let extras = chain.extras(&self.http, &city).await;
 
-                match resp.json::<WttrResult>().await {
-                    Ok(json) => {
-                        if let (Some(curr), Some(daily)) = (json.current_condition.first(), json.weather.first()) {
-                            let temp = &curr.temp_C;
-                            let max = &daily.maxtempC;
-                            let min = &daily.mintempC;
-
-                            let desc = curr.weatherDesc.first()
-                                .map(|d| d.value.to_lowercase())
-                                .unwrap_or_else(|| "unknown".to_string());
-
-                            let icon = if desc.contains("rain") || desc.contains("shower") || desc.contains("drizzle") { "☂" }
-                            else if desc.contains("snow") || desc.contains("ice") || desc.contains("hail") { "❄" }
-                            else if desc.contains("thunder") { "⚡" }
-                            else if desc.contains("cloud") || desc.contains("overcast") { "☁" }
-                            else if desc.contains("mist") || desc.contains("fog") { "🌫" }
-                            else { "☀" };
-
-                            return format!("{} {}℃ {}-{}", icon, temp, min, max);
-                        }
-                        println!("DEBUG: Wttr JSON structure mismatch (empty arrays)");
-                        "W:DataErr".to_string()
-                    }
-                    Err(e) => {
-                        println!("DEBUG: Wttr JSON Parse Error: {:?}", e);
-                        "W:JsonErr".to_string()
-                    }
-                }
-            }
-            Err(e) => {
-                println!("DEBUG: Wttr Network Error: {:?}", e);
-                "W:NetErr".to_string()
-            }
+        if !extras.is_empty() {
+            self.cached_extras = extras;
+            self.last_extras_time = Some(Instant::now());
         }
+        // 失败时沿用旧数据 (宁可旧，不可无)
+        self.cached_extras.clone()
     }
+}
 
-    // --- [通道3] 心知天气 ---
-    async fn get_weather_from_seniverse(&self, location: &str, key: &str) -> String {
-        // [安全] key 为空直接提示，不再依赖内置的公共测试 key
-        if key.trim().is_empty() {
-            return "W:NoKey".to_string();
-        }
-        let url = format!(
-            "https://api.seniverse.com/v3/weather/daily.json?key={}&location={}&language=en&unit=c&start=0&days=1",
-            key, location
-        );
+// ==========================================
+// 🧪 单元测试 (天气缓存 / 失败退避 / 旧数据回退 / IP 定位，接口全部指向本地 HTTP 桩)
+// ==========================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_http::{fixtures, http, MockServer, Reply};
+
+    const UAPIS: &str = "/api/v1/misc/weather";
+
+    async fn setup() -> (MockServer, WeatherAgent, WeatherChain) {
+        let m = MockServer::start().await;
+        let ep = Endpoints::all(&m.base);
+        let locator = Locator::new(LocatorConfig {
+            city: String::new(),
+            lat: None,
+            lon: None,
+            urls: ep.geoip.clone(),
+            file: String::new(),
+        });
+        let agent = WeatherAgent::new(http(), Arc::new(tokio::sync::Mutex::new(locator)));
+        let chain = WeatherChain::new("uapis", "", &ep);
+        (m, agent, chain)
+    }
 
-        match self.http_client.get(&url).send().await {
-            Ok(resp) => {
-                if let Ok(json) = resp.json::<SeniverseResponse>().await {
-                    if let Some(daily) = json.results.get(0).and_then(|r| r.daily.get(0)) {
-                        let max = daily.high.parse::<f64>().unwrap_or(0.0);
-                        let min = daily.low.parse::<f64>().unwrap_or(0.0);
-                        // 取平均值当作当前温度 (免费版日预报不返回实时温度)
-                        let temp = (max + min) / 2.0;
-
-                        // 图标代码: 0-3 晴, 4-9 云, 10-19 雨, 20-29 雪
-                        let code = daily.code_day.parse::<i32>().unwrap_or(99);
-                        let icon = match code {
-                            0..=3 => "☀",
-                            4..=9 => "☁",
-                            10..=19 => "☂",
-                            20..=29 => "❄",
-                            30..=36 => "☁", // 雾霾风
-                            _ => "☀",
-                        };
-
-                        return format!("{} {:.0}℃ {:.0}-{:.0}", icon, temp, min, max);
-                    }
-                }
-            }
-            Err(_) => {}
-        }
-        "W:Err(S)".to_string()
-    }
-
-    // --- [通道4] Open-Meteo ---
-    async fn get_weather_from_open_meteo(&self, city: &str) -> String {
-        // 清洗城市名：IP 定位返回的多是 "北京市"/"朝阳区" 这类带行政后缀的名字
-        let clean_city = city
-            .trim()
-            .trim_end_matches('市')
-            .trim_end_matches('区')
-            .trim_end_matches('县');
-        let clean_city = if clean_city.is_empty() { city.trim() } else { clean_city };
-
-        // Step 1: 查坐标
-        let geo_url = format!(
-            "https://geocoding-api.open-meteo.com/v1/search?name={}&count=1&language=zh&format=json",
-            clean_city
-        );
-
-        let (lat, lon) = match self.http_client.get(&geo_url).send().await {
-            Ok(resp) => {
-                if !resp.status().is_success() { return "W:GeoErr".to_string(); }
-                match resp.json::<OmGeoResponse>().await {
-                    Ok(data) => {
-                        if let Some(results) = data.results {
-                            if let Some(loc) = results.first() {
-                                (loc.latitude, loc.longitude)
-                            } else { return "W:NoCity".to_string(); }
-                        } else { return "W:NoCity".to_string(); }
-                    }
-                    Err(_) => return "W:GeoJson".to_string(),
-                }
-            }
-            Err(_) => return "W:GeoNet".to_string(),
-        };
+    #[tokio::test]
+    async fn weather_cache_and_stale_fallback() {
+        let (m, mut agent, chain) = setup().await;
+        assert_eq!((agent.weather_ttl.as_secs(), agent.weather_retry.as_secs()), (1800, 120));
+        m.route(UAPIS, Reply::Body(fixtures::UAPIS));
+
+        let first = agent.get_smart_weather("Beijing", &chain).await.unwrap();
+        assert_eq!(first.display(Units::default()), "☂ 18℃ 14-21");
+        // 30 分钟内直接走缓存
+        agent.get_smart_weather("Beijing", &chain).await.unwrap();
+        assert_eq!(m.hits(UAPIS), 1);
+
+        // 缓存过期后刷新失败: 请求确实发出，但继续显示旧数据
+        m.route(UAPIS, Reply::Status(500));
+        agent.weather_ttl = Duration::ZERO;
+        agent.weather_retry = Duration::ZERO;
+        assert_eq!(agent.get_smart_weather("Beijing", &chain).await, Ok(first.clone()));
+        assert_eq!(m.hits(UAPIS), 2);
+
+        // 退避期内不再请求，仍是旧数据
+        agent.weather_retry = Duration::from_secs(WEATHER_RETRY_SECS);
+        assert_eq!(agent.get_smart_weather("Beijing", &chain).await, Ok(first));
+        assert_eq!(m.hits(UAPIS), 2);
+    }
 
-        // Step 2: 查当前天气 + 当天最高/最低温 (一次请求全带回)
-        let weather_url = format!(
-            "https://api.open-meteo.com/v1/forecast?latitude={}&longitude={}&current_weather=true&daily=temperature_2m_max,temperature_2m_min&forecast_days=1&timezone=auto",
-            lat, lon
-        );
+    #[tokio::test]
+    async fn weather_failure_backoff_without_cache() {
+        let (m, mut agent, chain) = setup().await;
+        m.route(UAPIS, Reply::Body(fixtures::MISSING_FIELDS));
+
+        assert_eq!(agent.get_smart_weather("Beijing", &chain).await, Err("W:Err(U)".to_string()));
+        // 120 秒内不重试，返回同一个错误提示
+        assert_eq!(agent.get_smart_weather("Beijing", &chain).await, Err("W:Err(U)".to_string()));
+        assert_eq!(m.hits(UAPIS), 1);
+
+        // 退避结束后恢复
+        m.route(UAPIS, Reply::Body(fixtures::UAPIS));
+        agent.weather_retry = Duration::ZERO;
+        assert!(agent.get_smart_weather("Beijing", &chain).await.is_ok());
+        assert_eq!(m.hits(UAPIS), 2);
+    }
 
-        match self.http_client.get(&weather_url).send().await {
-            Ok(resp) => {
-                if !resp.status().is_success() { return "W:ApiErr".to_string(); }
-                match resp.json::<OmWeatherResponse>().await {
-                    Ok(data) => {
-                        let temp = data.current_weather.temperature;
-                        let code = data.current_weather.weathercode;
-
-                        // 图标转换 (WMO 天气代码)，未知代码兜底云朵
-                        let icon = match code {
-                            0 => "☀",
-                            1 | 2 | 3 => "☁",
-                            45 | 48 => "🌫",
-                            51..=67 | 80..=82 => "☂",
-                            71..=77 | 85..=86 => "❄",
-                            95..=99 => "⚡",
-                            _ => "☁",
-                        };
-
-                        // 返回格式与其他数据源统一: ☀ 26℃ 20-30
-                        if let Some(daily) = &data.daily {
-                            if let (Some(max), Some(min)) = (daily.temperature_2m_max.first(), daily.temperature_2m_min.first()) {
-                                return format!("{} {:.0}℃ {:.0}-{:.0}", icon, temp, min, max);
-                            }
-                        }
-                        // daily 缺失时退回旧格式: ☀ 26.5℃
-                        return format!("{} {:.1}℃", icon, temp);
-                    }
-                    Err(_) => "W:JsonErr".to_string(),
-                }
-            }
-            Err(_) => "W:NetErr".to_string(),
-        }
+    #[tokio::test]
+    async fn auto_city_from_geoip() {
+        let (m, mut agent, _) = setup().await;
+        m.route("/geoip", Reply::Body(fixtures::GEOIP));
+
+        assert_eq!(agent.resolve_city("auto").await.as_deref(), Some("杭州市"));
+        assert_eq!(agent.locator.lock().await.coords(), Some((30.2943, 120.1663)));
+        // 城市与坐标齐了就不再请求
+        agent.resolve_city("").await;
+        assert_eq!(m.hits("/geoip"), 1);
+        assert_eq!(agent.resolve_city("Shanghai").await.as_deref(), Some("Shanghai"));
+    }
+
+    #[tokio::test]
+    async fn auto_city_lookup_failure_is_reported() {
+        let (m, mut agent, chain) = setup().await;
+        m.route("/geoip", Reply::Status(503));
+
+        // 定位失败不再冒充北京，也不去查天气
+        assert_eq!(agent.get_smart_weather("auto", &chain).await, Err("W:NoLoc".to_string()));
+        assert_eq!(m.hits(UAPIS), 0);
     }
 }
@@ -0,0 +1,314 @@
+// ==========================================
+// 📡 ping.rs — 延迟探测 (ICMP 优先，TCP 兜底) 与滚动统计
+// ICMP 用免 root 的"数据报 ICMP 套接字" (SOCK_DGRAM + IPPROTO_ICMP，内核负责 ID)，
+// 需要 net.ipv4.ping_group_range 覆盖进程 GID；创建失败或目标写了端口 (host:port) 时
+// 退回原来的 TCP 建连计时。
+// 每轮发 PROBES_PER_ROUND 个探测，最近 WINDOW 个结果用于计算丢包/抖动/最小/平均/最大。
+// 参数: "ping[:目标][/模式]"，模式 last(默认) / loss / jitter / min / avg / max
+// ==========================================
+use std::collections::VecDeque;
+use std::net::{IpAddr, SocketAddr, ToSocketAddrs};
+use std::sync::atomic::{AtomicU16, Ordering};
+use std::time::{Duration, Instant};
+
+pub const PROBES_PER_ROUND: usize = 3;
+const WINDOW: usize = 30; // 10 轮
+const DEFAULT_TARGET: &str = "223.5.5.5";
+const TIMEOUT: Duration = Duration::from_secs(2);
+
+// 全局递增序号，用来区分本轮应答与上一轮迟到的应答
+static SEQ: AtomicU16 = AtomicU16::new(1);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PingMode {
+    Last,
+    Loss,
+    Jitter,
+    Min,
+    Avg,
+    Max,
+}
+
+// "1.1.1.1/loss" -> ("1.1.1.1", Loss)；目标留空 = 默认目标
+pub fn parse_param(param: &str) -> (&str, PingMode) {
+    let (target, mode) = param.split_once('/').unwrap_or((param, ""));
+    let mode = match mode.trim() {
+        "loss" => PingMode::Loss,
+        "jitter" => PingMode::Jitter,
+        "min" => PingMode::Min,
+        "avg" => PingMode::Avg,
+        "max" => PingMode::Max,
+        _ => PingMode::Last,
+    };
+    (target.trim(), mode)
+}
+
+// ==========================================
+// 📊 滚动窗口统计 (None = 丢包)
+// ==========================================
+#[derive(Debug, Clone, Default)]
+pub struct PingStats {
+    window: VecDeque<Option<f64>>,
+    last_round: Vec<Option<f64>>,
+}
+
+impl PingStats {
+    pub fn push_round(&mut self, round: &[Option<f64>]) {
+        for r in round {
+            if self.window.len() == WINDOW {
+                self.window.pop_front();
+            }
+            self.window.push_back(*r);
+        }
+        self.last_round = round.to_vec();
+    }
+
+    fn ok(&self) -> impl Iterator<Item = f64> + '_ {
+        self.window.iter().flatten().copied()
+    }
+
+    pub fn loss_pct(&self) -> Option<f64> {
+        if self.window.is_empty() {
+            return None;
+        }
+        let lost = self.window.iter().filter(|r| r.is_none()).count();
+        Some(100.0 * lost as f64 / self.window.len() as f64)
+    }
+
+    pub fn min(&self) -> Option<f64> {
+        self.ok().reduce(f64::min)
+    }
+
+    pub fn max(&self) -> Option<f64> {
+        self.ok().reduce(f64::max)
+    }
+
+    pub fn avg(&self) -> Option<f64> {
+        let (sum, n) = self.ok().fold((0.0, 0), |(s, n), v| (s + v, n + 1));
+        (n > 0).then(|| sum / n as f64)
+    }
+
+    // 抖动: 相邻两次成功探测的延迟差绝对值的平均 (丢包处断开，不跨越计算)
+    pub fn jitter(&self) -> Option<f64> {
+        let v: Vec<Option<f64>> = self.window.iter().copied().collect();
+        let diffs: Vec<f64> = v
+            .windows(2)
+            .filter_map(|w| Some((w[1]? - w[0]?).abs()))
+            .collect();
+        (!diffs.is_empty()).then(|| diffs.iter().sum::<f64>() / diffs.len() as f64)
+    }
+
+    // 最近一轮的平均延迟 (整轮全丢 = None)
+    pub fn last(&self) -> Option<f64> {
+        let ok: Vec<f64> = self.last_round.iter().flatten().copied().collect();
+        (!ok.is_empty()).then(|| ok.iter().sum::<f64>() / ok.len() as f64)
+    }
+
+    pub fn display(&self, mode: PingMode) -> String {
+        if self.window.is_empty() {
+            return "P:Wait".to_string();
+        }
+        let ms = |label: &str, v: Option<f64>| match v {
+            Some(v) => format!("{}{:.0}ms", label, v),
+            None => format!("{}Err", label),
+        };
+        match mode {
+            PingMode::Last => ms("P:", self.last()),
+            PingMode::Loss => format!("L:{:.0}%", self.loss_pct().unwrap_or(100.0)),
+            PingMode::Jitter => ms("J:", self.jitter()),
+            PingMode::Min => ms("MIN:", self.min()),
+            PingMode::Avg => ms("AVG:", self.avg()),
+            PingMode::Max => ms("MAX:", self.max()),
+        }
+    }
+}
+
+// ==========================================
+// 🧱 ICMP 报文 (回显请求 / 应答)
+// ==========================================
+fn checksum(data: &[u8]) -> u16 {
+    let mut sum: u32 = data
+        .chunks(2)
+        .map(|c| u16::from_be_bytes([c[0], *c.get(1).unwrap_or(&0)]) as u32)
+        .sum();
+    while sum >> 16 != 0 {
+        sum = (sum & 0xFFFF) + (sum >> 16);
+    }
+    !(sum as u16)
+}
+
+// 回显请求: IPv4 type 8 / IPv6 type 128；ID 由内核改写，这里填 0
+fn echo_request(v6: bool, seq: u16) -> Vec<u8> {
+    let mut pkt = vec![if v6 { 128 } else { 8 }, 0, 0, 0, 0, 0];
+    pkt.extend_from_slice(&seq.to_be_bytes());
+    pkt.extend_from_slice(b"athena-led-ping!");
+    if !v6 {
+        // ICMPv6 的校验和含伪首部，由内核计算
+        let c = checksum(&pkt);
+        pkt[2..4].copy_from_slice(&c.to_be_bytes());
+    }
+    pkt
+}
+
+// 数据报 ICMP 套接字收到的是不含 IP 头的 ICMP 报文
+fn is_echo_reply(buf: &[u8], v6: bool, seq: u16) -> bool {
+    buf.len() >= 8 && buf[0] == if v6 { 129 } else { 0 } && buf[6..8] == seq.to_be_bytes()
+}
+
+#[cfg(unix)]
+fn icmp_socket(v6: bool) -> std::io::Result<std::net::UdpSocket> {
+    use std::os::unix::io::FromRawFd;
+    let (domain, proto) = if v6 {
+        (libc::AF_INET6, libc::IPPROTO_ICMPV6)
+    } else {
+        (libc::AF_INET, libc::IPPROTO_ICMP)
+    };
+    // SAFETY: 普通 socket 系统调用；成功返回的 fd 交给 UdpSocket 独占管理 (drop 时关闭)
+    let fd = unsafe { libc::socket(domain, libc::SOCK_DGRAM | libc::SOCK_CLOEXEC, proto) };
+    if fd < 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(unsafe { std::net::UdpSocket::from_raw_fd(fd) })
+}
+
+#[cfg(not(unix))]
+fn icmp_socket(_v6: bool) -> std::io::Result<std::net::UdpSocket> {
+    Err(std::io::Error::new(std::io::ErrorKind::Unsupported, "no ICMP datagram socket"))
+}
+
+// 一轮 ICMP 探测 (阻塞，放在 spawn_blocking 里跑)。套接字都建不起来时返回 Err 以便回退 TCP
+fn icmp_round(ip: IpAddr, count: usize) -> std::io::Result<Vec<Option<f64>>> {
+    let v6 = ip.is_ipv6();
+    let sock = icmp_socket(v6)?;
+    sock.set_read_timeout(Some(TIMEOUT))?;
+    let dest = SocketAddr::new(ip, 0);
+    let mut results = Vec::with_capacity(count);
+    let mut buf = [0u8; 512];
+    for i in 0..count {
+        let seq = SEQ.fetch_add(1, Ordering::Relaxed);
+        let start = Instant::now();
+        if sock.send_to(&echo_request(v6, seq), dest).is_err() {
+            results.push(None);
+            continue;
+        }
+        // 丢弃迟到的旧应答，直到收到本序号或超时
+        let mut rtt = None;
+        while start.elapsed() < TIMEOUT {
+            match sock.recv_from(&mut buf) {
+                Ok((n, _)) if is_echo_reply(&buf[..n], v6, seq) => {
+                    rtt = Some(start.elapsed().as_secs_f64() * 1000.0);
+                    break;
+                }
+                Ok(_) => continue,
+                Err(_) => break,
+            }
+        }
+        results.push(rtt);
+        if i + 1 < count {
+            std::thread::sleep(Duration::from_millis(200));
+        }
+    }
+    Ok(results)
+}
+
+// TCP 建连计时 (兜底；目标未写端口时连 80)
+async fn tcp_round(addr: &str, count: usize) -> Vec<Option<f64>> {
+    let mut results = Vec::with_capacity(count);
+    for _ in 0..count {
+        let start = Instant::now();
+        let r = tokio::time::timeout(TIMEOUT, tokio::net::TcpStream::connect(addr)).await;
+        results.push(matches!(r, Ok(Ok(_))).then(|| start.elapsed().as_secs_f64() * 1000.0));
+    }
+    results
+}
+
+// 一轮探测: 返回每个探测的延迟 (ms)，None = 丢包
+pub async fn probe_round(target: &str) -> Vec<Option<f64>> {
+    let target = if target.is_empty() { DEFAULT_TARGET } else { target };
+
+    // 显式写了端口 (host:port / [v6]:port) 就是要测 TCP
+    if let Ok(sa) = target.parse::<SocketAddr>() {
+        return tcp_round(&sa.to_string(), PROBES_PER_ROUND).await;
+    }
+    if target.matches(':').count() == 1 {
+        return tcp_round(target, PROBES_PER_ROUND).await;
+    }
+
+    let host = target.to_string();
+    let ip = match target.parse::<IpAddr>() {
+        Ok(ip) => Some(ip),
+        Err(_) => tokio::task::spawn_blocking(move || {
+            (host.as_str(), 0).to_socket_addrs().ok()?.next().map(|a| a.ip())
+        })
+        .await
+        .ok()
+        .flatten(),
+    };
+    let Some(ip) = ip else { return vec![None; PROBES_PER_ROUND] };
+
+    match tokio::task::spawn_blocking(move || icmp_round(ip, PROBES_PER_ROUND)).await {
+        Ok(Ok(results)) => results,
+        _ => tcp_round(&SocketAddr::new(ip, 80).to_string(), PROBES_PER_ROUND).await,
+    }
+}
+
+// ==========================================
+// 🧪 单元测试
+// ==========================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn params() {
+        assert_eq!(parse_param(""), ("", PingMode::Last));
+        assert_eq!(parse_param("1.1.1.1/loss"), ("1.1.1.1", PingMode::Loss));
+        assert_eq!(parse_param("8.8.8.8:53/jitter"), ("8.8.8.8:53", PingMode::Jitter));
+        assert_eq!(parse_param("/max"), ("", PingMode::Max));
+    }
+
+    #[test]
+    fn rolling_stats() {
+        let mut s = PingStats::default();
+        assert_eq!(s.display(PingMode::Last), "P:Wait");
+        s.push_round(&[Some(10.0), Some(14.0), None]);
+        s.push_round(&[Some(12.0), None, Some(20.0)]);
+        assert_eq!(s.display(PingMode::Last), "P:16ms");
+        assert_eq!(s.display(PingMode::Loss), "L:33%");
+        assert_eq!(s.display(PingMode::Min), "MIN:10ms");
+        assert_eq!(s.display(PingMode::Max), "MAX:20ms");
+        assert_eq!(s.display(PingMode::Avg), "AVG:14ms");
+        // 相邻成功对: (10,14) 只有这一对 (其余被丢包隔开) -> 4
+        assert_eq!(s.display(PingMode::Jitter), "J:4ms");
+
+        s.push_round(&[None, None, None]);
+        assert_eq!(s.display(PingMode::Last), "P:Err");
+    }
+
+    #[test]
+    fn window_is_bounded() {
+        let mut s = PingStats::default();
+        for _ in 0..20 {
+            s.push_round(&[None, None, None]);
+        }
+        s.push_round(&[Some(5.0); PROBES_PER_ROUND]);
+        assert_eq!(s.window.len(), WINDOW);
+        assert_eq!(s.loss_pct(), Some(90.0));
+    }
+
+    #[test]
+    fn icmp_packets() {
+        let pkt = echo_request(false, 0x1234);
+        assert_eq!(&pkt[..2], &[8, 0]);
+        assert_eq!(&pkt[6..8], &[0x12, 0x34]);
+        // 含正确校验和的报文再算一遍应为 0
+        assert_eq!(checksum(&pkt), 0);
+
+        let mut reply = pkt.clone();
+        reply[0] = 0;
+        assert!(is_echo_reply(&reply, false, 0x1234));
+        assert!(!is_echo_reply(&reply, false, 0x1235));
+        assert!(!is_echo_reply(&pkt, false, 0x1234));
+        assert!(is_echo_reply(&[129, 0, 0, 0, 0, 0, 0, 7], true, 7));
+    }
+}
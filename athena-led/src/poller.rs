@@ -0,0 +1,137 @@
+// ==========================================
+// ⏱️ poller.rs — 后台定时采集 (wifi / wan / clients 模块共用)
+// 外部命令 (iw / ubus) 或大文件 (conntrack) 可能卡住好几秒，不能在渲染循环里同步跑:
+// 每个键起一个后台任务，把阻塞的采集函数放进 spawn_blocking 并加超时，结果写进快照，
+// 渲染路径只读快照。采集函数是 FnMut，可以在闭包里保留上一轮的状态 (做差值用)。
+// ==========================================
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+// 键 -> 最近一轮采集结果 (None = 采集失败或超时)
+pub type Snapshot<T> = Arc<RwLock<HashMap<String, Option<T>>>>;
+
+// 后台采集循环: 超时后先把该键标记为 None，再等这一轮真正结束 (不叠加卡死的线程)
+// 采集函数 panic 时任务结束，该键保持 None
+pub fn spawn_poll<T, F>(tag: &'static str, key: String, timeout: Duration, interval: Duration, snapshot: Snapshot<T>, poll: F)
+where
+    T: Send + Sync + 'static,
+    F: FnMut() -> Option<T> + Send + 'static,
+{
+    tokio::spawn(async move {
+        let mut poll = Some(poll);
+        while let Some(mut f) = poll.take() {
+            let mut job = tokio::task::spawn_blocking(move || {
+                let v = f();
+                (f, v)
+            });
+            let done = match tokio::time::timeout(timeout, &mut job).await {
+                Ok(res) => res.ok(),
+                Err(_) => {
+                    println!("⚠️ [{}] {} 采集超过 {} 秒未返回", tag, key, timeout.as_secs_f32());
+                    if let Ok(mut snap) = snapshot.write() {
+                        snap.insert(key.clone(), None);
+                    }
+                    job.await.ok()
+                }
+            };
+            let value = done.and_then(|(f, v)| {
+                poll = Some(f);
+                v
+            });
+            if let Ok(mut snap) = snapshot.write() {
+                snap.insert(key.clone(), value);
+            }
+            if poll.is_some() {
+                tokio::time::sleep(interval).await;
+            }
+        }
+    });
+}
+
+// ==========================================
+// 🧠 按键懒启动的采集器: 首次用到某个键时起后台任务，之后只读快照
+// ==========================================
+pub struct Poller<T> {
+    tag: &'static str,
+    timeout: Duration,
+    interval: Duration,
+    snapshot: Snapshot<T>,
+    watched: HashSet<String>,
+}
+
+impl<T: Clone + Send + Sync + 'static> Poller<T> {
+    pub fn new(tag: &'static str, timeout: Duration, interval: Duration) -> Self {
+        Self { tag, timeout, interval, snapshot: Arc::new(RwLock::new(HashMap::new())), watched: HashSet::new() }
+    }
+
+    // 外层 None = 首轮采集还没完成；内层 None = 采集失败或超时
+    // make 只在该键第一次出现时调用，用来构造这个键的采集函数
+    pub fn get<F>(&mut self, key: &str, make: impl FnOnce() -> F) -> Option<Option<T>>
+    where
+        F: FnMut() -> Option<T> + Send + 'static,
+    {
+        if self.watched.insert(key.to_string()) {
+            spawn_poll(self.tag, key.to_string(), self.timeout, self.interval, Arc::clone(&self.snapshot), make());
+        }
+        self.snapshot.read().ok()?.get(key).cloned()
+    }
+}
+
+// ==========================================
+// 🧪 单元测试
+// ==========================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 轮询快照直到满足条件 (最多约 2 秒)
+    async fn wait_for<T: Clone + Send + Sync + 'static>(p: &Poller<T>, key: &str, ok: impl Fn(&Option<Option<T>>) -> bool) {
+        for _ in 0..100 {
+            let cur = p.snapshot.read().unwrap().get(key).cloned();
+            if ok(&cur) {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        panic!("快照一直没有满足条件");
+    }
+
+    // 首次请求只起任务不当场采集；之后每轮都读到新值，闭包里的状态跨轮保留；同一个键只起一个任务
+    #[tokio::test]
+    async fn lazy_start_and_state() {
+        let mut p: Poller<u32> = Poller::new("测试", Duration::from_secs(1), Duration::from_millis(10));
+        let make = || {
+            let mut n = 0;
+            move || {
+                n += 1;
+                Some(n)
+            }
+        };
+        assert_eq!(p.get("a", make), None);
+        wait_for(&p, "a", |v| matches!(v, Some(Some(n)) if *n >= 3)).await;
+        assert!(matches!(p.get("a", make), Some(Some(n)) if n >= 3));
+        assert_eq!(p.watched.len(), 1);
+        // 采集失败 -> 内层 None
+        assert_eq!(p.get("b", || || None), None);
+        wait_for(&p, "b", |v| *v == Some(None)).await;
+    }
+
+    // 卡住的采集: 超时后先标记 None，这一轮结束后恢复正常值
+    #[tokio::test]
+    async fn timeout_marks_none_then_recovers() {
+        let mut p: Poller<u32> = Poller::new("测试", Duration::from_millis(50), Duration::from_millis(10));
+        let slow = || {
+            let mut first = true;
+            move || {
+                if std::mem::take(&mut first) {
+                    std::thread::sleep(Duration::from_millis(300));
+                }
+                Some(7)
+            }
+        };
+        p.get("slow", slow);
+        wait_for(&p, "slow", |v| *v == Some(None)).await;
+        wait_for(&p, "slow", |v| *v == Some(Some(7))).await;
+    }
+}
@@ -0,0 +1,385 @@
+// ==========================================
+// 📦 quota.rs — 月度流量配额记账 (quota 模块 + 配额告警)
+// 内核计数器 (/sys/class/net/<iface>/statistics/{rx,tx}_bytes) 重启归零、接口重建 (PPPoE 重拨) 也会归零，
+// 这里按增量累加到自己的账本，并低频落盘到闪存 (默认 30 分钟一次 + 退出时一次)。
+// 计数器归零判定: 开机 ID (/proc/sys/kernel/random/boot_id) 或接口 ifindex 变化，
+// 或者读数比上次小 —— 三者任一成立，本次增量 = 当前读数 (从 0 重新计)。
+// 新建的账本 (首次启用 / 换了接口) 第一次采样只记基线不计入，免得把开机以来的流量全算进本月。
+// 采样由独立的后台定时任务推进 (5 秒一次)，不依赖渲染循环，夜间休眠期间的重拨也不漏账。
+// 账期按"结算日"滚动: 结算日 15 号 = 每月 15 日 00:00 清零月累计 (大于月末天数按月末算)
+// ==========================================
+use chrono::{Datelike, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+const GIB: f64 = 1_073_741_824.0;
+
+// 落盘的账本 (JSON)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+struct Ledger {
+    iface: String,
+    boot_id: String,
+    ifindex: u64,
+    last_rx: u64,
+    last_tx: u64,
+    day: Option<NaiveDate>,
+    day_rx: u64,
+    day_tx: u64,
+    period_start: Option<NaiveDate>,
+    month_rx: u64,
+    month_tx: u64,
+    alerted: bool, // 本账期是否已播过配额告警 (落盘，重启不重复播)
+}
+
+// 一次计数器采样
+#[derive(Debug, Clone, Copy)]
+struct Sample<'a> {
+    boot_id: &'a str,
+    ifindex: u64,
+    rx: u64,
+    tx: u64,
+}
+
+// 某天所在账期的起始日 (结算日超出当月天数时取当月最后一天)
+fn period_start(today: NaiveDate, billing_day: u32) -> NaiveDate {
+    let bd = billing_day.clamp(1, 31);
+    let clamp_in = |y: i32, m: u32| -> NaiveDate {
+        let mut d = bd;
+        loop {
+            if let Some(date) = NaiveDate::from_ymd_opt(y, m, d) {
+                return date;
+            }
+            d -= 1;
+        }
+    };
+    let this_month = clamp_in(today.year(), today.month());
+    if today >= this_month {
+        this_month
+    } else if today.month() == 1 {
+        clamp_in(today.year() - 1, 12)
+    } else {
+        clamp_in(today.year(), today.month() - 1)
+    }
+}
+
+impl Ledger {
+    // 记账: 返回本次是否发生了日/账期滚动 (滚动时调用方应立即落盘)
+    fn account(&mut self, s: Sample, today: NaiveDate, billing_day: u32) -> bool {
+        // 未初始化的账本: 本次读数只作基线
+        let baseline = self.boot_id.is_empty();
+        let reset = s.boot_id != self.boot_id
+            || s.ifindex != self.ifindex
+            || s.rx < self.last_rx
+            || s.tx < self.last_tx;
+        let (drx, dtx) = if baseline {
+            (0, 0)
+        } else if reset {
+            (s.rx, s.tx)
+        } else {
+            (s.rx - self.last_rx, s.tx - self.last_tx)
+        };
+        self.boot_id = s.boot_id.to_string();
+        self.ifindex = s.ifindex;
+        self.last_rx = s.rx;
+        self.last_tx = s.tx;
+
+        let mut rolled = false;
+        if self.day != Some(today) {
+            self.day = Some(today);
+            self.day_rx = 0;
+            self.day_tx = 0;
+            rolled = true;
+        }
+        let start = period_start(today, billing_day);
+        if self.period_start != Some(start) {
+            self.period_start = Some(start);
+            self.month_rx = 0;
+            self.month_tx = 0;
+            self.alerted = false;
+            rolled = true;
+        }
+
+        self.day_rx += drx;
+        self.day_tx += dtx;
+        self.month_rx += drx;
+        self.month_tx += dtx;
+        rolled
+    }
+
+    fn month_total(&self) -> u64 {
+        self.month_rx + self.month_tx
+    }
+}
+
+// 屏显用的紧凑字节数: "850M" / "3.4G" / "123G" / "1.2T"
+fn short_bytes(bytes: u64) -> String {
+    let b = bytes as f64;
+    if b >= 1024.0 * GIB {
+        format!("{:.1}T", b / 1024.0 / GIB)
+    } else if b >= 100.0 * GIB {
+        format!("{:.0}G", b / GIB)
+    } else if b >= GIB {
+        format!("{:.1}G", b / GIB)
+    } else {
+        format!("{:.0}M", b / 1_048_576.0)
+    }
+}
+
+// 屏显文本 (param: day / month / left / pct，留空 = 有配额显示百分比，否则月累计)
+fn display(ledger: &Ledger, limit_gb: u64, param: &str) -> String {
+    let limit = limit_gb * GIB as u64;
+    let month = ledger.month_total();
+    match (param, limit_gb) {
+        ("day", _) => format!("D:{}", short_bytes(ledger.day_rx + ledger.day_tx)),
+        ("month", _) | ("", 0) | ("pct", 0) | ("left", 0) => format!("M:{}", short_bytes(month)),
+        ("left", _) => format!("L:{}", short_bytes(limit.saturating_sub(month))),
+        _ => format!("Q:{}%", month * 100 / limit),
+    }
+}
+
+// 配额告警文本: 首次越过阈值时返回 "80% of 500G used"
+fn alert_text(ledger: &mut Ledger, limit_gb: u64, alert_pct: u32) -> Option<String> {
+    if limit_gb == 0 || alert_pct == 0 || ledger.alerted {
+        return None;
+    }
+    let pct = ledger.month_total() * 100 / (limit_gb * GIB as u64);
+    if pct >= alert_pct as u64 {
+        ledger.alerted = true;
+        Some(format!("{}% of {}G used", pct, limit_gb))
+    } else {
+        None
+    }
+}
+
+fn read_sample(iface: &str) -> Option<(String, u64, u64, u64)> {
+    let boot_id = fs::read_to_string("/proc/sys/kernel/random/boot_id").unwrap_or_default();
+    let ifindex = fs::read_to_string(format!("/sys/class/net/{}/ifindex", iface))
+        .ok()?
+        .trim()
+        .parse()
+        .ok()?;
+    let stat = |name: &str| -> Option<u64> {
+        fs::read_to_string(format!("/sys/class/net/{}/statistics/{}", iface, name))
+            .ok()?
+            .trim()
+            .parse()
+            .ok()
+    };
+    Some((boot_id.trim().to_string(), ifindex, stat("rx_bytes")?, stat("tx_bytes")?))
+}
+
+// ==========================================
+// 🧠 记账器 (由 SystemMonitor 持有)
+// ==========================================
+pub struct QuotaMeter {
+    iface: String,
+    limit_gb: u64,
+    billing_day: u32,
+    alert_pct: u32,
+    file: String,
+    save_every: Duration,
+    ledger: Ledger,
+    last_sample: Option<Instant>,
+    last_save: Instant,
+    dirty: bool,
+}
+
+impl QuotaMeter {
+    pub fn new(iface: &str, limit_gb: u64, billing_day: u32, alert_pct: u32, file: &str, save_mins: u64) -> Self {
+        // 读旧账本；换了接口就从头记
+        let ledger = fs::read_to_string(file)
+            .ok()
+            .and_then(|s| serde_json::from_str::<Ledger>(&s).ok())
+            .filter(|l| l.iface == iface)
+            .unwrap_or_else(|| Ledger { iface: iface.to_string(), ..Default::default() });
+        Self {
+            iface: iface.to_string(),
+            limit_gb,
+            billing_day,
+            alert_pct,
+            file: file.to_string(),
+            save_every: Duration::from_secs(save_mins.max(1) * 60),
+            ledger,
+            last_sample: None,
+            last_save: Instant::now(),
+            dirty: false,
+        }
+    }
+
+    /// 采样记账 (5 秒节流)
+    pub fn tick(&mut self) {
+        if self.last_sample.is_some_and(|i| i.elapsed() < Duration::from_secs(5)) {
+            return;
+        }
+        self.last_sample = Some(Instant::now());
+
+        let Some((boot_id, ifindex, rx, tx)) = read_sample(&self.iface) else { return };
+        let sample = Sample { boot_id: &boot_id, ifindex, rx, tx };
+        let rolled = self.ledger.account(sample, Local::now().date_naive(), self.billing_day);
+        self.dirty = true;
+        if rolled || self.last_save.elapsed() >= self.save_every {
+            self.save();
+        }
+    }
+
+    /// 检查阈值，返回需要插播的配额告警 (告警标记立即落盘)
+    pub fn poll_alert(&mut self) -> Option<String> {
+        let alert = alert_text(&mut self.ledger, self.limit_gb, self.alert_pct);
+        if alert.is_some() {
+            self.dirty = true;
+            self.save();
+        }
+        alert
+    }
+
+    /// 写入闪存 (先写临时文件再 rename，断电不留半截 JSON)
+    pub fn save(&mut self) {
+        if !self.dirty {
+            return;
+        }
+        if let Some(dir) = std::path::Path::new(&self.file).parent() {
+            let _ = fs::create_dir_all(dir);
+        }
+        let tmp = format!("{}.tmp", self.file);
+        let ok = serde_json::to_string(&self.ledger)
+            .ok()
+            .and_then(|json| fs::write(&tmp, json).ok())
+            .and_then(|_| fs::rename(&tmp, &self.file).ok())
+            .is_some();
+        if !ok {
+            println!("⚠️ [配额] 账本写入失败: {}", self.file);
+        }
+        self.last_save = Instant::now();
+        self.dirty = false;
+    }
+
+    /// 本账期已用百分比 (未设配额或尚无数据时 None，gauge 模块用)
+    pub fn percent(&self) -> Option<f64> {
+        if self.limit_gb == 0 || self.ledger.period_start.is_none() {
+            return None;
+        }
+        Some(self.ledger.month_total() as f64 * 100.0 / (self.limit_gb as f64 * GIB))
+    }
+
+    pub fn display(&self, param: &str) -> String {
+        if self.ledger.period_start.is_none() {
+            return "Q:Wait".to_string();
+        }
+        display(&self.ledger, self.limit_gb, param)
+    }
+}
+
+// 后台与渲染循环共用的记账器
+pub type SharedQuota = Arc<Mutex<QuotaMeter>>;
+
+// 起独立的采样定时任务 (先立即采一次，之后每 5 秒)
+pub fn spawn_ticker(meter: QuotaMeter) -> SharedQuota {
+    let shared = Arc::new(Mutex::new(meter));
+    let for_task = Arc::clone(&shared);
+    tokio::spawn(async move {
+        let mut every = tokio::time::interval(Duration::from_secs(5));
+        loop {
+            every.tick().await;
+            if let Ok(mut q) = for_task.lock() {
+                q.tick();
+            }
+        }
+    });
+    shared
+}
+
+// ==========================================
+// 🧪 单元测试
+// ==========================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn d(y: i32, m: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, day).unwrap()
+    }
+
+    fn s(boot: &str, idx: u64, rx: u64, tx: u64) -> Sample<'_> {
+        Sample { boot_id: boot, ifindex: idx, rx, tx }
+    }
+
+    #[test]
+    fn billing_period_start() {
+        assert_eq!(period_start(d(2026, 3, 20), 15), d(2026, 3, 15));
+        assert_eq!(period_start(d(2026, 3, 14), 15), d(2026, 2, 15));
+        assert_eq!(period_start(d(2026, 1, 3), 15), d(2025, 12, 15));
+        // 结算日 31 号: 2 月按月末算
+        assert_eq!(period_start(d(2026, 2, 28), 31), d(2026, 2, 28));
+        assert_eq!(period_start(d(2026, 3, 30), 31), d(2026, 2, 28));
+        assert_eq!(period_start(d(2026, 3, 31), 31), d(2026, 3, 31));
+    }
+
+    #[test]
+    fn counter_resets() {
+        let mut l = Ledger::default();
+        let today = d(2026, 5, 10);
+        // 首次采样 (新账本): 只记基线，开机以来的读数不计入
+        l.account(s("a", 3, 1000, 100), today, 1);
+        assert_eq!((l.month_rx, l.month_tx), (0, 0));
+        assert_eq!((l.last_rx, l.last_tx), (1000, 100));
+        // 正常增量
+        l.account(s("a", 3, 1500, 300), today, 1);
+        assert_eq!((l.month_rx, l.month_tx), (500, 200));
+        // 接口重建 (ifindex 变化，读数恰好更大也不能当增量)
+        l.account(s("a", 9, 2000, 400), today, 1);
+        assert_eq!((l.month_rx, l.month_tx), (2500, 600));
+        // 计数器回绕/归零
+        l.account(s("a", 9, 50, 10), today, 1);
+        assert_eq!((l.month_rx, l.month_tx), (2550, 610));
+        // 重启
+        l.account(s("b", 9, 100, 0), today, 1);
+        assert_eq!((l.month_rx, l.month_tx), (2650, 610));
+    }
+
+    #[test]
+    fn day_and_period_rollover() {
+        let mut l = Ledger::default();
+        assert!(l.account(s("a", 1, 100, 0), d(2026, 5, 13), 15));
+        assert!(!l.account(s("a", 1, 300, 0), d(2026, 5, 13), 15));
+        // 新的一天，同一账期: 日累计清零，月累计保留
+        assert!(l.account(s("a", 1, 400, 0), d(2026, 5, 14), 15));
+        assert_eq!((l.day_rx, l.month_rx), (100, 300));
+        l.alerted = true;
+        // 到结算日换账期: 月累计与告警标记清零
+        assert!(l.account(s("a", 1, 500, 0), d(2026, 5, 15), 15));
+        assert_eq!((l.day_rx, l.month_rx, l.alerted), (100, 100, false));
+    }
+
+    #[test]
+    fn display_and_alert() {
+        let g = GIB as u64;
+        let mut l = Ledger {
+            period_start: Some(d(2026, 5, 1)),
+            day_rx: 3 * g + g / 2,
+            month_rx: 300 * g,
+            month_tx: 100 * g,
+            ..Default::default()
+        };
+        assert_eq!(display(&l, 500, ""), "Q:80%");
+        assert_eq!(display(&l, 500, "left"), "L:100G");
+        assert_eq!(display(&l, 500, "day"), "D:3.5G");
+        assert_eq!(display(&l, 0, ""), "M:400G");
+        assert_eq!(short_bytes(850 * 1_048_576), "850M");
+        assert_eq!(short_bytes(1536 * g), "1.5T");
+
+        assert_eq!(alert_text(&mut l, 500, 90), None);
+        assert_eq!(alert_text(&mut l, 500, 80).as_deref(), Some("80% of 500G used"));
+        // 同一账期只播一次
+        assert_eq!(alert_text(&mut l, 500, 80), None);
+    }
+
+    #[test]
+    fn ledger_roundtrip() {
+        let mut l = Ledger { iface: "wan".into(), ..Default::default() };
+        l.account(s("a", 2, 10, 20), d(2026, 5, 1), 1);
+        let json = serde_json::to_string(&l).unwrap();
+        assert_eq!(serde_json::from_str::<Ledger>(&json).unwrap(), l);
+    }
+}
@@ -0,0 +1,297 @@
+// ==========================================
+// 📰 rss.rs — RSS / Atom 标题滚动 (rss:<名字> 模块, v2.6.0)
+// 每个源通过 --rss-feed 传入一个 JSON 对象 (可重复)，init 脚本用 jshn 从 UCI 生成:
+//   {"name":"hn","url":"https://hnrss.org/frontpage","interval":600,"count":5}
+// url 也可以是本地文件 (file:///tmp/feed.xml 或 /tmp/feed.xml)，方便离线调试/测试。
+//
+// 后台按各自间隔拉取，只保留最新 count 条标题 (按 guid/id/link/标题去重)；
+// 显示时模块时长内一条接一条滚动，优先播还没播过的标题，
+// 全部播过之后才从头再轮一遍 (有新标题时新标题插队)。
+// 标题只做轻量解析: 正则切出 <item>/<entry> 里的 <title>，去 CDATA/标签、解实体。
+// 注意: LED 字库没有汉字，中文标题会显示不全，建议用英文源。
+// ==========================================
+use crate::http_client::Http;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Deserialize;
+use std::time::{Duration, Instant};
+
+const DEFAULT_INTERVAL: u64 = 600;
+const MIN_INTERVAL: u64 = 60; // 刷新间隔下限 (秒)
+const RETRY_SECS: u64 = 120; // 拉取失败后的重试间隔
+const DEFAULT_COUNT: usize = 5;
+
+static ITEM: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?is)<(?:item|entry)\b[^>]*>(.*?)</(?:item|entry)>").unwrap());
+static TITLE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?is)<title\b[^>]*>(.*?)</title>").unwrap());
+// 去重用的 id 按 guid/id > link > 标题 的优先级取
+static ID: Lazy<[Regex; 3]> = Lazy::new(|| {
+    [
+        Regex::new(r"(?is)<(?:guid|id)\b[^>]*>(.*?)</(?:guid|id)>").unwrap(),
+        Regex::new(r#"(?is)<link\b[^>]*?href="([^"]*)""#).unwrap(),
+        Regex::new(r"(?is)<link\b[^>]*>(.*?)</link>").unwrap(),
+    ]
+});
+static CDATA: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?s)<!\[CDATA\[(.*?)\]\]>").unwrap());
+static TAG: Lazy<Regex> = Lazy::new(|| Regex::new(r"<[^>]*>").unwrap());
+static ENTITY: Lazy<Regex> = Lazy::new(|| Regex::new(r"&(#x[0-9a-fA-F]+|#[0-9]+|[a-zA-Z]+);").unwrap());
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct FeedSpec {
+    pub name: String,
+    pub url: String,
+    // 刷新间隔 (秒)，缺省 600
+    #[serde(default)]
+    pub interval: Option<u64>,
+    // 保留最新几条，缺省 5
+    #[serde(default)]
+    pub count: Option<usize>,
+}
+
+// 解析全部 --rss-feed，格式错误的跳过并打印原因
+pub fn parse_specs(specs: &[String]) -> Vec<FeedSpec> {
+    specs
+        .iter()
+        .filter_map(|spec| match serde_json::from_str::<FeedSpec>(spec) {
+            Ok(f) if !f.name.is_empty() && !f.url.is_empty() => Some(f),
+            Ok(_) => {
+                println!("⚠️ [RSS] 缺少 name 或 url，已忽略: {}", spec);
+                None
+            }
+            Err(e) => {
+                println!("⚠️ [RSS] 配置解析失败 ({}): {}", e, spec);
+                None
+            }
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Headline {
+    pub id: String,
+    pub title: String,
+}
+
+// ==========================================
+// 🔍 解析: 返回文档顺序 (通常最新在前) 的标题列表
+// ==========================================
+pub fn parse_feed(xml: &str) -> Vec<Headline> {
+    ITEM.captures_iter(xml)
+        .filter_map(|c| {
+            let body = c.get(1)?.as_str();
+            let title = clean(TITLE.captures(body)?.get(1)?.as_str());
+            if title.is_empty() {
+                return None;
+            }
+            let id = ID
+                .iter()
+                .filter_map(|re| re.captures(body).map(|m| clean(&m[1])))
+                .find(|s| !s.is_empty())
+                .unwrap_or_else(|| title.clone());
+            Some(Headline { id, title })
+        })
+        .collect()
+}
+
+// 去 CDATA 外壳和内嵌标签、解实体、折叠空白
+fn clean(raw: &str) -> String {
+    let text = CDATA.replace_all(raw, "$1");
+    let text = decode_entities(&TAG.replace_all(&text, ""));
+    // CDATA 里的内容本身可能还是转义过的 HTML (&lt;b&gt;)，再剥一次标签
+    let text = TAG.replace_all(&text, "");
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn decode_entities(s: &str) -> String {
+    ENTITY
+        .replace_all(s, |c: &regex::Captures| {
+            let e = &c[1];
+            let ch = match e {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some(' '),
+                _ if e.starts_with("#x") || e.starts_with("#X") => u32::from_str_radix(&e[2..], 16).ok().and_then(char::from_u32),
+                _ if e.starts_with('#') => e[1..].parse().ok().and_then(char::from_u32),
+                _ => None,
+            };
+            ch.map_or_else(|| c[0].to_string(), |ch| ch.to_string())
+        })
+        .into_owned()
+}
+
+// ==========================================
+// 🗂️ 显示端状态 (放在网络快照里): 最新 N 条 + 是否已播
+// ==========================================
+#[derive(Debug, Default, Clone)]
+pub struct Headlines {
+    items: Vec<(Headline, bool)>,
+    loaded: bool,
+    failed: bool,
+}
+
+impl Headlines {
+    // 合并新拉到的列表: 已知标题保留“已播”标记，新标题未播；只留最新 keep 条
+    pub fn merge(&mut self, fresh: Vec<Headline>, keep: usize) {
+        let mut items: Vec<(Headline, bool)> = Vec::new();
+        for h in fresh {
+            if items.iter().any(|(x, _)| x.id == h.id) {
+                continue;
+            }
+            let shown = self.items.iter().any(|(x, shown)| x.id == h.id && *shown);
+            items.push((h, shown));
+            if items.len() >= keep.max(1) {
+                break;
+            }
+        }
+        self.items = items;
+        self.loaded = true;
+        self.failed = false;
+    }
+
+    pub fn mark_failed(&mut self) {
+        self.failed = true;
+    }
+
+    // 下一条要滚动的标题: 先播没播过的；全播过就重置开始新一轮
+    pub fn next(&mut self) -> Option<String> {
+        if self.items.is_empty() {
+            return None;
+        }
+        if self.items.iter().all(|(_, shown)| *shown) {
+            self.items.iter_mut().for_each(|(_, shown)| *shown = false);
+        }
+        let (h, shown) = self.items.iter_mut().find(|(_, shown)| !*shown)?;
+        *shown = true;
+        Some(h.title.clone())
+    }
+
+    // 没有标题可播时的提示
+    pub fn status(&self) -> &'static str {
+        match (self.loaded, self.failed) {
+            (_, true) if self.items.is_empty() => "RSS:Err",
+            (true, _) => "RSS:Empty",
+            _ => "RSS:Wait",
+        }
+    }
+}
+
+// ==========================================
+// 📡 拉取端 (net_agent 每个源一个任务)
+// ==========================================
+pub struct RssFeed {
+    pub spec: FeedSpec,
+    next_due: Option<Instant>,
+}
+
+impl RssFeed {
+    pub fn new(spec: FeedSpec) -> Self {
+        Self { spec, next_due: None }
+    }
+
+    pub fn is_due(&self) -> bool {
+        self.next_due.is_none_or(|t| Instant::now() >= t)
+    }
+
+    pub fn keep(&self) -> usize {
+        self.spec.count.unwrap_or(DEFAULT_COUNT)
+    }
+
+    // 成功返回解析出的标题 (可能为空列表)，失败返回 None
+    pub async fn refresh(&mut self, http: &Http) -> Option<Vec<Headline>> {
+        let result = http.fetch_text("rss", &self.spec.url).await.map(|xml| parse_feed(&xml));
+        let wait = match &result {
+            Some(_) => self.spec.interval.unwrap_or(DEFAULT_INTERVAL).max(MIN_INTERVAL),
+            None => {
+                println!("⚠️ [RSS] {} 获取失败", self.spec.name);
+                RETRY_SECS
+            }
+        };
+        self.next_due = Some(Instant::now() + Duration::from_secs(wait));
+        result
+    }
+}
+
+// ==========================================
+// 🧪 单元测试
+// ==========================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RSS: &str = r#"<?xml version="1.0"?><rss version="2.0"><channel><title>Site</title>
+        <item><title><![CDATA[Rust 1.90 released]]></title><guid isPermaLink="false">a1</guid></item>
+        <item><title>Tom &amp; Jerry &#8211; &lt;b&gt;live&lt;/b&gt;</title><link>https://x/2</link></item>
+        <item><title>   </title><guid>empty</guid></item>
+        <item><title>Rust 1.90 released</title><guid isPermaLink="false">a1</guid></item>
+        </channel></rss>"#;
+
+    const ATOM: &str = r#"<feed xmlns="http://www.w3.org/2005/Atom"><title>Blog</title>
+        <entry><title type="html">Hello
+           world</title><link rel="alternate" href="https://b/1"/><id>tag:b,1</id></entry>
+        <entry><title>Second</title><link href="https://b/2"/></entry></feed>"#;
+
+    #[test]
+    fn parses_rss_and_atom() {
+        let h = parse_feed(RSS);
+        assert_eq!(h.len(), 3);
+        assert_eq!(h[0], Headline { id: "a1".into(), title: "Rust 1.90 released".into() });
+        assert_eq!(h[1], Headline { id: "https://x/2".into(), title: "Tom & Jerry – live".into() });
+
+        let h = parse_feed(ATOM);
+        assert_eq!(h[0], Headline { id: "tag:b,1".into(), title: "Hello world".into() });
+        assert_eq!(h[1].id, "https://b/2");
+        assert!(parse_feed("<html>not a feed</html>").is_empty());
+    }
+
+    #[test]
+    fn rotation_prefers_unseen_and_dedups() {
+        let mut hl = Headlines::default();
+        assert_eq!((hl.next(), hl.status()), (None, "RSS:Wait"));
+
+        hl.merge(parse_feed(RSS), 5);
+        // 重复的 guid 只留一条
+        assert_eq!(hl.next().as_deref(), Some("Rust 1.90 released"));
+        assert_eq!(hl.next().as_deref(), Some("Tom & Jerry – live"));
+
+        // 刷新后旧标题保持已播，新标题插队
+        let mut fresh = vec![Headline { id: "n".into(), title: "Breaking".into() }];
+        fresh.extend(parse_feed(RSS));
+        hl.merge(fresh, 2);
+        assert_eq!(hl.next().as_deref(), Some("Breaking"));
+        // 全部播过: 从头再轮 (只保留了最新 2 条)
+        assert_eq!(hl.next().as_deref(), Some("Breaking"));
+        assert_eq!(hl.next().as_deref(), Some("Rust 1.90 released"));
+
+        hl.merge(Vec::new(), 5);
+        assert_eq!((hl.next(), hl.status()), (None, "RSS:Empty"));
+        let mut bad = Headlines::default();
+        bad.mark_failed();
+        assert_eq!(bad.status(), "RSS:Err");
+    }
+
+    #[tokio::test]
+    async fn local_feed_file() {
+        let path = std::env::temp_dir().join(format!("athena-rss-{}.xml", std::process::id()));
+        std::fs::write(&path, ATOM).unwrap();
+        let specs = parse_specs(&[
+            format!(r#"{{"name":"blog","url":"file://{}","count":1}}"#, path.display()),
+            r#"{"name":"","url":"x"}"#.into(),
+            "oops".into(),
+        ]);
+        assert_eq!(specs.len(), 1);
+
+        let mut feed = RssFeed::new(specs[0].clone());
+        let items = feed.refresh(&crate::mock_http::http()).await.unwrap();
+        assert!(!feed.is_due());
+        let mut hl = Headlines::default();
+        hl.merge(items, feed.keep());
+        assert_eq!(hl.next().as_deref(), Some("Hello world"));
+        assert_eq!(hl.next().as_deref(), Some("Hello world"));
+        let _ = std::fs::remove_file(&path);
+
+        let mut missing = RssFeed::new(FeedSpec { url: "/nonexistent/feed.xml".into(), ..specs[0].clone() });
+        assert!(missing.refresh(&crate::mock_http::http()).await.is_none());
+    }
+}
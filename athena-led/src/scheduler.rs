@@ -4,10 +4,16 @@
 // 数据来自 monitor.rs，硬件输出走 led_screen
 // ==========================================
 use crate::control::SharedControl;
+use crate::gauge;
+use crate::history;
 use crate::led_screen;
 use crate::monitor::SystemMonitor;
 use crate::mqtt::MqttHandle;
 use crate::net_agent::NetHandle;
+use crate::ticker;
+use crate::timesync;
+use crate::tz::{self, PosixTz};
+use crate::weather;
 use crate::Args;
 use anyhow::Result;
 use chrono::{Local, NaiveTime};
@@ -29,33 +35,28 @@ struct ProfileConfig {
 }
 
 fn get_seconds_until_wake(wake_time_str: &str) -> u64 {
-    let now = Local::now();
-
-    // 1. 解析目标唤醒时间
-    let wake_time = match NaiveTime::parse_from_str(wake_time_str, "%H:%M") {
-        Ok(t) => t,
-        Err(_) => return 60, // 解析失败兜底
-    };
-
-    // 2. 构造今天的唤醒时间点 (安全处理夏令时/不存在的时间)
-    let mut target_dt = match now.date_naive().and_time(wake_time).and_local_timezone(Local).latest() {
-        Some(dt) => dt,
-        None => return 60, // 极罕见的夏令时跳跃导致时间不存在，兜底睡 60 秒后重试
-    };
-
-    // 3. 如果唤醒时间比现在早 (比如现在23:00, 唤醒是07:00)，说明是"明天"
-    if target_dt <= now {
-        target_dt = target_dt + chrono::Duration::days(1);
+    // 解析失败兜底睡 60 秒后重试
+    match NaiveTime::parse_from_str(wake_time_str, "%H:%M") {
+        Ok(wake) => seconds_until_wake(chrono::Utc::now().timestamp(), wake, tz::system()),
+        Err(_) => 60,
     }
+}
 
-    // 4. 计算秒数差
-    let duration = target_dt.signed_duration_since(now).num_seconds();
-
-    // 5. 加上 2 秒缓冲，确保醒来时肯定过了时间点
-    if duration > 0 {
-        (duration as u64) + 2
-    } else {
-        60
+/// 🧪 [可测试的纯函数] 距离下一次本地墙上时间到达 wake 的秒数 (v2.6.0 按 POSIX TZ 规则处理夏令时)
+/// - 拨快当天 wake 落在被跳过的那一小时里: 按切换前偏移换算 (02:30 -> 03:30)
+/// - 拨回当天 wake 出现两次: 取较早的一次
+fn seconds_until_wake(now: i64, wake: NaiveTime, tz: &PosixTz) -> u64 {
+    let today = tz.to_local(now).date();
+    // 1. 今天的唤醒时刻已过 (比如现在 23:00, 唤醒是 07:00)，就是"明天"
+    let target = [today, today + chrono::Duration::days(1), today + chrono::Duration::days(2)]
+        .into_iter()
+        .map(|d| tz.to_utc(d.and_time(wake)))
+        .find(|&t| t > now);
+
+    // 2. 加上 2 秒缓冲，确保醒来时肯定过了时间点
+    match target {
+        Some(t) => (t - now) as u64 + 2,
+        None => 60,
     }
 }
 
@@ -88,7 +89,8 @@ fn is_in_window(now: NaiveTime, start_str: &str, end_str: &str) -> bool {
 
 /// 判断当前时间是否在休眠区间内
 fn is_sleep_time(start_str: &str, end_str: &str) -> bool {
-    is_in_window(Local::now().time(), start_str, end_str)
+    let now = tz::system().to_local(chrono::Utc::now().timestamp());
+    is_in_window(now.time(), start_str, end_str)
 }
 
 // 🌟 当前应使用的亮度: 控制接口锁定 > 夜间低亮度 > 常规亮度
@@ -103,6 +105,33 @@ fn effective_light(args: &Args, control: &SharedControl) -> u8 {
     }
 }
 
+// 🌅 sun 模块文本: 参数填 "lat,lon" 本地直算; 留空用 IP 定位 (后台代理提供)
+// 可附加模式: "moon" / "moonrise" / "twilight" / "day" / "next" (如 "39.90,116.40/moon")
+fn sun_text(param: &str, net: &NetHandle) -> String {
+    use crate::sun::SunMode;
+    let (mode, manual) = crate::sun::parse_param(param);
+    match (mode, manual.or_else(|| net.coords())) {
+        (SunMode::RiseSet, None) => net.sun(),
+        // 月相与位置无关，没有坐标也能算
+        (SunMode::Moon, coords) => {
+            let (lat, lon) = coords.unwrap_or((0.0, 0.0));
+            crate::sun::today_mode_string(mode, lat, lon)
+        }
+        (_, Some((lat, lon))) => crate::sun::today_mode_string(mode, lat, lon),
+        (_, None) => "SUN:--".to_string(),
+    }
+}
+
+// 🌦️ weather 模块的静态文本参数: 当前天气的单个字段或扩展数据 (见 weather.rs)
+fn weather_text(param: &str, net: &NetHandle, args: &Args) -> String {
+    let units = weather::Units::parse(&args.weather_unit, &args.wind_unit);
+    match (weather::parse_field(param), weather::parse_extra(param)) {
+        (Some(field), _) => net.weather_field(field, units),
+        (None, Some(mode)) => net.weather_extra(mode, units),
+        (None, None) => String::new(),
+    }
+}
+
 /// 🧪 [可测试的纯函数] 解析 profile 参数为频道/模块结构
 /// 语法: "模块[:参数][#时长]"，空格分隔模块，每个字符串一个频道
 fn parse_profiles(profile_args: &[String], default_secs: u64) -> Vec<ProfileConfig> {
@@ -188,7 +217,7 @@ pub async fn process_loop(
         }
 
         // 🌟 判断当前是否处于“临时唤醒”保护期
-        let is_manual_awake = manual_wake_expire.map_or(false, |exp| exp > std::time::Instant::now());
+        let is_manual_awake = manual_wake_expire.is_some_and(|exp| exp > std::time::Instant::now());
 
         // 🌟 [处理夜间休眠] (仅在保护期外，且满足时间时才休眠)
         if !is_manual_awake && is_sleep_time(&args.sleep_start, &args.sleep_end) {
@@ -338,6 +367,14 @@ pub async fn process_loop(
                     let sensor_id = if module.param.is_empty() { "4" } else { &module.param };
                     text_to_show = monitor.get_single_temp(sensor_id);
                 }
+                "quota" => text_to_show = monitor.get_quota_string(&module.param),
+                "disk" => text_to_show = monitor.get_disk_string(&module.param),
+                "wan" => text_to_show = monitor.get_wan_string(&module.param),
+                // 📶 无线射频: 参数为射频编号 (phyN)，缺省 0
+                "wifi" | "wifi_ch" | "wifi_rssi" => {
+                    let radio = if module.param.is_empty() { "0" } else { &module.param };
+                    text_to_show = monitor.get_wifi_string(&module.name, radio);
+                }
 
                 // 🌟 [解耦] 网络类数据全部改读后台快照，渲染永不等网络
                 "ip" => text_to_show = net.ip(),
@@ -367,21 +404,19 @@ pub async fn process_loop(
                 // ==========================================
                 // 🌟 [新功能模块] 倒数日 / 网络延迟 / 连接数
                 // ==========================================
-                "countdown" => text_to_show = monitor.get_countdown(&module.param),
+                "countdown" => text_to_show = timesync::dated(|| monitor.get_countdown(&module.param)),
                 "ping" => text_to_show = net.ping(&module.param),
+                "dns" => text_to_show = net.dns(&module.param),
                 "conn" => text_to_show = monitor.get_conntrack(),
 
                 // ==========================================
                 // 🌟 [v2.4.0 新功能模块] 农历 / 日出日落 / MQTT
                 // ==========================================
-                "lunar" => text_to_show = crate::lunar::today_string(),
-                "sun" => {
-                    // 参数填 "lat,lon" 本地直算; 留空用 IP 定位 (后台代理提供)
-                    text_to_show = match crate::sun::parse_coords(&module.param) {
-                        Some((lat, lon)) => crate::sun::today_string(lat, lon),
-                        None => net.sun(),
-                    };
-                }
+                "lunar" => text_to_show = timesync::dated(crate::lunar::today_string),
+                "sun" => text_to_show = timesync::dated(|| sun_text(&module.param, net)),
+                // 📍 [v2.6.0] 当前定位城市 (loc:coords 显示经纬度)
+                "loc" => text_to_show = net.location(&module.param),
+                "agenda" => text_to_show = timesync::dated(|| net.agenda()),
                 "mqtt" => text_to_show = mqtt.text(),
 
                 "banner" => {
@@ -391,6 +426,7 @@ pub async fn process_loop(
                 "http_custom" => {
                     text_to_show = net.http_text();
                 }
+                "http" => text_to_show = net.http(&module.param),
 
                 // ==========================================
                 // 🌟 [向下兼容合并] 时间与日期组
@@ -407,7 +443,7 @@ pub async fn process_loop(
                         "time_sec" => {
                             let start = Instant::now();
                             while start.elapsed() < Duration::from_secs(module.duration) {
-                                let time_str = Local::now().format("%H^%M^%S").to_string();
+                                let time_str = timesync::clock_text(Local::now().format("%H^%M^%S").to_string());
                                 let _ = screen.write_data(time_str.as_bytes(), get_leds(monitor, args)).await;
                                 tokio::select! {
                                     _ = tokio::time::sleep(Duration::from_millis(100)) => {}
@@ -420,7 +456,7 @@ pub async fn process_loop(
                             let mut time_flag = false;
                             let mut last_tick = Instant::now();
                             while start.elapsed() < Duration::from_secs(module.duration) {
-                                let mut time_str = Local::now().format("%H:%M").to_string();
+                                let mut time_str = timesync::clock_text(Local::now().format("%H:%M").to_string());
                                 if time_flag { time_str = time_str.replace(':', ";"); }
                                 let _ = screen.write_data(time_str.as_bytes(), get_leds(monitor, args)).await;
                                 if last_tick.elapsed().as_secs() >= 1 {
@@ -441,7 +477,7 @@ pub async fn process_loop(
                                 let display_text = if cycle < 1500 {
                                     Local::now().format("%a").to_string().to_uppercase()
                                 } else {
-                                    Local::now().format("%H:%M").to_string()
+                                    timesync::clock_text(Local::now().format("%H:%M").to_string())
                                 };
                                 let _ = screen.write_data(display_text.as_bytes(), get_leds(monitor, args)).await;
                                 tokio::select! {
@@ -451,24 +487,27 @@ pub async fn process_loop(
                             }
                         }
                         // --- 静态时间与日期 ---
-                        "time" => text_to_show = Local::now().format("%H:%M").to_string(),
+                        "time" => text_to_show = timesync::clock_text(Local::now().format("%H:%M").to_string()),
                         "date" => text_to_show = Local::now().format("%m-%d").to_string(),
                         "date_y" => text_to_show = Local::now().format("%y-%m-%d").to_string(),
                         "date_Y" => text_to_show = Local::now().format("%Y.%m.%d").to_string(),
                         "week_only" => text_to_show = Local::now().format("%a").to_string().to_uppercase(),
-                        _ => text_to_show = Local::now().format("%H:%M").to_string(), // 兜底
+                        _ => text_to_show = timesync::clock_text(Local::now().format("%H:%M").to_string()), // 兜底
                     }
                 }
 
+                // --- 天气扩展 / 单个字段: 明日预报 / 空气质量 / 降水概率 / 湿度 / 风速 (静态文本) ---
+                "weather" if weather::is_text_param(&module.param) => text_to_show = weather_text(&module.param, net, args),
+
                 // --- 动态模块: 天气动画 (智能双模版：静态防抖 + 循环滚动) ---
                 "weather" => {
                     // 🌟 [解耦] 读后台快照 (后台代理已带 30min 缓存 + 失败退避 + 旧数据回退)
-                    let full_text = net.weather();
-                    let (static_icon, raw_rest) = match full_text.split_once(' ') {
-                        Some((icon, rest)) => (icon, rest),
-                        None => {
-                            // 解析失败时，直接显示原文，防止极端乱码卡死
-                            let _ = screen.write_data(full_text.as_bytes(), get_leds(monitor, args)).await;
+                    let units = weather::Units::parse(&args.weather_unit, &args.wind_unit);
+                    let report = match net.weather() {
+                        Ok(report) => report,
+                        Err(msg) => {
+                            // 还没有数据 (Wait... / W:Err(U) 等)，直接显示提示
+                            let _ = screen.write_data(msg.as_bytes(), get_leds(monitor, args)).await;
                             // 🌟 [修复] 短暂停留 2 秒再切走 (可被按键打断，不再吞掉打断信号)
                             let mut wait_interrupted = false;
                             tokio::select! {
@@ -498,21 +537,16 @@ pub async fn process_loop(
                         }
                     };
 
-                    let clean_rest = raw_rest.trim();
+                    let static_icon = report.condition.icon();
 
                     // 🌟 记录这个模块开始的绝对时间，用于整体倒计时控制
                     let start_module = Instant::now();
 
                     if args.weather_format == "simple" {
                         // ==========================================
-                        // 【策略 A】精简模式：截取数字，原地动画 + 强制静态
+                        // 【策略 A】精简模式：图标 + 当前温度，原地动画 + 强制静态
                         // ==========================================
-                        let mut temp_val = String::new();
-                        for (i, c) in clean_rest.chars().enumerate() {
-                            if (i == 0 && c == '-') || c.is_ascii_digit() || c == '.' { temp_val.push(c); }
-                            else { break; }
-                        }
-                        let temp_part_str = if temp_val.starts_with('-') { temp_val } else { format!("{}℃", temp_val) };
+                        let temp_part_str = report.temp_short(units);
 
                         let mut frame_flag = true;
                         let mut last_frame = Instant::now();
@@ -539,7 +573,7 @@ pub async fn process_loop(
                         // ==========================================
                         // 【策略 B】完整模式：长文本滚动 + 停顿 1 秒循环
                         // ==========================================
-                        let display_text = format!("{} {}", static_icon, clean_rest);
+                        let display_text = report.display(units);
 
                         while start_module.elapsed() < Duration::from_secs(module.duration) {
                             tokio::select! {
@@ -561,8 +595,133 @@ pub async fn process_loop(
                         }
                     }
                 }
-                "stock" => {
-                    text_to_show = net.stock();
+                // ==========================================
+                // 📈 行情: param 为空时轮流显示全部品种，否则只显示该代号
+                // 显示期间上/下箭头灯改为指示涨跌 (仍受 disable_led_up/down 约束)
+                // ==========================================
+                "stock" | "ticker" => {
+                    let start = Instant::now();
+                    let mut item_idx = 0;
+                    let mut last_switch = Instant::now();
+                    while start.elapsed() < Duration::from_secs(module.duration) {
+                        let quotes = net.quotes(&module.param);
+                        // 没配 --ticker / --stock-url (或代号不在配置里): 不占时长，直接轮到下一个模块
+                        if quotes.is_empty() { break; }
+                        if last_switch.elapsed() >= Duration::from_secs(5) {
+                            item_idx += 1;
+                            last_switch = Instant::now();
+                        }
+                        let (display_text, trend) = quotes[item_idx % quotes.len()].clone();
+                        tokio::select! {
+                            _ = async {
+                                let mut leds = get_leds(monitor, args) & !(ticker::LED_UP | ticker::LED_DOWN);
+                                if !args.disable_led_up { leds |= trend & ticker::LED_UP; }
+                                if !args.disable_led_down { leds |= trend & ticker::LED_DOWN; }
+                                let _ = screen.write_data(display_text.as_bytes(), leds).await;
+                                tokio::time::sleep(Duration::from_millis(100)).await;
+                            } => {}
+                            Ok(_) = rx.changed() => { module_interrupted = true; break; }
+                        }
+                    }
+                }
+                // ==========================================
+                // 📰 RSS 标题: 时长内一条接一条完整滚动 (滚完停 1 秒)，优先播没播过的
+                // ==========================================
+                "rss" => {
+                    let start = Instant::now();
+                    while start.elapsed() < Duration::from_secs(module.duration) {
+                        let display_text = net.rss_next(&module.param);
+                        tokio::select! {
+                            _ = async {
+                                let _ = screen.write_data(display_text.as_bytes(), get_leds(monitor, args)).await;
+                                tokio::time::sleep(Duration::from_secs(1)).await;
+                            } => {}
+                            Ok(_) = rx.changed() => { module_interrupted = true; break; }
+                        }
+                    }
+                }
+                // ==========================================
+                // 🏆 局域网流量排行: param = 前 N 名 (默认 3)，时长内轮流显示
+                // ==========================================
+                "top" => {
+                    let n = module.param.parse::<usize>().ok().filter(|n| *n > 0).unwrap_or(3);
+                    // 每名至少停留 2 秒，时长不够时只轮到前几名
+                    let per_item = Duration::from_secs((module.duration / n as u64).max(2));
+                    let start = Instant::now();
+                    let mut item_idx = 0;
+                    let mut last_switch = Instant::now();
+                    while start.elapsed() < Duration::from_secs(module.duration) {
+                        let lines = monitor.get_top_clients(n);
+                        if last_switch.elapsed() >= per_item {
+                            item_idx += 1;
+                            last_switch = Instant::now();
+                        }
+                        let display_text = lines[item_idx % lines.len()].clone();
+                        tokio::select! {
+                            _ = async {
+                                let _ = screen.write_data(display_text.as_bytes(), get_leds(monitor, args)).await;
+                                tokio::time::sleep(Duration::from_millis(100)).await;
+                            } => {}
+                            Ok(_) = rx.changed() => { module_interrupted = true; break; }
+                        }
+                    }
+                }
+                // ==========================================
+                // 📈 迷你折线图: param = 指标[/目标][/line]，每秒重画一次
+                // 指标: cpu / mem / temp (=temp4) / tempN / rx / tx / ping
+                // ==========================================
+                "spark" => {
+                    let (metric, target, line) = history::parse_param(&module.param);
+                    let metric = if metric == "temp" { "temp4".to_string() } else { metric };
+                    let scale = history::scale_for(&metric);
+                    let start = Instant::now();
+                    while start.elapsed() < Duration::from_secs(module.duration) {
+                        let samples = if metric == "ping" {
+                            net.ping_history(&target)
+                        } else {
+                            monitor.get_history(&metric)
+                        };
+                        tokio::select! {
+                            _ = async {
+                                let leds = get_leds(monitor, args);
+                                if samples.is_empty() {
+                                    let _ = screen.write_data(b"WAIT", leds).await;
+                                } else {
+                                    let _ = screen.write_frame(&history::render(&samples, scale, line), leds);
+                                }
+                                tokio::time::sleep(Duration::from_secs(1)).await;
+                            } => {}
+                            Ok(_) = rx.changed() => { module_interrupted = true; break; }
+                        }
+                    }
+                }
+                // ==========================================
+                // 📊 条形仪表: param = cpu / mem / conn / quota / cores，每 0.5 秒重画
+                // ==========================================
+                "gauge" => {
+                    let metric = if module.param.is_empty() { "cpu" } else { module.param.as_str() };
+                    let label = led_screen::glyph_columns(gauge::label_for(metric));
+                    let start = Instant::now();
+                    while start.elapsed() < Duration::from_secs(module.duration) {
+                        let body = if metric == "cores" {
+                            Some(gauge::cores(&monitor.get_core_usages()))
+                        } else {
+                            monitor
+                                .get_gauge_percent(metric)
+                                .map(|pct| gauge::bar(pct, gauge::bar_width(label.len())))
+                        };
+                        tokio::select! {
+                            _ = async {
+                                let leds = get_leds(monitor, args);
+                                match &body {
+                                    Some(cols) => { let _ = screen.write_frame(&gauge::compose(&label, cols), leds); }
+                                    None => { let _ = screen.write_data(b"G:--", leds).await; }
+                                }
+                                tokio::time::sleep(Duration::from_millis(500)).await;
+                            } => {}
+                            Ok(_) = rx.changed() => { module_interrupted = true; break; }
+                        }
+                    }
                 }
                 // ==========================================
                 // 🎬 动画模块专属分支 (支持按键秒切)
@@ -647,12 +806,27 @@ pub async fn process_loop(
                                 text_to_show = monitor.get_single_temp(sensor_id);
                             },
                             "dev"           => text_to_show = monitor.get_online_devices(),
+                            "quota"         => text_to_show = monitor.get_quota_string(&module.param),
+                            "disk"          => text_to_show = monitor.get_disk_string(&module.param),
+                            "wan"           => text_to_show = monitor.get_wan_string(&module.param),
+                            "wifi" | "wifi_ch" | "wifi_rssi" => {
+                                let radio = if module.param.is_empty() { "0" } else { &module.param };
+                                text_to_show = monitor.get_wifi_string(&module.name, radio);
+                            },
                             // 🌟 [新功能模块] 连接数/延迟实时刷新 (延迟由后台代理每10秒探测)
                             "conn"          => text_to_show = monitor.get_conntrack(),
                             "ping"          => text_to_show = net.ping(&module.param),
+                            "dns"           => text_to_show = net.dns(&module.param),
+                            "weather" if weather::is_text_param(&module.param) => text_to_show = weather_text(&module.param, net, args),
+                            "http"          => text_to_show = net.http(&module.param),
+                            // 🌅 日出日落倒计时等需要逐秒刷新 (其余模式重算也很廉价)
+                            "sun"           => text_to_show = timesync::dated(|| sun_text(&module.param, net)),
+                            "loc"           => text_to_show = net.location(&module.param),
+                            // 📅 日程跨过开始时间就换下一个
+                            "agenda"        => text_to_show = timesync::dated(|| net.agenda()),
 
                             // --- 🕒 时间组 (防止跨分) ---
-                            "time"          => text_to_show = Local::now().format("%H:%M").to_string(),
+                            "time"          => text_to_show = timesync::clock_text(Local::now().format("%H:%M").to_string()),
 
                             _ => {} // 纯静态模块（如 Banner）不处理
                         }
@@ -757,6 +931,27 @@ mod tests {
         assert!(!is_in_window(t(12, 0), "banana", "14:00")); // 格式错误
     }
 
+    #[test]
+    fn wake_across_dst_transitions() {
+        let cet = PosixTz::parse("CET-1CEST,M3.5.0,M10.5.0/3").unwrap();
+        let at = |mo, d, h, mi| {
+            chrono::NaiveDate::from_ymd_opt(2026, mo, d).unwrap().and_hms_opt(h, mi, 0).unwrap().and_utc().timestamp()
+        };
+        // 平常夜晚: 本地 23:00 (22:00Z) 睡到 07:00 = 8 小时
+        assert_eq!(seconds_until_wake(at(1, 10, 22, 0), t(7, 0), &cet), 8 * 3600 + 2);
+        // 拨快那晚 (3/28 -> 3/29) 只睡 7 小时；拨回那晚 (10/24 -> 10/25) 睡 9 小时
+        assert_eq!(seconds_until_wake(at(3, 28, 22, 0), t(7, 0), &cet), 7 * 3600 + 2);
+        assert_eq!(seconds_until_wake(at(10, 24, 21, 0), t(7, 0), &cet), 9 * 3600 + 2);
+        // 唤醒时刻 02:30 在拨快当天不存在 -> 03:30 CEST (01:30Z)
+        assert_eq!(seconds_until_wake(at(3, 28, 22, 0), t(2, 30), &cet), 3 * 3600 + 1800 + 2);
+        // 拨回当天 02:30 出现两次: 第一次 (00:30Z) 已过就不再等第二次，直接到明天
+        assert_eq!(seconds_until_wake(at(10, 25, 0, 45), t(2, 30), &cet), 24 * 3600 + 2700 + 2);
+
+        // 睡眠窗口判断用的本地时刻跟着夏令时走: 21:30Z 冬天是 22:30 (窗口外)，夏天是 23:30 (窗口内)
+        assert!(!is_in_window(cet.to_local(at(1, 10, 21, 30)).time(), "23:00", "07:00"));
+        assert!(is_in_window(cet.to_local(at(7, 10, 21, 30)).time(), "23:00", "07:00"));
+    }
+
     #[test]
     fn profiles_full_syntax() {
         let input = vec![
@@ -776,17 +971,17 @@ mod tests {
     #[test]
     fn profiles_defaults_and_edge() {
         // 无参数无时长 -> 默认时长, 空参数
-        let p = parse_profiles(&vec!["cpu mem".to_string()], 5);
+        let p = parse_profiles(&["cpu mem".to_string()], 5);
         assert_eq!(p[0].modules[0].duration, 5);
         assert_eq!(p[0].modules[0].param, "");
         // 时长非法 -> 回落默认
-        let p = parse_profiles(&vec!["cpu#abc".to_string()], 5);
+        let p = parse_profiles(&["cpu#abc".to_string()], 5);
         assert_eq!(p[0].modules[0].duration, 5);
         // 空频道字符串被丢弃
-        let p = parse_profiles(&vec!["  ".to_string(), "cpu".to_string()], 5);
+        let p = parse_profiles(&["  ".to_string(), "cpu".to_string()], 5);
         assert_eq!(p.len(), 1);
         // 带冒号参数含日期 (countdown:2027-06-07#5)
-        let p = parse_profiles(&vec!["countdown:2027-06-07#5".to_string()], 5);
+        let p = parse_profiles(&["countdown:2027-06-07#5".to_string()], 5);
         assert_eq!(p[0].modules[0].name, "countdown");
         assert_eq!(p[0].modules[0].param, "2027-06-07");
     }
@@ -3,15 +3,26 @@
 // NOAA/《Almanac for Computers》标准天文公式，纯本地计算零网络。
 // 经纬度来源: 手动参数 "lat,lon" 或 net_agent 的 IP 定位。
 // 屏显格式: "6:02~19:23" (日出~日落)
+// 🌙 [扩展] 同样纯本地计算的月相/月出月落/民用晨昏蒙影/昼长，
+// 通过模块参数选择: "sun:moon" / "sun:39.90,116.40/twilight"
 // ==========================================
+use crate::tz::PosixTz;
+use chrono::{Datelike, NaiveDate, NaiveTime};
 
 const D2R: f64 = std::f64::consts::PI / 180.0;
 
+// 官方日出日落天顶角 (含大气折射修正)
+const ZENITH_OFFICIAL: f64 = 90.833;
+// 民用晨昏蒙影: 太阳位于地平线下 6°
+const ZENITH_CIVIL: f64 = 96.0;
+
 /// 计算日出或日落的 UTC 时刻 (小时, 0~24)。极昼/极夜返回 None
-fn calc_event_utc(day_of_year: f64, lat: f64, lon: f64, rising: bool) -> Option<f64> {
-    // 官方民用日出日落天顶角 (含大气折射修正)
-    let zenith: f64 = 90.833;
+pub fn calc_event_utc(day_of_year: f64, lat: f64, lon: f64, rising: bool) -> Option<f64> {
+    calc_zenith_utc(day_of_year, lat, lon, rising, ZENITH_OFFICIAL)
+}
 
+/// 太阳到达指定天顶角的 UTC 时刻 (日出日落 / 晨昏蒙影共用)
+fn calc_zenith_utc(day_of_year: f64, lat: f64, lon: f64, rising: bool, zenith: f64) -> Option<f64> {
     // 1. 经度换算小时 + 近似事件时刻
     let lng_hour = lon / 15.0;
     let t = if rising {
@@ -62,16 +73,16 @@ fn fmt_hour(hours: f64) -> String {
     format!("{}:{:02}", total_min / 60, total_min % 60)
 }
 
-/// 生成屏显字符串: "6:02~19:23"。tz_hours = 本地时区偏移 (东八区 = 8.0)
-pub fn sun_string(lat: f64, lon: f64, day_of_year: u32, tz_hours: f64) -> String {
-    let doy = day_of_year as f64;
+/// 生成屏显字符串: "6:02~19:23"。date = 本地日期，tz = 本地时区规则 (每个事件按自身时刻的偏移换算)
+pub fn sun_string(lat: f64, lon: f64, date: NaiveDate, tz: &PosixTz) -> String {
+    let doy = date.ordinal() as f64;
     match (
         calc_event_utc(doy, lat, lon, true),
         calc_event_utc(doy, lat, lon, false),
     ) {
         (Some(rise_utc), Some(set_utc)) => {
-            let rise = (rise_utc + tz_hours).rem_euclid(24.0);
-            let set = (set_utc + tz_hours).rem_euclid(24.0);
+            let rise = tz.local_hour(date, rise_utc);
+            let set = tz.local_hour(date, set_utc);
             format!("{}~{}", fmt_hour(rise), fmt_hour(set))
         }
         // 高纬度极昼/极夜
@@ -79,12 +90,243 @@ pub fn sun_string(lat: f64, lon: f64, day_of_year: u32, tz_hours: f64) -> String
     }
 }
 
+/// 民用晨昏蒙影: "C4:10~20:22" (天亮~天黑)
+pub fn twilight_string(lat: f64, lon: f64, date: NaiveDate, tz: &PosixTz) -> String {
+    let doy = date.ordinal() as f64;
+    match (
+        calc_zenith_utc(doy, lat, lon, true, ZENITH_CIVIL),
+        calc_zenith_utc(doy, lat, lon, false, ZENITH_CIVIL),
+    ) {
+        (Some(dawn_utc), Some(dusk_utc)) => {
+            let dawn = tz.local_hour(date, dawn_utc);
+            let dusk = tz.local_hour(date, dusk_utc);
+            format!("C{}~{}", fmt_hour(dawn), fmt_hour(dusk))
+        }
+        // 白夜 (整夜不进入民用蒙影) 或极夜
+        _ => "C:--".to_string(),
+    }
+}
+
+/// 昼长: "14h32m"。极昼 "24h0m"，极夜 "0h0m"
+pub fn day_length_string(lat: f64, lon: f64, day_of_year: u32) -> String {
+    let doy = day_of_year as f64;
+    let minutes = match (
+        calc_event_utc(doy, lat, lon, true),
+        calc_event_utc(doy, lat, lon, false),
+    ) {
+        (Some(rise), Some(set)) => ((set - rise).rem_euclid(24.0) * 60.0).round() as i64,
+        // 无日出日落: 看正午太阳在不在地平线上 (夏半年北半球高纬 = 极昼)
+        _ => {
+            let summer = (80..=266).contains(&day_of_year);
+            if summer == (lat >= 0.0) { 24 * 60 } else { 0 }
+        }
+    };
+    format!("{}h{}m", minutes / 60, minutes % 60)
+}
+
+// ==========================================
+// 🌙 月相 (平均朔望月近似，误差约 ±0.5 天，屏显足够)
+// ==========================================
+// 平均朔望月 (天)
+const SYNODIC_MONTH: f64 = 29.530588853;
+// 参考新月: 2000-01-06 18:14 UTC (儒略日)
+const NEW_MOON_JD: f64 = 2451550.259;
+
+// Unix 秒 -> 儒略日
+fn julian_day(unix_secs: i64) -> f64 {
+    unix_secs as f64 / 86400.0 + 2440587.5
+}
+
+/// 月龄 (距上次新月的天数, 0 ~ 29.53)
+pub fn moon_age(unix_secs: i64) -> f64 {
+    (julian_day(unix_secs) - NEW_MOON_JD).rem_euclid(SYNODIC_MONTH)
+}
+
+/// 月面照亮比例 (0.0 = 新月, 1.0 = 满月)
+pub fn moon_illumination(unix_secs: i64) -> f64 {
+    let angle = moon_age(unix_secs) / SYNODIC_MONTH * 2.0 * std::f64::consts::PI;
+    (1.0 - angle.cos()) / 2.0
+}
+
+/// 八相位图标 (字模见 char_dict: 🌑🌒🌓🌔🌕🌖🌗🌘)
+pub fn moon_phase_glyph(unix_secs: i64) -> char {
+    const GLYPHS: [char; 8] = ['🌑', '🌒', '🌓', '🌔', '🌕', '🌖', '🌗', '🌘'];
+    // 每个相位占 1/8 周期，以相位中心为界 (新月 = -1/16 ~ +1/16)
+    let idx = ((moon_age(unix_secs) / SYNODIC_MONTH * 8.0) + 0.5).floor() as usize % 8;
+    GLYPHS[idx]
+}
+
+/// 月相屏显: "🌔 63%"
+pub fn moon_string(unix_secs: i64) -> String {
+    format!(
+        "{} {:.0}%",
+        moon_phase_glyph(unix_secs),
+        moon_illumination(unix_secs) * 100.0
+    )
+}
+
+// ==========================================
+// 🌙 月出月落 (低精度月球位置 + 逐小时扫描地平线穿越，误差约 ±10 分钟)
+// ==========================================
+// 月亮视半径 + 折射 - 地平视差后的地平高度修正 (度)
+const MOON_HORIZON: f64 = 0.133;
+
+// 月球赤经/赤纬 (弧度)。d = 距 J2000.0 的天数
+fn moon_ra_dec(d: f64) -> (f64, f64) {
+    let l = D2R * (218.316 + 13.176396 * d); // 平黄经
+    let m = D2R * (134.963 + 13.064993 * d); // 平近点角
+    let f = D2R * (93.272 + 13.229350 * d); // 升交角距
+
+    let lambda = l + D2R * 6.289 * m.sin(); // 黄经
+    let beta = D2R * 5.128 * f.sin(); // 黄纬
+    let e = D2R * 23.4397; // 黄赤交角
+
+    let ra = (lambda.sin() * e.cos() - beta.tan() * e.sin()).atan2(lambda.cos());
+    let dec = (beta.sin() * e.cos() + beta.cos() * e.sin() * lambda.sin()).asin();
+    (ra, dec)
+}
+
+// 月亮地平高度 (度)
+fn moon_altitude(unix_secs: f64, lat: f64, lon: f64) -> f64 {
+    let d = unix_secs / 86400.0 + 2440587.5 - 2451545.0;
+    let (ra, dec) = moon_ra_dec(d);
+    let sidereal = D2R * (280.16 + 360.9856235 * d) + D2R * lon;
+    let h = sidereal - ra;
+    let phi = lat * D2R;
+    (phi.sin() * dec.sin() + phi.cos() * dec.cos() * h.cos()).asin() / D2R
+}
+
+/// 当地某天 [day_start, day_start+24h) 内的月出/月落时刻 (Unix 秒)。
+/// 月亮每天晚约 50 分钟，某天没有月出或月落是正常现象 (返回 None)
+pub fn moon_rise_set(day_start_unix: i64, lat: f64, lon: f64) -> (Option<i64>, Option<i64>) {
+    let t0 = day_start_unix as f64;
+    let alt = |hour: f64| moon_altitude(t0 + hour * 3600.0, lat, lon) - MOON_HORIZON;
+
+    let mut rise = None;
+    let mut set = None;
+    let mut prev = alt(0.0);
+    // 10 分钟步长线性插值，足够捕捉每次穿越
+    let step = 1.0 / 6.0;
+    let mut hour = step;
+    while hour <= 24.0 + 1e-9 {
+        let cur = alt(hour);
+        if prev < 0.0 && cur >= 0.0 && rise.is_none() {
+            let frac = prev / (prev - cur);
+            rise = Some((t0 + (hour - step + frac * step) * 3600.0) as i64);
+        } else if prev >= 0.0 && cur < 0.0 && set.is_none() {
+            let frac = prev / (prev - cur);
+            set = Some((t0 + (hour - step + frac * step) * 3600.0) as i64);
+        }
+        prev = cur;
+        hour += step;
+    }
+    (rise, set)
+}
+
+/// 月出月落屏显: "🌕18:22~5:40" (无月出/月落的一侧显示 "--")。
+/// day_start_unix 为当地零点，时刻按距零点的小时数显示
+pub fn moon_rise_set_string(day_start_unix: i64, lat: f64, lon: f64, glyph: char) -> String {
+    let fmt = |t: Option<i64>| match t {
+        Some(secs) => fmt_hour((secs - day_start_unix) as f64 / 3600.0),
+        None => "--".to_string(),
+    };
+    let (rise, set) = moon_rise_set(day_start_unix, lat, lon);
+    format!("{}{}~{}", glyph, fmt(rise), fmt(set))
+}
+
+// ==========================================
+// ⏳ 日出日落倒计时: 显示下一个事件还有多久 ("SET 1:23" / "RISE 7:05")
+// ==========================================
+/// 下一个日出/日落事件: (是否日出, Unix 秒)。tz = 本地时区规则。
+/// 扫描昨天/今天/明天三天的事件，取严格晚于 now 的最早一个；极昼/极夜返回 None
+pub fn next_event(now_unix: i64, lat: f64, lon: f64, tz: &PosixTz) -> Option<(bool, i64)> {
+    let today = tz.to_local(now_unix).date();
+
+    let mut best: Option<(bool, i64)> = None;
+    for date in [today.pred_opt()?, today, today.succ_opt()?] {
+        let doy = date.ordinal() as f64;
+        for rising in [true, false] {
+            if let Some(utc_hour) = calc_event_utc(doy, lat, lon, rising) {
+                // 与 sun_string 一致: 事件归到当地日期上
+                let t = tz.event_at(date, utc_hour);
+                if t > now_unix && best.is_none_or(|(_, b)| t < b) {
+                    best = Some((rising, t));
+                }
+            }
+        }
+    }
+    best
+}
+
+/// 倒计时屏显: "SET 1:23" (距日落 1 小时 23 分) / "RISE 7:05"
+pub fn next_event_string(now_unix: i64, lat: f64, lon: f64, tz: &PosixTz) -> String {
+    match next_event(now_unix, lat, lon, tz) {
+        Some((rising, t)) => {
+            let mins = (t - now_unix) / 60;
+            let label = if rising { "RISE" } else { "SET" };
+            format!("{} {}:{:02}", label, mins / 60, mins % 60)
+        }
+        None => "SUN:--".to_string(),
+    }
+}
+
+// ==========================================
+// 🎛️ 显示模式: 由模块参数选择 (与坐标用 '/' 分隔)
+// ==========================================
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SunMode {
+    RiseSet,   // 默认: "6:02~19:23"
+    Moon,      // 月相: "🌔 63%"
+    MoonRise,  // 月出月落: "🌕18:22~5:40"
+    Twilight,  // 民用晨昏蒙影: "C4:10~20:22"
+    DayLength, // 昼长: "14h32m"
+    Next,      // 下一事件倒计时: "SET 1:23" / "RISE 7:05" (每秒刷新)
+}
+
+/// 解析模块参数: "" / "lat,lon" / "moon" / "lat,lon/moon" (顺序不限)。
+/// 返回 (模式, 手动坐标)；无法识别的片段忽略
+pub fn parse_param(param: &str) -> (SunMode, Option<(f64, f64)>) {
+    let mut mode = SunMode::RiseSet;
+    let mut coords = None;
+    for part in param.split('/') {
+        if let Some(c) = parse_coords(part) {
+            coords = Some(c);
+            continue;
+        }
+        mode = match part.trim().to_lowercase().as_str() {
+            "moon" | "phase" => SunMode::Moon,
+            "moonrise" | "moonset" => SunMode::MoonRise,
+            "twilight" | "civil" => SunMode::Twilight,
+            "day" | "daylen" | "daylength" => SunMode::DayLength,
+            "next" | "countdown" => SunMode::Next,
+            _ => mode,
+        };
+    }
+    (mode, coords)
+}
+
 /// 便捷入口: 今天 + 本机时区
 pub fn today_string(lat: f64, lon: f64) -> String {
-    use chrono::{Datelike, Local, Offset};
-    let now = Local::now();
-    let tz_hours = now.offset().fix().local_minus_utc() as f64 / 3600.0;
-    sun_string(lat, lon, now.ordinal(), tz_hours)
+    today_mode_string(SunMode::RiseSet, lat, lon)
+}
+
+/// 便捷入口: 今天 + 本机时区，按模式生成屏显
+pub fn today_mode_string(mode: SunMode, lat: f64, lon: f64) -> String {
+    let tz = crate::tz::system();
+    let now = chrono::Utc::now().timestamp();
+    let today = tz.to_local(now).date();
+    match mode {
+        SunMode::RiseSet => sun_string(lat, lon, today, tz),
+        SunMode::Twilight => twilight_string(lat, lon, today, tz),
+        SunMode::DayLength => day_length_string(lat, lon, today.ordinal()),
+        SunMode::Moon => moon_string(now),
+        SunMode::Next => next_event_string(now, lat, lon, tz),
+        SunMode::MoonRise => {
+            // 本地零点 (夏令时切换恰在零点时按切换后的时刻)
+            let day_start = tz.to_utc(today.and_time(NaiveTime::MIN));
+            moon_rise_set_string(day_start, lat, lon, moon_phase_glyph(now))
+        }
+    }
 }
 
 /// 解析模块参数 "lat,lon" (如 "39.90,116.40")
@@ -106,6 +348,14 @@ pub fn parse_coords(param: &str) -> Option<(f64, f64)> {
 mod tests {
     use super::*;
 
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn cst() -> PosixTz {
+        PosixTz::parse("CST-8").unwrap()
+    }
+
     // "H:MM" -> 分钟数
     fn to_min(s: &str) -> i64 {
         let (h, m) = s.split_once(':').unwrap();
@@ -116,7 +366,7 @@ mod tests {
     fn beijing_summer_solstice() {
         // 北京 (39.90N, 116.40E) 2025-06-21 (第172天):
         // 日出约 04:46, 日落约 19:46 (东八区)
-        let s = sun_string(39.90, 116.40, 172, 8.0);
+        let s = sun_string(39.90, 116.40, date(2025, 6, 21), &cst());
         let (rise, set) = s.split_once('~').unwrap();
         assert!((to_min(rise) - to_min("4:46")).abs() <= 15, "rise={}", rise);
         assert!((to_min(set) - to_min("19:46")).abs() <= 15, "set={}", set);
@@ -125,7 +375,7 @@ mod tests {
     #[test]
     fn beijing_winter_solstice() {
         // 北京 2025-12-21 (第355天): 日出约 07:33, 日落约 16:53
-        let s = sun_string(39.90, 116.40, 355, 8.0);
+        let s = sun_string(39.90, 116.40, date(2025, 12, 21), &cst());
         let (rise, set) = s.split_once('~').unwrap();
         assert!((to_min(rise) - to_min("7:33")).abs() <= 15, "rise={}", rise);
         assert!((to_min(set) - to_min("16:53")).abs() <= 15, "set={}", set);
@@ -134,7 +384,7 @@ mod tests {
     #[test]
     fn polar_night() {
         // 北极圈内冬至应返回极夜占位
-        assert_eq!(sun_string(78.0, 15.0, 355, 1.0), "SUN:--");
+        assert_eq!(sun_string(78.0, 15.0, date(2025, 12, 21), &PosixTz::parse("CET-1").unwrap()), "SUN:--");
     }
 
     #[test]
@@ -144,4 +394,141 @@ mod tests {
         assert_eq!(parse_coords("999,0"), None);
         assert_eq!(parse_coords("abc"), None);
     }
+
+    #[test]
+    fn beijing_civil_twilight() {
+        // 北京夏至民用晨昏蒙影约 04:10 ~ 20:22; 冬至约 07:03 ~ 17:23
+        let s = twilight_string(39.90, 116.40, date(2025, 6, 21), &cst());
+        let (dawn, dusk) = s.trim_start_matches('C').split_once('~').unwrap();
+        assert!((to_min(dawn) - to_min("4:10")).abs() <= 15, "dawn={}", dawn);
+        assert!((to_min(dusk) - to_min("20:22")).abs() <= 15, "dusk={}", dusk);
+        let s = twilight_string(39.90, 116.40, date(2025, 12, 21), &cst());
+        let (dawn, dusk) = s.trim_start_matches('C').split_once('~').unwrap();
+        assert!((to_min(dawn) - to_min("7:03")).abs() <= 15, "dawn={}", dawn);
+        assert!((to_min(dusk) - to_min("17:23")).abs() <= 15, "dusk={}", dusk);
+        // 北极圈冬至: 整天达不到民用蒙影
+        assert_eq!(twilight_string(85.0, 15.0, date(2025, 12, 21), &PosixTz::parse("CET-1").unwrap()), "C:--");
+    }
+
+    #[test]
+    fn day_length() {
+        // 北京夏至昼长约 15 小时，冬至约 9 小时 20 分
+        assert_eq!(day_length_string(39.90, 116.40, 172), "15h0m");
+        assert_eq!(day_length_string(39.90, 116.40, 355), "9h20m");
+        // 斯瓦尔巴: 夏至极昼、冬至极夜
+        assert_eq!(day_length_string(78.0, 15.0, 172), "24h0m");
+        assert_eq!(day_length_string(78.0, 15.0, 355), "0h0m");
+    }
+
+    fn utc(y: i32, mo: u32, d: u32, h: u32, mi: u32) -> i64 {
+        use chrono::TimeZone;
+        chrono::Utc.with_ymd_and_hms(y, mo, d, h, mi, 0).unwrap().timestamp()
+    }
+
+    #[test]
+    fn moon_phases() {
+        // 2024-09-18 02:34 UTC 满月; 2024-04-08 18:21 UTC 新月 (日全食)
+        let full = utc(2024, 9, 18, 2, 34);
+        assert!(moon_illumination(full) > 0.98);
+        assert_eq!(moon_phase_glyph(full), '🌕');
+        assert_eq!(moon_string(full), "🌕 100%");
+        let new = utc(2024, 4, 8, 18, 21);
+        assert!(moon_illumination(new) < 0.02);
+        assert_eq!(moon_phase_glyph(new), '🌑');
+        // 2024-09-11 06:06 UTC 上弦; 2024-09-24 18:50 UTC 下弦
+        assert_eq!(moon_phase_glyph(utc(2024, 9, 11, 6, 6)), '🌓');
+        assert_eq!(moon_phase_glyph(utc(2024, 9, 24, 18, 50)), '🌗');
+        // 新月后第 3 天是蛾眉月，满月后第 4 天是亏凸月
+        assert_eq!(moon_phase_glyph(new + 3 * 86400), '🌒');
+        assert_eq!(moon_phase_glyph(full + 4 * 86400), '🌖');
+    }
+
+    #[test]
+    fn moonrise_tracks_phase() {
+        // 参考值 (北京 39.90N 116.40E，东八区，当地零点起算):
+        // 按 Meeus《Astronomical Algorithms》第 47 章月球位置 (黄经/黄纬/距离主要周期项，含 ΔT)
+        // 逐分钟求地平高度 h0 = 0.7275π - 34' 的穿越时刻得出
+        //   2024-09-17 (中秋，次日满月)  月落 4:26  月出 18:02
+        //   2024-04-08 (新月，日全食)    月出 5:27  月落 18:16
+        //   2024-09-11 (上弦)            月出 13:47 月落 22:32
+        // 本模块的低精度位置 + 10 分钟扫描应在 ±15 分钟内
+        for (day, rise_ref, set_ref) in [
+            (utc(2024, 9, 16, 16, 0), "18:02", "4:26"),
+            (utc(2024, 4, 7, 16, 0), "5:27", "18:16"),
+            (utc(2024, 9, 10, 16, 0), "13:47", "22:32"),
+        ] {
+            let (rise, set) = moon_rise_set(day, 39.90, 116.40);
+            let rise_min = (rise.unwrap() - day) / 60;
+            let set_min = (set.unwrap() - day) / 60;
+            assert!((rise_min - to_min(rise_ref)).abs() <= 15, "rise={} ref={}", rise_min, rise_ref);
+            assert!((set_min - to_min(set_ref)).abs() <= 15, "set={} ref={}", set_min, set_ref);
+        }
+        // 屏显格式
+        let s = moon_rise_set_string(utc(2024, 9, 16, 16, 0), 39.90, 116.40, '🌕');
+        assert!(s.starts_with('🌕') && s.contains('~'), "{}", s);
+    }
+
+    #[test]
+    fn param_modes() {
+        assert_eq!(parse_param(""), (SunMode::RiseSet, None));
+        assert_eq!(parse_param("39.90,116.40"), (SunMode::RiseSet, Some((39.90, 116.40))));
+        assert_eq!(parse_param("moon"), (SunMode::Moon, None));
+        assert_eq!(parse_param("39.90,116.40/twilight"), (SunMode::Twilight, Some((39.90, 116.40))));
+        assert_eq!(parse_param("moonrise/31.2,121.5"), (SunMode::MoonRise, Some((31.2, 121.5))));
+        assert_eq!(parse_param("day"), (SunMode::DayLength, None));
+        assert_eq!(parse_param("next"), (SunMode::Next, None));
+        assert_eq!(parse_param("banana"), (SunMode::RiseSet, None));
+    }
+
+    #[test]
+    fn next_event_countdown() {
+        // 北京 2024-09-17 (第261天) 日出约 5:57、日落约 18:19 (东八区)
+        // 本地 12:00 = 04:00 UTC: 下一个是日落，约 6 小时 19 分后
+        let noon = utc(2024, 9, 17, 4, 0);
+        let (rising, t) = next_event(noon, 39.90, 116.40, &cst()).unwrap();
+        assert!(!rising);
+        assert!(((t - noon) / 60 - (6 * 60 + 19)).abs() <= 15);
+        assert!(next_event_string(noon, 39.90, 116.40, &cst()).starts_with("SET 6:"));
+
+        // 本地 22:00: 下一个是次日日出，约 7 小时 58 分后
+        let night = utc(2024, 9, 17, 14, 0);
+        let (rising, t) = next_event(night, 39.90, 116.40, &cst()).unwrap();
+        assert!(rising);
+        assert!(((t - night) / 60 - (7 * 60 + 58)).abs() <= 15);
+
+        // 本地 03:00 (UTC 前一天 19:00，跨 UTC 日期): 下一个仍是当天日出
+        let early = utc(2024, 9, 16, 19, 0);
+        let (rising, t) = next_event(early, 39.90, 116.40, &cst()).unwrap();
+        assert!(rising);
+        assert!(((t - early) / 60 - (2 * 60 + 57)).abs() <= 15);
+        assert_eq!(next_event_string(early, 39.90, 116.40, &cst()).split(' ').next(), Some("RISE"));
+
+        // 事件刚过即切换到下一个
+        let (_, set) = next_event(noon, 39.90, 116.40, &cst()).unwrap();
+        assert_eq!(next_event(set, 39.90, 116.40, &cst()).map(|(r, _)| r), Some(true));
+
+        // 极夜无事件
+        assert_eq!(next_event_string(utc(2024, 12, 21, 12, 0), 85.0, 15.0, &PosixTz::parse("CET-1").unwrap()), "SUN:--");
+    }
+
+    #[test]
+    fn dst_switch_day() {
+        // 柏林 (52.52N, 13.40E) 2026-03-29 凌晨 02:00 拨快到 03:00:
+        // 日出日落都在切换之后，应按夏令时 (UTC+2) 显示，比前一天整体晚约 1 小时
+        let cet = PosixTz::parse("CET-1CEST,M3.5.0,M10.5.0/3").unwrap();
+        let before = sun_string(52.52, 13.40, date(2026, 3, 28), &cet);
+        let after = sun_string(52.52, 13.40, date(2026, 3, 29), &cet);
+        let (rise0, set0) = before.split_once('~').unwrap();
+        let (rise1, set1) = after.split_once('~').unwrap();
+        assert!((to_min(rise0) - to_min("6:00")).abs() <= 15, "rise={}", rise0);
+        assert!((to_min(rise1) - to_min("6:58")).abs() <= 15, "rise={}", rise1);
+        assert!((55..=60).contains(&(to_min(rise1) - to_min(rise0))), "{} / {}", before, after);
+        assert!((60..=65).contains(&(to_min(set1) - to_min(set0))), "{} / {}", before, after);
+
+        // 切换前 (本地 01:30 CET) 看倒计时: 日出按真实时刻算，只差约 4 小时 28 分而非 5 小时 28 分
+        let now = utc(2026, 3, 29, 0, 30);
+        let (rising, t) = next_event(now, 52.52, 13.40, &cet).unwrap();
+        assert!(rising);
+        assert!(((t - now) / 60 - (4 * 60 + 28)).abs() <= 15, "{}", (t - now) / 60);
+    }
 }
@@ -0,0 +1,285 @@
+// ==========================================
+// 📈 ticker.rs — 多品种行情 (股票/加密货币) 与涨跌幅 (stock / ticker 模块)
+// 以前只有一个 --stock-url，猜 price/last/close 字段，只和上一次轮询比涨跌，
+// 算出来的涨跌标志还被丢掉了。现在:
+//   - 任意多个品种，每个通过 --ticker 传入一个 JSON 对象 (可重复，init 脚本用 jshn 生成)
+//   - 字段映射用 JSONPath / JSON Pointer (同 http_source.rs)，可映射开盘价 / 昨收 / 涨跌幅
+//   - 有昨收 (或开盘价) 时显示当日涨跌幅 "BTC 67123 +1.23%"，涨跌决定上/下箭头灯
+//   - 没有参考价时退回旧行为: 与上一次轮询的价格比较
+//
+//   {"symbol":"BTC","url":"https://api.example.com/btc","price":"$.data.last",
+//    "prev_close":"$.data.prevClose","interval":30}
+//
+// 旧的 --stock-url 自动变成一个没有代号的品种 (字段仍按 price/last/close 猜)。
+// 参数: "ticker" / "stock" 轮流显示全部品种，"ticker:BTC" 只显示 BTC。
+// ==========================================
+use crate::http_source::json_path;
+use crate::http_client::Http;
+use serde::Deserialize;
+use serde_json::Value;
+use std::time::{Duration, Instant};
+
+const DEFAULT_INTERVAL: u64 = 30;
+const MIN_INTERVAL: u64 = 10;
+const RETRY_SECS: u64 = 60;
+
+// 上/下箭头灯的位 (与 monitor::get_global_led_flag 一致)
+pub const LED_UP: u8 = 4;
+pub const LED_DOWN: u8 = 8;
+
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct TickerSpec {
+    #[serde(default)]
+    pub symbol: String,
+    pub url: String,
+    // 以下为字段路径 ($.a.b 或 /a/b)，price 缺省时按 price / last / close 猜
+    #[serde(default)]
+    pub price: Option<String>,
+    #[serde(default)]
+    pub open: Option<String>,
+    #[serde(default)]
+    pub prev_close: Option<String>,
+    // 接口直接给了涨跌幅 (百分数) 时优先使用
+    #[serde(default)]
+    pub change_pct: Option<String>,
+    #[serde(default)]
+    pub interval: Option<u64>,
+    #[serde(default)]
+    pub headers: Vec<String>,
+}
+
+// 解析全部 --ticker，并把旧的 --stock-url 放在最前面
+pub fn parse_specs(specs: &[String], legacy_url: &str) -> Vec<TickerSpec> {
+    let mut out = Vec::new();
+    if !legacy_url.is_empty() {
+        out.push(TickerSpec { url: legacy_url.to_string(), ..Default::default() });
+    }
+    for spec in specs {
+        match serde_json::from_str::<TickerSpec>(spec) {
+            Ok(t) if !t.url.is_empty() => out.push(t),
+            Ok(_) => println!("⚠️ [行情] 缺少 url，已忽略: {}", spec),
+            Err(e) => println!("⚠️ [行情] 配置解析失败 ({}): {}", e, spec),
+        }
+    }
+    out
+}
+
+// 数字或数字字符串 ("67123.5" / "+1.23%")
+fn as_number(v: &Value) -> Option<f64> {
+    v.as_f64()
+        .or_else(|| v.as_str().and_then(|s| s.trim().trim_end_matches('%').parse().ok()))
+}
+
+fn field(root: &Value, path: &Option<String>) -> Option<f64> {
+    path.as_deref().filter(|p| !p.is_empty()).and_then(|p| json_path(root, p)).and_then(as_number)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Trend {
+    Up,
+    Down,
+    Flat,
+}
+
+// ==========================================
+// 💹 一次报价
+// ==========================================
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quote {
+    pub price: f64,
+    pub change_pct: Option<f64>, // 相对昨收/开盘的涨跌幅
+    pub trend: Trend,
+}
+
+// 从响应 JSON 提取报价; prev_poll = 上一次轮询的价格 (没有参考价时用它判断涨跌)
+pub fn extract(spec: &TickerSpec, root: &Value, prev_poll: Option<f64>) -> Option<Quote> {
+    let price = match &spec.price {
+        Some(p) if !p.is_empty() => field(root, &spec.price)?,
+        _ => ["price", "last", "close"].iter().find_map(|k| root.get(*k).and_then(as_number))?,
+    };
+
+    let reference = field(root, &spec.prev_close).or_else(|| field(root, &spec.open)).filter(|r| *r > 0.0);
+    let change_pct = field(root, &spec.change_pct).or_else(|| reference.map(|r| (price - r) / r * 100.0));
+
+    let cmp = |delta: f64| {
+        if delta > 0.0 {
+            Trend::Up
+        } else if delta < 0.0 {
+            Trend::Down
+        } else {
+            Trend::Flat
+        }
+    };
+    let trend = match (change_pct, prev_poll) {
+        (Some(pct), _) => cmp(pct),
+        (None, Some(prev)) => cmp(price - prev),
+        (None, None) => Trend::Flat,
+    };
+    Some(Quote { price, change_pct, trend })
+}
+
+pub fn format_price(p: f64) -> String {
+    if p.abs() >= 1000.0 {
+        format!("{:.0}", p)
+    } else if p.abs() >= 1.0 {
+        format!("{:.2}", p)
+    } else {
+        format!("{:.4}", p)
+    }
+}
+
+// "BTC 67123 +1.23%"；无代号时省略，无涨跌幅时只显示价格
+pub fn display(symbol: &str, q: &Quote) -> String {
+    let mut s = String::new();
+    if !symbol.is_empty() {
+        s.push_str(symbol);
+        s.push(' ');
+    }
+    s.push_str(&format_price(q.price));
+    if let Some(pct) = q.change_pct {
+        s.push_str(&format!(" {:+.2}%", pct));
+    }
+    s
+}
+
+pub fn trend_leds(trend: Trend) -> u8 {
+    match trend {
+        Trend::Up => LED_UP,
+        Trend::Down => LED_DOWN,
+        Trend::Flat => 0,
+    }
+}
+
+// ==========================================
+// 🔄 单个品种的刷新状态 (由 net_agent 持有)
+// ==========================================
+pub struct TickerFeed {
+    pub spec: TickerSpec,
+    pub quote: Option<Quote>,
+    failed: bool,
+    next_due: Option<Instant>,
+}
+
+impl TickerFeed {
+    pub fn new(spec: TickerSpec) -> Self {
+        Self { spec, quote: None, failed: false, next_due: None }
+    }
+
+    pub fn is_due(&self) -> bool {
+        self.next_due.is_none_or(|t| Instant::now() >= t)
+    }
+
+    pub async fn refresh(&mut self, http: &Http) {
+        let prev = self.quote.map(|q| q.price);
+        let fetched = fetch(http, &self.spec).await.and_then(|v| extract(&self.spec, &v, prev));
+        let wait = match fetched {
+            Some(q) => {
+                self.quote = Some(q);
+                self.failed = false;
+                self.spec.interval.unwrap_or(DEFAULT_INTERVAL).max(MIN_INTERVAL)
+            }
+            None => {
+                // 失败保留旧报价继续显示
+                self.failed = true;
+                RETRY_SECS
+            }
+        };
+        self.next_due = Some(Instant::now() + Duration::from_secs(wait));
+    }
+
+    // (显示文本, 涨跌灯位)
+    pub fn status(&self) -> (String, u8) {
+        match (&self.quote, self.failed) {
+            (Some(q), _) => (display(&self.spec.symbol, q), trend_leds(q.trend)),
+            (None, true) => (format!("{}Err", label(&self.spec.symbol)), 0),
+            (None, false) => (format!("{}Wait", label(&self.spec.symbol)), 0),
+        }
+    }
+}
+
+fn label(symbol: &str) -> String {
+    if symbol.is_empty() { String::new() } else { format!("{} ", symbol) }
+}
+
+async fn fetch(http: &Http, spec: &TickerSpec) -> Option<Value> {
+    // 超时 / 限流 / 去重由 Http 统一处理 (--http-timeout ticker=秒，默认 10 秒)
+    let mut req = http.request("ticker", &spec.url);
+    for h in &spec.headers {
+        if let Some((k, v)) = h.split_once(':') {
+            req = req.header(k.trim(), v.trim());
+        }
+    }
+    let resp = http.send("ticker", req).await.ok()?;
+    if !resp.is_success() {
+        return None;
+    }
+    resp.json::<Value>().ok()
+}
+
+// ==========================================
+// 🧪 单元测试
+// ==========================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn spec(price: Option<&str>, prev_close: Option<&str>) -> TickerSpec {
+        TickerSpec {
+            symbol: "BTC".into(),
+            url: "http://x".into(),
+            price: price.map(str::to_string),
+            prev_close: prev_close.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn legacy_guess_and_poll_trend() {
+        let s = TickerSpec { url: "http://x".into(), ..Default::default() };
+        let q = extract(&s, &json!({"last": "12.5"}), None).unwrap();
+        assert_eq!((q.price, q.change_pct, q.trend), (12.5, None, Trend::Flat));
+        assert_eq!(extract(&s, &json!({"close": 10.0}), Some(12.5)).unwrap().trend, Trend::Down);
+        assert_eq!(extract(&s, &json!({"price": 13}), Some(12.5)).unwrap().trend, Trend::Up);
+        assert!(extract(&s, &json!({"foo": 1}), None).is_none());
+        assert_eq!(display("", &extract(&s, &json!({"price": 3050.4}), None).unwrap()), "3050");
+    }
+
+    #[test]
+    fn daily_change_from_prev_close() {
+        let v = json!({"data": {"last": "67123.4", "prevClose": 66307.8, "open": 70000}});
+        let q = extract(&spec(Some("$.data.last"), Some("/data/prevClose")), &v, Some(99999.0)).unwrap();
+        assert_eq!(q.trend, Trend::Up); // 以昨收为准，不看上一次轮询
+        assert_eq!(display("BTC", &q), "BTC 67123 +1.23%");
+        assert_eq!(trend_leds(q.trend), LED_UP);
+
+        // 昨收缺失时退回开盘价
+        let mut s = spec(Some("$.data.last"), Some("$.data.nope"));
+        s.open = Some("$.data.open".into());
+        let q = extract(&s, &v, None).unwrap();
+        assert_eq!(q.trend, Trend::Down);
+        assert_eq!(display("BTC", &q), "BTC 67123 -4.11%");
+
+        // 接口直接给出涨跌幅
+        let mut s = spec(Some("$.p"), None);
+        s.change_pct = Some("$.chg".into());
+        let q = extract(&s, &json!({"p": 0.1234, "chg": "-0.5%"}), None).unwrap();
+        assert_eq!(display("DOGE", &q), "DOGE 0.1234 -0.50%");
+        assert_eq!(trend_leds(q.trend), LED_DOWN);
+    }
+
+    #[test]
+    fn specs_and_status() {
+        let specs = parse_specs(
+            &[r#"{"symbol":"ETH","url":"http://e","interval":15}"#.into(), r#"{"symbol":"X"}"#.into()],
+            "http://legacy",
+        );
+        assert_eq!(specs.len(), 2);
+        assert_eq!(specs[0].url, "http://legacy");
+        assert_eq!(specs[1].symbol, "ETH");
+
+        let feed = TickerFeed::new(specs[1].clone());
+        assert!(feed.is_due());
+        assert_eq!(feed.status(), ("ETH Wait".to_string(), 0));
+    }
+}
@@ -0,0 +1,181 @@
+// ==========================================
+// 🕒 timesync.rs — 系统时间是否可信 (NTP 校时状态, v2.6.0)
+//
+// 路由器没有 RTC 电池，断网重启后时间停在上次关机附近 (甚至 1970 年)，
+// 而整块屏就是个钟。这里判断时间是否已校准:
+//   1. 年份早于编译年份 → 肯定没校时
+//   2. 内核 adjtimex 状态: ntpd / chrony 接管时钟后会清掉 STA_UNSYNC
+//   3. 运行期间墙上时间相对单调时钟跳变超过 60 秒 → ntpd 刚步进校时 (或手动改了时间)
+// auto 模式下 1 不满足即未同步；满足后 2、3 任一成立即视为已同步。
+// 一旦同步就一直认为可信 (之后短暂失联不影响已经走准的时间)。
+//
+// 未同步期间: 时间模块冒号显示为暗点，倒数日/农历/日出日落/日程显示 "NO NTP" 且不做计算。
+// ==========================================
+use chrono::{Datelike, Local};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant, SystemTime};
+
+const BUILD_YEAR: &str = env!("ATHENA_BUILD_YEAR");
+// 判定为“人工/NTP 步进校时”的跳变阈值
+const STEP_SECS: u64 = 60;
+
+static SYNCED: AtomicBool = AtomicBool::new(false);
+
+// 日期相关模块在未校时时显示的提示
+pub const NO_NTP: &str = "NO NTP";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CheckMode {
+    Auto, // 年份 + 内核同步状态 / 校时跳变
+    Year, // 只看年份 (不跑 ntpd、手动维护时间的环境)
+    Off,  // 不检查，始终认为时间可信
+}
+
+impl CheckMode {
+    pub fn parse(s: &str) -> Self {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "year" => CheckMode::Year,
+            "off" | "0" | "false" => CheckMode::Off,
+            _ => CheckMode::Auto,
+        }
+    }
+}
+
+pub fn synced() -> bool {
+    SYNCED.load(Ordering::Relaxed)
+}
+
+fn build_year() -> i32 {
+    BUILD_YEAR.parse().unwrap_or(2024)
+}
+
+// 🧪 [可测试的纯函数] kernel: Some(true)=内核已同步, Some(false)=未同步, None=读不到
+fn assess(year: i32, build_year: i32, kernel: Option<bool>, stepped: bool, mode: CheckMode) -> bool {
+    match mode {
+        CheckMode::Off => true,
+        CheckMode::Year => year >= build_year,
+        CheckMode::Auto => year >= build_year && (stepped || kernel != Some(false)),
+    }
+}
+
+// 内核时钟同步状态 (modes = 0 只读不改)
+#[cfg(target_os = "linux")]
+fn kernel_synced() -> Option<bool> {
+    // SAFETY: timex 是纯 C 结构体，全零是合法初值；modes = 0 时 adjtimex 只读取状态
+    let mut tx: libc::timex = unsafe { std::mem::zeroed() };
+    let state = unsafe { libc::adjtimex(&mut tx) };
+    if state < 0 {
+        return None;
+    }
+    Some(state != libc::TIME_ERROR && tx.status & libc::STA_UNSYNC == 0)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn kernel_synced() -> Option<bool> {
+    None
+}
+
+// 墙上时间相对单调时钟的跳变检测
+struct StepWatch {
+    mono: Instant,
+    wall: SystemTime,
+}
+
+impl StepWatch {
+    fn new() -> Self {
+        Self { mono: Instant::now(), wall: SystemTime::now() }
+    }
+
+    fn stepped(&self) -> bool {
+        let mono = self.mono.elapsed();
+        let wall = match SystemTime::now().duration_since(self.wall) {
+            Ok(d) => d,
+            Err(_) => return true, // 时间往回调了
+        };
+        wall.abs_diff(mono) > Duration::from_secs(STEP_SECS)
+    }
+}
+
+fn check(mode: CheckMode, watch: &StepWatch) -> bool {
+    if synced() {
+        return true;
+    }
+    let ok = assess(Local::now().year(), build_year(), kernel_synced(), watch.stepped(), mode);
+    if ok {
+        SYNCED.store(true, Ordering::Relaxed);
+    }
+    ok
+}
+
+// 启动时先同步判断一次 (第一帧就要用)，没同步再起后台任务每 5 秒复查，同步后任务结束
+pub fn spawn_watch(mode: CheckMode) {
+    let watch = StepWatch::new();
+    if check(mode, &watch) {
+        return;
+    }
+    println!("⚠️ [校时] 系统时间尚未同步 (NTP)，时间模块显示暗冒号，日期相关模块暂停计算");
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            if check(mode, &watch) {
+                println!("🕒 [校时] 系统时间已同步: {}", Local::now().format("%Y-%m-%d %H:%M:%S"));
+                break;
+            }
+        }
+    });
+}
+
+// 时间模块文本: 未同步时冒号换成暗点 ("12·34")，紧凑冒号换成小数点 ("12.34.56"，宽度不变)
+pub fn clock_text(text: String) -> String {
+    if synced() {
+        text
+    } else {
+        text.replace(':', "·").replace('^', ".")
+    }
+}
+
+// 依赖日期的模块: 未同步时不计算，直接显示提示
+pub fn dated(f: impl FnOnce() -> String) -> String {
+    if synced() {
+        f()
+    } else {
+        NO_NTP.to_string()
+    }
+}
+
+// ==========================================
+// 🧪 单元测试
+// ==========================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn assessment_rules() {
+        // 年份早于编译年份: 无论内核怎么说都不可信
+        assert!(!assess(1970, 2026, Some(true), true, CheckMode::Auto));
+        assert!(!assess(2025, 2026, None, false, CheckMode::Year));
+        // 年份正常时: 内核未同步且没见到校时跳变 → 仍然不可信 (sysfixtime 恢复的旧时间)
+        assert!(!assess(2026, 2026, Some(false), false, CheckMode::Auto));
+        assert!(assess(2026, 2026, Some(false), true, CheckMode::Auto));
+        assert!(assess(2027, 2026, Some(true), false, CheckMode::Auto));
+        assert!(assess(2026, 2026, None, false, CheckMode::Auto));
+        assert!(assess(2026, 2026, Some(false), false, CheckMode::Year));
+        assert!(assess(1970, 2026, Some(false), false, CheckMode::Off));
+
+        assert_eq!(CheckMode::parse("YEAR"), CheckMode::Year);
+        assert_eq!(CheckMode::parse("off"), CheckMode::Off);
+        assert_eq!(CheckMode::parse(""), CheckMode::Auto);
+        assert!(build_year() >= 2024);
+    }
+
+    #[test]
+    fn step_detection() {
+        let w = StepWatch::new();
+        assert!(!w.stepped());
+        let ahead = StepWatch { mono: Instant::now(), wall: SystemTime::now() - Duration::from_secs(3600) };
+        assert!(ahead.stepped());
+        let behind = StepWatch { mono: Instant::now(), wall: SystemTime::now() + Duration::from_secs(3600) };
+        assert!(behind.stepped());
+    }
+}
@@ -0,0 +1,475 @@
+// ==========================================
+// 🌐 tz.rs — 时区: POSIX TZ 规则解析与夏令时换算 (v2.6.0)
+//
+// OpenWrt 的 /etc/config/system 里有两个相关选项:
+//   option zonename 'Europe/Berlin'                      (LuCI 里选的地区名)
+//   option timezone 'CET-1CEST,M3.5.0,M10.5.0/3'        (对应的 POSIX TZ 规则)
+// 启动时按 timezone > zonename (/usr/share/zoneinfo 文件尾部的规则，或内置常用表) > CST-8
+// 的顺序确定规则，写入 TZ 环境变量 (chrono::Local 显示用)，同时留一份解析结果，
+// 供需要精确换算夏令时的地方使用: 休眠唤醒倒计时、日出日落的本地钟点、月出月落的本地零点。
+//
+// POSIX TZ 语法: 标准名 偏移 [夏令名 [偏移] [,开始[/时刻],结束[/时刻]]]
+//   名字: 3 个以上字母，或 <+08> 这种尖括号写法；偏移为 "UTC 减本地" (CST-8 = 东八区)
+//   规则: Mm.w.d (m 月第 w 个星期 d，w=5 表示最后一个，d=0 为周日) / Jn (1~365，不计 2/29) / n (0~365)
+//   时刻: 默认 02:00:00，可为负或超过 24 小时 (如 M3.5.0/-1、J60/25)
+// ==========================================
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime};
+use once_cell::sync::OnceCell;
+
+const FALLBACK: &str = "CST-8";
+const ZONEINFO_DIR: &str = "/usr/share/zoneinfo";
+
+static SYSTEM: OnceCell<PosixTz> = OnceCell::new();
+
+// 没装 zoneinfo 时，zonename 常见取值到 POSIX 规则的对照 (与 LuCI 生成的 timezone 一致)
+const ZONES: &[(&str, &str)] = &[
+    ("UTC", "UTC0"),
+    ("Etc/UTC", "UTC0"),
+    ("Asia/Shanghai", "CST-8"),
+    ("Asia/Chongqing", "CST-8"),
+    ("Asia/Hong_Kong", "HKT-8"),
+    ("Asia/Macau", "CST-8"),
+    ("Asia/Taipei", "CST-8"),
+    ("Asia/Singapore", "<+08>-8"),
+    ("Asia/Tokyo", "JST-9"),
+    ("Asia/Seoul", "KST-9"),
+    ("Asia/Kolkata", "IST-5:30"),
+    ("Asia/Dubai", "<+04>-4"),
+    ("Europe/London", "GMT0BST,M3.5.0/1,M10.5.0"),
+    ("Europe/Dublin", "IST-1GMT0,M10.5.0,M3.5.0/1"),
+    ("Europe/Berlin", "CET-1CEST,M3.5.0,M10.5.0/3"),
+    ("Europe/Paris", "CET-1CEST,M3.5.0,M10.5.0/3"),
+    ("Europe/Amsterdam", "CET-1CEST,M3.5.0,M10.5.0/3"),
+    ("Europe/Madrid", "CET-1CEST,M3.5.0,M10.5.0/3"),
+    ("Europe/Rome", "CET-1CEST,M3.5.0,M10.5.0/3"),
+    ("Europe/Moscow", "MSK-3"),
+    ("America/New_York", "EST5EDT,M3.2.0,M11.1.0"),
+    ("America/Chicago", "CST6CDT,M3.2.0,M11.1.0"),
+    ("America/Denver", "MST7MDT,M3.2.0,M11.1.0"),
+    ("America/Phoenix", "MST7"),
+    ("America/Los_Angeles", "PST8PDT,M3.2.0,M11.1.0"),
+    ("America/Sao_Paulo", "<-03>3"),
+    ("Australia/Sydney", "AEST-10AEDT,M10.1.0,M4.1.0/3"),
+    ("Australia/Melbourne", "AEST-10AEDT,M10.1.0,M4.1.0/3"),
+    ("Australia/Brisbane", "AEST-10"),
+    ("Pacific/Auckland", "NZST-12NZDT,M9.5.0,M4.1.0/3"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DateRule {
+    Julian1(u32),          // Jn: 1~365，不计 2 月 29 日
+    Julian0(u32),          // n: 0~365，计 2 月 29 日
+    MonthWeek(u32, u32, u32), // Mm.w.d
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Transition {
+    date: DateRule,
+    secs: i64, // 当天本地时刻 (秒，可为负或超过一天)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Dst {
+    offset: i32, // 东正西负 (秒)
+    start: Transition,
+    end: Transition,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PosixTz {
+    spec: String,
+    std_offset: i32, // 东正西负 (秒)
+    dst: Option<Dst>,
+}
+
+// ==========================================
+// 🔍 解析
+// ==========================================
+struct Cursor<'a> {
+    s: &'a str,
+}
+
+impl<'a> Cursor<'a> {
+    fn peek(&self) -> Option<char> {
+        self.s.chars().next()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        match self.s.strip_prefix(c) {
+            Some(rest) => {
+                self.s = rest;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn take_while(&mut self, f: impl Fn(char) -> bool) -> &'a str {
+        let end = self.s.find(|c: char| !f(c)).unwrap_or(self.s.len());
+        let (head, rest) = self.s.split_at(end);
+        self.s = rest;
+        head
+    }
+
+    fn number(&mut self) -> Option<u32> {
+        self.take_while(|c| c.is_ascii_digit()).parse().ok()
+    }
+
+    // "CST" 或 "<+08>"
+    fn name(&mut self) -> Option<&'a str> {
+        if self.eat('<') {
+            let name = self.take_while(|c| c != '>');
+            return (self.eat('>') && !name.is_empty()).then_some(name);
+        }
+        let name = self.take_while(|c| c.is_ascii_alphabetic());
+        (name.len() >= 3).then_some(name)
+    }
+
+    // [+-]hh[:mm[:ss]] -> 秒 (带符号)
+    fn hms(&mut self) -> Option<i64> {
+        let sign = if self.eat('-') {
+            -1
+        } else {
+            self.eat('+');
+            1
+        };
+        let h = i64::from(self.number()?);
+        let m = if self.eat(':') { i64::from(self.number()?) } else { 0 };
+        let s = if self.eat(':') { i64::from(self.number()?) } else { 0 };
+        (h <= 167 && m < 60 && s < 60).then_some(sign * (h * 3600 + m * 60 + s))
+    }
+
+    fn transition(&mut self) -> Option<Transition> {
+        let date = if self.eat('M') {
+            let m = self.number()?;
+            let w = self.eat('.').then(|| self.number()).flatten()?;
+            let d = self.eat('.').then(|| self.number()).flatten()?;
+            ((1..=12).contains(&m) && (1..=5).contains(&w) && d <= 6).then_some(DateRule::MonthWeek(m, w, d))?
+        } else if self.eat('J') {
+            let n = self.number()?;
+            (1..=365).contains(&n).then_some(DateRule::Julian1(n))?
+        } else {
+            let n = self.number()?;
+            (n <= 365).then_some(DateRule::Julian0(n))?
+        };
+        let secs = if self.eat('/') { self.hms()? } else { 7200 };
+        Some(Transition { date, secs })
+    }
+}
+
+impl PosixTz {
+    pub fn parse(spec: &str) -> Option<Self> {
+        let spec = spec.trim();
+        let mut c = Cursor { s: spec };
+        c.name()?;
+        let std_offset = -c.hms()? as i32;
+        let dst = if c.peek().is_none() {
+            None
+        } else {
+            c.name()?;
+            let offset = match c.peek() {
+                Some(ch) if ch != ',' => -c.hms()? as i32,
+                _ => std_offset + 3600,
+            };
+            // 没写切换规则时沿用 POSIX 实现的默认 (美国规则)
+            let (start, end) = if c.eat(',') {
+                let start = c.transition()?;
+                if !c.eat(',') {
+                    return None;
+                }
+                (start, c.transition()?)
+            } else {
+                (
+                    Transition { date: DateRule::MonthWeek(3, 2, 0), secs: 7200 },
+                    Transition { date: DateRule::MonthWeek(11, 1, 0), secs: 7200 },
+                )
+            };
+            Some(Dst { offset, start, end })
+        };
+        c.peek().is_none().then(|| PosixTz { spec: spec.to_string(), std_offset, dst })
+    }
+
+    pub fn spec(&self) -> &str {
+        &self.spec
+    }
+
+    // ==========================================
+    // 🕰️ 换算 (t 均为 Unix 秒)
+    // ==========================================
+    // 某年夏令时开始/结束的 UTC 时刻
+    fn transitions(&self, dst: &Dst, year: i32) -> Option<(i64, i64)> {
+        let at = |tr: &Transition, offset: i32| -> Option<i64> {
+            let day = rule_date(tr.date, year)?;
+            Some(day.and_hms_opt(0, 0, 0)?.and_utc().timestamp() + tr.secs - i64::from(offset))
+        };
+        // 开始时刻按标准时间理解，结束时刻按夏令时理解
+        Some((at(&dst.start, self.std_offset)?, at(&dst.end, dst.offset)?))
+    }
+
+    // UTC 时刻 t 的本地偏移 (秒，东正西负)
+    pub fn offset_at(&self, t: i64) -> i32 {
+        let Some(dst) = &self.dst else { return self.std_offset };
+        let year = chrono::DateTime::from_timestamp(t + i64::from(self.std_offset), 0).map_or(1970, |d| d.year());
+        let Some((start, end)) = self.transitions(dst, year) else { return self.std_offset };
+        let in_dst = if start < end {
+            start <= t && t < end
+        } else {
+            // 南半球: 夏令时跨年 (如悉尼 10 月开始、次年 4 月结束)
+            t >= start || t < end
+        };
+        if in_dst {
+            dst.offset
+        } else {
+            self.std_offset
+        }
+    }
+
+    pub fn to_local(&self, t: i64) -> NaiveDateTime {
+        let local = t + i64::from(self.offset_at(t));
+        chrono::DateTime::from_timestamp(local, 0).map_or(NaiveDateTime::MIN, |d| d.naive_utc())
+    }
+
+    // 本地墙上时间 -> UTC: 回拨重叠的那一小时取较早的一次；
+    // 拨快跳过的那一小时不存在，按切换前的偏移换算 (02:30 -> 03:30)
+    pub fn to_utc(&self, local: NaiveDateTime) -> i64 {
+        let wall = local.and_utc().timestamp();
+        let offsets = [self.std_offset, self.dst.as_ref().map_or(self.std_offset, |d| d.offset)];
+        let valid = offsets
+            .iter()
+            .map(|&o| wall - i64::from(o))
+            .filter(|&t| self.to_local(t) == local)
+            .min();
+        valid.unwrap_or_else(|| {
+            // 不存在的时刻: 用比它早一小时那一刻的偏移 (即切换前的偏移)
+            wall - i64::from(self.offset_at(wall - i64::from(self.std_offset) - 3600))
+        })
+    }
+
+    // 事件在 UTC 的钟点 (小时，0~24) -> 落在本地 date 那天的那次事件的 UTC 时刻
+    // (日出日落算法只给出 UTC 钟点；按事件自身时刻的偏移归到本地日期，夏令时切换当天也准确)
+    pub fn event_at(&self, date: NaiveDate, utc_hour: f64) -> i64 {
+        let days = date.and_hms_opt(0, 0, 0).map_or(0, |d| d.and_utc().timestamp()).div_euclid(86400);
+        let secs = (utc_hour * 3600.0).round() as i64;
+        [days, days - 1, days + 1]
+            .into_iter()
+            .map(|day| day * 86400 + secs)
+            .find(|&t| (t + i64::from(self.offset_at(t))).div_euclid(86400) == days)
+            .unwrap_or(days * 86400 + secs)
+    }
+
+    // 同上，换算成本地钟点 (小时)
+    pub fn local_hour(&self, date: NaiveDate, utc_hour: f64) -> f64 {
+        let t = self.event_at(date, utc_hour);
+        (t + i64::from(self.offset_at(t))).rem_euclid(86400) as f64 / 3600.0
+    }
+}
+
+fn days_in_month(y: i32, m: u32) -> u32 {
+    let (ny, nm) = if m == 12 { (y + 1, 1) } else { (y, m + 1) };
+    NaiveDate::from_ymd_opt(ny, nm, 1).and_then(|d| d.pred_opt()).map_or(28, |d| d.day())
+}
+
+fn rule_date(rule: DateRule, year: i32) -> Option<NaiveDate> {
+    let jan1 = NaiveDate::from_ymd_opt(year, 1, 1)?;
+    match rule {
+        DateRule::Julian0(n) => Some(jan1 + Duration::days(i64::from(n))),
+        DateRule::Julian1(n) => {
+            let leap = NaiveDate::from_ymd_opt(year, 2, 29).is_some();
+            let skip = i64::from(leap && n >= 60);
+            Some(jan1 + Duration::days(i64::from(n) - 1 + skip))
+        }
+        DateRule::MonthWeek(m, w, d) => {
+            let first = NaiveDate::from_ymd_opt(year, m, 1)?;
+            let first_wd = first.weekday().num_days_from_sunday();
+            let mut day = 1 + (d + 7 - first_wd) % 7 + (w - 1) * 7;
+            while day > days_in_month(year, m) {
+                day -= 7;
+            }
+            NaiveDate::from_ymd_opt(year, m, day)
+        }
+    }
+}
+
+// ==========================================
+// ⚙️ 系统配置
+// ==========================================
+// 取 config system 段里某个 option 的值 (单引号/双引号/不加引号都认)
+fn uci_option(content: &str, key: &str) -> Option<String> {
+    let mut in_system = false;
+    for line in content.lines() {
+        let mut words = line.split_whitespace();
+        match words.next() {
+            Some("config") => in_system = words.next() == Some("system"),
+            Some("option") if in_system && words.next() == Some(key) => {
+                let rest = line.trim_start().strip_prefix("option")?.trim_start().strip_prefix(key)?.trim();
+                let value = rest
+                    .strip_prefix('\'')
+                    .and_then(|v| v.strip_suffix('\''))
+                    .or_else(|| rest.strip_prefix('"').and_then(|v| v.strip_suffix('"')))
+                    .unwrap_or(rest);
+                return Some(value.to_string());
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+// TZif (v2 起) 文件末尾是一行 POSIX TZ 规则: "...\nCET-1CEST,M3.5.0,M10.5.0/3\n"
+fn zoneinfo_footer(data: &[u8]) -> Option<String> {
+    if !data.starts_with(b"TZif") || data.get(4).is_none_or(|v| *v < b'2') {
+        return None;
+    }
+    let text = std::str::from_utf8(data.strip_suffix(b"\n")?.rsplit(|b| *b == b'\n').next()?).ok()?;
+    Some(text.to_string())
+}
+
+fn zonename_rule(zonename: &str, zoneinfo_dir: &str) -> Option<String> {
+    let path = format!("{}/{}", zoneinfo_dir, zonename);
+    if !zonename.contains("..") {
+        if let Some(rule) = std::fs::read(path).ok().as_deref().and_then(zoneinfo_footer) {
+            return Some(rule);
+        }
+    }
+    ZONES.iter().find(|(name, _)| *name == zonename).map(|(_, rule)| rule.to_string())
+}
+
+// 🧪 [可测试的纯函数] system 配置内容 -> 时区规则
+pub fn resolve(content: &str, zoneinfo_dir: &str) -> PosixTz {
+    let from_timezone = uci_option(content, "timezone").and_then(|s| PosixTz::parse(&s));
+    let from_zonename = || {
+        uci_option(content, "zonename")
+            .and_then(|z| zonename_rule(&z, zoneinfo_dir))
+            .and_then(|s| PosixTz::parse(&s))
+    };
+    from_timezone
+        .or_else(from_zonename)
+        .unwrap_or_else(|| PosixTz::parse(FALLBACK).expect("内置时区规则"))
+}
+
+// 启动时调用一次: 读 /etc/config/system，设置 TZ 环境变量并记住解析结果
+pub fn init_from_system() -> &'static PosixTz {
+    let content = std::fs::read_to_string("/etc/config/system").unwrap_or_default();
+    let tz = resolve(&content, ZONEINFO_DIR);
+    std::env::set_var("TZ", tz.spec());
+    println!("🌐 [时区] {}", tz.spec());
+    SYSTEM.get_or_init(|| tz)
+}
+
+// 当前生效的时区规则 (未初始化时按 TZ 环境变量，再不行用 CST-8)
+pub fn system() -> &'static PosixTz {
+    SYSTEM.get_or_init(|| {
+        std::env::var("TZ")
+            .ok()
+            .and_then(|s| PosixTz::parse(&s))
+            .unwrap_or_else(|| PosixTz::parse(FALLBACK).expect("内置时区规则"))
+    })
+}
+
+// ==========================================
+// 🧪 单元测试
+// ==========================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(y: i32, mo: u32, d: u32, h: u32, mi: u32) -> i64 {
+        NaiveDate::from_ymd_opt(y, mo, d).unwrap().and_hms_opt(h, mi, 0).unwrap().and_utc().timestamp()
+    }
+
+    fn local(y: i32, mo: u32, d: u32, h: u32, mi: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(y, mo, d).unwrap().and_hms_opt(h, mi, 0).unwrap()
+    }
+
+    #[test]
+    fn parsing() {
+        let cst = PosixTz::parse("CST-8").unwrap();
+        assert_eq!((cst.std_offset, cst.dst.is_none()), (8 * 3600, true));
+        assert_eq!(PosixTz::parse("<+0530>-5:30").unwrap().std_offset, 19800);
+        assert_eq!(PosixTz::parse("IST-5:30").unwrap().std_offset, 19800);
+
+        let cet = PosixTz::parse("CET-1CEST,M3.5.0,M10.5.0/3").unwrap();
+        let dst = cet.dst.as_ref().unwrap();
+        assert_eq!(dst.offset, 7200);
+        assert_eq!(dst.start, Transition { date: DateRule::MonthWeek(3, 5, 0), secs: 7200 });
+        assert_eq!(dst.end, Transition { date: DateRule::MonthWeek(10, 5, 0), secs: 3 * 3600 });
+
+        // 没写规则 = 美国默认；负的切换时刻 / Jn 规则
+        assert_eq!(PosixTz::parse("EST5EDT").unwrap().dst.unwrap().start.date, DateRule::MonthWeek(3, 2, 0));
+        assert_eq!(PosixTz::parse("<-02>2<-01>,M3.5.0/-1,M10.5.0/0").unwrap().dst.unwrap().start.secs, -3600);
+        assert_eq!(PosixTz::parse("XST3XDT,J60,J300").unwrap().dst.unwrap().start.date, DateRule::Julian1(60));
+
+        for bad in ["", "C-8", "CST", "CST-8CDT,M3.2.0", "CST-8 garbage", "CET-1CEST,M13.5.0,M10.5.0"] {
+            assert!(PosixTz::parse(bad).is_none(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn rule_dates() {
+        // 2026 年 3 月最后一个周日 = 29 日，10 月 = 25 日；美国 3 月第二个周日 = 8 日
+        assert_eq!(rule_date(DateRule::MonthWeek(3, 5, 0), 2026), NaiveDate::from_ymd_opt(2026, 3, 29));
+        assert_eq!(rule_date(DateRule::MonthWeek(10, 5, 0), 2026), NaiveDate::from_ymd_opt(2026, 10, 25));
+        assert_eq!(rule_date(DateRule::MonthWeek(3, 2, 0), 2026), NaiveDate::from_ymd_opt(2026, 3, 8));
+        // J60 永远是 3 月 1 日；59 (从 0 数) 在闰年是 2 月 29 日
+        assert_eq!(rule_date(DateRule::Julian1(60), 2028), NaiveDate::from_ymd_opt(2028, 3, 1));
+        assert_eq!(rule_date(DateRule::Julian0(59), 2028), NaiveDate::from_ymd_opt(2028, 2, 29));
+    }
+
+    #[test]
+    fn transitions_north_and_south() {
+        let cet = PosixTz::parse("CET-1CEST,M3.5.0,M10.5.0/3").unwrap();
+        // 3/29 01:00Z (本地 02:00) 拨快；10/25 01:00Z (本地夏令 03:00) 拨回
+        assert_eq!(cet.offset_at(utc(2026, 3, 29, 0, 59)), 3600);
+        assert_eq!(cet.offset_at(utc(2026, 3, 29, 1, 0)), 7200);
+        assert_eq!(cet.offset_at(utc(2026, 10, 25, 0, 59)), 7200);
+        assert_eq!(cet.offset_at(utc(2026, 10, 25, 1, 0)), 3600);
+
+        let syd = PosixTz::parse("AEST-10AEDT,M10.1.0,M4.1.0/3").unwrap();
+        assert_eq!(syd.offset_at(utc(2026, 1, 15, 0, 0)), 11 * 3600);
+        assert_eq!(syd.offset_at(utc(2026, 6, 15, 0, 0)), 10 * 3600);
+        assert_eq!(syd.offset_at(utc(2026, 12, 15, 0, 0)), 11 * 3600);
+
+        // 跳过的 02:30 不存在 -> 按 CET 换算 (= 夏令 03:30)；重叠的 02:30 取较早那次 (夏令)
+        assert_eq!(cet.to_utc(local(2026, 3, 29, 2, 30)), utc(2026, 3, 29, 1, 30));
+        assert_eq!(cet.to_utc(local(2026, 10, 25, 2, 30)), utc(2026, 10, 25, 0, 30));
+        assert_eq!(cet.to_utc(local(2026, 7, 1, 12, 0)), utc(2026, 7, 1, 10, 0));
+        assert_eq!(cet.to_local(utc(2026, 1, 1, 23, 30)), local(2026, 1, 2, 0, 30));
+    }
+
+    #[test]
+    fn sun_local_hours_follow_event_offset() {
+        let cet = PosixTz::parse("CET-1CEST,M3.5.0,M10.5.0/3").unwrap();
+        let day = NaiveDate::from_ymd_opt(2026, 3, 29).unwrap();
+        // 切换当天: 凌晨 00:30Z 仍是冬令时 (01:30)，日出 05:00Z 已是夏令时 (07:00)
+        assert!((cet.local_hour(day, 0.5) - 1.5).abs() < 1e-9);
+        assert!((cet.local_hour(day, 5.0) - 7.0).abs() < 1e-9);
+        // 东八区: UTC 22:00 落在本地次日 06:00，按本地日期取前一天的 UTC 事件
+        let cst = PosixTz::parse("CST-8").unwrap();
+        let solstice = NaiveDate::from_ymd_opt(2026, 6, 21).unwrap();
+        assert!((cst.local_hour(solstice, 21.0) - 5.0).abs() < 1e-9);
+        assert_eq!(cst.event_at(solstice, 21.0), utc(2026, 6, 20, 21, 0));
+    }
+
+    #[test]
+    fn system_config_resolution() {
+        let both = "config system\n\toption hostname 'OpenWrt'\n\toption timezone 'CET-1CEST,M3.5.0,M10.5.0/3'\n\toption zonename 'Europe/Berlin'\n";
+        assert_eq!(resolve(both, "/nonexistent").spec(), "CET-1CEST,M3.5.0,M10.5.0/3");
+
+        // timezone 缺失或无效时按 zonename 查表
+        let zone_only = "config system\n\toption timezone \"garbage\"\n\toption zonename \"America/New_York\"\n";
+        assert_eq!(resolve(zone_only, "/nonexistent").spec(), "EST5EDT,M3.2.0,M11.1.0");
+
+        // 其他段里的同名 option 不算；都没有时兜底东八区
+        let other = "config timeserver 'ntp'\n\toption timezone 'PST8PDT'\n";
+        assert_eq!(resolve(other, "/nonexistent").spec(), "CST-8");
+
+        // zoneinfo 文件尾部规则优先于内置表
+        let dir = std::env::temp_dir().join(format!("athena-tz-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("Europe")).unwrap();
+        std::fs::write(dir.join("Europe/Test"), b"TZif2\0\0\0...binary...\n<+03>-3\n").unwrap();
+        let cfg = "config system\n\toption zonename 'Europe/Test'\n";
+        assert_eq!(resolve(cfg, dir.to_str().unwrap()).spec(), "<+03>-3");
+        let _ = std::fs::remove_dir_all(&dir);
+        assert_eq!(zoneinfo_footer(b"TZif\0old v1 file"), None);
+    }
+}
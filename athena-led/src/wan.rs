@@ -0,0 +1,212 @@
+// ==========================================
+// 🌍 wan.rs — WAN 会话信息 (wan 模块)
+// 数据来自 netifd: `ubus call network.interface.<名> status`，
+// 没有 ubus 时退回 `ifstatus <名>` (两者输出同一份 JSON)，都失败则显示 W:Err。
+// 参数: "wan[:接口][/模式]"，接口缺省 wan；模式 uptime(默认) / proto / ip / ip6
+//   uptime -> "W:3d4h"   proto -> "PPPOE:UP"   ip -> "1.2.3.4"   ip6 -> "240e:1:2::/56"
+// 🌟 重拨时 netifd 正忙，ubus 可能卡好几秒，所以由 poller.rs 在后台查询，首轮完成前显示 W:--
+// ==========================================
+use crate::poller::Poller;
+use serde_json::Value;
+use std::process::Command;
+use std::time::Duration;
+
+// 查询间隔与单轮命令超时
+const POLL_SECS: u64 = 5;
+const CMD_TIMEOUT_SECS: u64 = 8;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WanInfo {
+    pub up: bool,
+    pub pending: bool,
+    pub proto: String,
+    pub uptime: Option<u64>,
+    pub ipv4: Option<String>,
+    pub ipv6_prefix: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WanMode {
+    Uptime,
+    Proto,
+    Ip,
+    Ip6,
+}
+
+pub fn parse_param(param: &str) -> (&str, WanMode) {
+    let (iface, mode) = param.split_once('/').unwrap_or((param, ""));
+    let iface = if iface.trim().is_empty() { "wan" } else { iface.trim() };
+    let mode = match mode.trim() {
+        "proto" => WanMode::Proto,
+        "ip" | "ip4" => WanMode::Ip,
+        "ip6" | "prefix" => WanMode::Ip6,
+        _ => WanMode::Uptime,
+    };
+    (iface, mode)
+}
+
+// 取 "ipv6-prefix" (上游下发的 PD) 的第一个，没有时退回本机分配段 "ipv6-prefix-assignment"
+fn first_prefix(v: &Value) -> Option<String> {
+    ["ipv6-prefix", "ipv6-prefix-assignment"].iter().find_map(|key| {
+        let p = v.get(*key)?.as_array()?.first()?;
+        Some(format!("{}/{}", p.get("address")?.as_str()?, p.get("mask")?.as_u64()?))
+    })
+}
+
+// 解析 netifd 的 interface status JSON
+pub fn parse_status(json: &str) -> Option<WanInfo> {
+    let v: Value = serde_json::from_str(json).ok()?;
+    let obj = v.as_object()?;
+    Some(WanInfo {
+        up: obj.get("up").and_then(Value::as_bool).unwrap_or(false),
+        pending: obj.get("pending").and_then(Value::as_bool).unwrap_or(false),
+        proto: obj.get("proto").and_then(Value::as_str).unwrap_or("?").to_string(),
+        uptime: obj.get("uptime").and_then(Value::as_u64),
+        ipv4: v
+            .get("ipv4-address")
+            .and_then(|a| a.as_array()?.first()?.get("address")?.as_str().map(str::to_string)),
+        ipv6_prefix: first_prefix(&v),
+    })
+}
+
+// 会话时长: 3d4h / 5h12m / 12m
+pub fn format_uptime(secs: u64) -> String {
+    let (d, h, m) = (secs / 86400, secs % 86400 / 3600, secs % 3600 / 60);
+    if d > 0 {
+        format!("{}d{}h", d, h)
+    } else if h > 0 {
+        format!("{}h{}m", h, m)
+    } else {
+        format!("{}m", m)
+    }
+}
+
+pub fn display(info: Option<&WanInfo>, mode: WanMode) -> String {
+    let Some(w) = info else { return "W:Err".to_string() };
+    let state = if w.up { "UP" } else if w.pending { "WAIT" } else { "DN" };
+    match mode {
+        WanMode::Proto => format!("{}:{}", w.proto.to_uppercase(), state),
+        WanMode::Ip => w.ipv4.clone().unwrap_or_else(|| "IP:--".to_string()),
+        WanMode::Ip6 => w.ipv6_prefix.clone().unwrap_or_else(|| "PD:--".to_string()),
+        WanMode::Uptime if !w.up => format!("W:{}", if w.pending { "WAIT" } else { "DOWN" }),
+        WanMode::Uptime => format!("W:{}", w.uptime.map(format_uptime).unwrap_or_else(|| "--".into())),
+    }
+}
+
+// 依次尝试候选命令，返回第一个成功且有输出的 stdout
+fn run_first(cmds: &[Vec<String>]) -> Option<String> {
+    cmds.iter().find_map(|cmd| {
+        let (prog, args) = cmd.split_first()?;
+        let out = Command::new(prog).args(args).output().ok()?;
+        let text = String::from_utf8_lossy(&out.stdout).into_owned();
+        (out.status.success() && !text.trim().is_empty()).then_some(text)
+    })
+}
+
+fn default_cmds(iface: &str) -> Vec<Vec<String>> {
+    vec![
+        vec!["ubus".into(), "call".into(), format!("network.interface.{}", iface), "status".into()],
+        vec!["ifstatus".into(), iface.to_string()],
+    ]
+}
+
+// 查询一次接口状态 (阻塞)
+fn query(cmds: &[Vec<String>]) -> Option<WanInfo> {
+    run_first(cmds).and_then(|json| parse_status(&json))
+}
+
+// ==========================================
+// 🧠 查询器 (由 SystemMonitor 持有): 首次用到某接口时起后台任务，之后只读快照
+// ==========================================
+pub struct WanMonitor {
+    poller: Poller<WanInfo>,
+    cmds: fn(&str) -> Vec<Vec<String>>, // 可替换的命令表 (测试时换成桩命令)
+}
+
+impl WanMonitor {
+    pub fn new() -> Self {
+        Self::with_cmds(default_cmds)
+    }
+
+    fn with_cmds(cmds: fn(&str) -> Vec<Vec<String>>) -> Self {
+        Self { poller: Poller::new("WAN", Duration::from_secs(CMD_TIMEOUT_SECS), Duration::from_secs(POLL_SECS)), cmds }
+    }
+
+    // 外层 None = 首轮查询还没完成；内层 None = 查询失败
+    pub fn status(&mut self, iface: &str) -> Option<Option<WanInfo>> {
+        let cmds = self.cmds;
+        self.poller.get(iface, || {
+            let c = cmds(iface);
+            move || query(&c)
+        })
+    }
+}
+
+// ==========================================
+// 🧪 单元测试 (JSON 样本取自 OpenWrt 23.05 PPPoE 拨号)
+// ==========================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PPPOE: &str = r#"{
+        "up": true, "pending": false, "available": true, "autostart": true,
+        "uptime": 273600, "l3_device": "pppoe-wan", "proto": "pppoe", "device": "wan",
+        "ipv4-address": [ { "address": "100.64.12.34", "mask": 32, "ptpaddress": "100.64.0.1" } ],
+        "ipv6-address": [ ],
+        "ipv6-prefix": [ { "address": "240e:3a1:1234:5600::", "mask": 56, "preferred": 3000, "valid": 6000 } ],
+        "ipv6-prefix-assignment": [ ]
+    }"#;
+
+    #[test]
+    fn parse_pppoe_status() {
+        let w = parse_status(PPPOE).unwrap();
+        assert!(w.up);
+        assert_eq!(w.proto, "pppoe");
+        assert_eq!(w.uptime, Some(273600));
+        assert_eq!(w.ipv4.as_deref(), Some("100.64.12.34"));
+        assert_eq!(w.ipv6_prefix.as_deref(), Some("240e:3a1:1234:5600::/56"));
+        assert_eq!(display(Some(&w), WanMode::Uptime), "W:3d4h");
+        assert_eq!(display(Some(&w), WanMode::Proto), "PPPOE:UP");
+        assert_eq!(display(Some(&w), WanMode::Ip6), "240e:3a1:1234:5600::/56");
+    }
+
+    #[test]
+    fn parse_down_and_garbage() {
+        let w = parse_status(r#"{"up": false, "pending": true, "proto": "dhcp"}"#).unwrap();
+        assert_eq!(display(Some(&w), WanMode::Uptime), "W:WAIT");
+        assert_eq!(display(Some(&w), WanMode::Proto), "DHCP:WAIT");
+        assert_eq!(display(Some(&w), WanMode::Ip), "IP:--");
+        assert!(parse_status("Interface wan not found").is_none());
+        assert_eq!(display(None, WanMode::Uptime), "W:Err");
+    }
+
+    #[test]
+    fn params_and_uptime() {
+        assert_eq!(parse_param(""), ("wan", WanMode::Uptime));
+        assert_eq!(parse_param("wan6/prefix"), ("wan6", WanMode::Ip6));
+        assert_eq!(parse_param("/proto"), ("wan", WanMode::Proto));
+        assert_eq!(format_uptime(59), "0m");
+        assert_eq!(format_uptime(3 * 3600 + 7 * 60), "3h7m");
+    }
+
+    // 桩命令: 第一个候选 (模拟 ubus) 不存在，应回退到第二个
+    #[cfg(unix)]
+    #[test]
+    fn fallback_to_second_command() {
+        fn stub(_iface: &str) -> Vec<Vec<String>> {
+            vec![
+                vec!["/nonexistent/ubus".into(), "call".into()],
+                vec!["sh".into(), "-c".into(), "exit 1".into()],
+                vec!["echo".into(), r#"{"up":true,"proto":"dhcp","uptime":720}"#.into()],
+            ]
+        }
+        let w = query(&stub("wan")).unwrap();
+        assert_eq!(display(Some(&w), WanMode::Uptime), "W:12m");
+
+        fn absent(_iface: &str) -> Vec<Vec<String>> {
+            vec![vec!["/nonexistent/ubus".into()]]
+        }
+        assert_eq!(query(&absent("wan")), None);
+    }
+}
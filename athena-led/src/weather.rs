@@ -0,0 +1,458 @@
+// ==========================================
+// 🌦️ weather.rs — 天气数据模型: 当前天气 / 明日预报 / 空气质量 / 逐小时降水概率 (v2.6.0)
+// 数据由 weather_provider.rs 的各数据源拉取 (按配置顺序故障切换、互相补齐)，
+// 这里只放与网络无关的数据模型、天气现象 -> 图标映射、显示格式与降雨告警状态机。
+// 参数: "weather:tomorrow" -> "TMR ☂18-25"   "weather:aqi" -> "AQI:53"
+//       "weather:pm25" -> "PM:12"           "weather:rain" -> "R:70%" (未来一小时降水概率)
+//       "weather:temp" -> "25℃"             "weather:hilo" -> "20-30"
+//       "weather:humidity" -> "H:82%"       "weather:wind" -> "W:9KMH"
+// 温度单位 (--weather-unit c/f) 与风速单位 (--wind-unit kmh/ms/mph) 只在显示时换算，
+// 快照里保存的 WeatherReport 一律是 ℃ 与 km/h。
+// ==========================================
+use chrono::{Duration, NaiveDateTime};
+
+// ==========================================
+// 📏 显示单位
+// ==========================================
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum WindUnit {
+    #[default]
+    Kmh,
+    Ms,
+    Mph,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Units {
+    pub fahrenheit: bool,
+    pub wind: WindUnit,
+}
+
+impl Units {
+    // 无法识别的写法按默认 (℃ / km/h) 处理
+    pub fn parse(temp: &str, wind: &str) -> Self {
+        let fahrenheit = matches!(temp.trim().to_lowercase().as_str(), "f" | "fahrenheit" | "℉");
+        let wind = match wind.trim().to_lowercase().replace('/', "").as_str() {
+            "ms" => WindUnit::Ms,
+            "mph" => WindUnit::Mph,
+            _ => WindUnit::Kmh,
+        };
+        Self { fahrenheit, wind }
+    }
+
+    pub fn temp(&self, celsius: f64) -> f64 {
+        if self.fahrenheit { celsius * 9.0 / 5.0 + 32.0 } else { celsius }
+    }
+
+    pub fn temp_symbol(&self) -> &'static str {
+        if self.fahrenheit { "℉" } else { "℃" }
+    }
+
+    // "9KMH" / "2.6M/S" / "6MPH" (输入为 km/h)
+    pub fn wind_text(&self, kmh: f64) -> String {
+        match self.wind {
+            WindUnit::Kmh => format!("{:.0}KMH", kmh),
+            WindUnit::Ms => format!("{:.1}M/S", kmh / 3.6),
+            WindUnit::Mph => format!("{:.0}MPH", kmh / 1.609_344),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExtraMode {
+    Tomorrow,
+    Aqi,
+    Pm25,
+    Rain,
+}
+
+// 当前天气的单个字段 (静态文本)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Field {
+    Temp,
+    HiLo,
+    Humidity,
+    Wind,
+}
+
+pub fn parse_field(param: &str) -> Option<Field> {
+    match param.trim() {
+        "temp" => Some(Field::Temp),
+        "hilo" => Some(Field::HiLo),
+        "humidity" | "hum" => Some(Field::Humidity),
+        "wind" => Some(Field::Wind),
+        _ => None,
+    }
+}
+
+// 静态文本参数 (单个字段或扩展数据)；其余参数走动画天气模块
+pub fn is_text_param(param: &str) -> bool {
+    parse_field(param).is_some() || parse_extra(param).is_some()
+}
+
+// 返回 None = 当前天气 (动画模块或上面的单个字段)
+pub fn parse_extra(param: &str) -> Option<ExtraMode> {
+    match param.trim() {
+        "tomorrow" => Some(ExtraMode::Tomorrow),
+        "aqi" => Some(ExtraMode::Aqi),
+        "pm25" | "pm2.5" => Some(ExtraMode::Pm25),
+        "rain" => Some(ExtraMode::Rain),
+        _ => None,
+    }
+}
+
+// ==========================================
+// 🌈 天气现象: 各数据源的代码/描述统一映射到这里，图标只在此处定义一次
+// ==========================================
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Condition {
+    Clear,
+    Cloudy,
+    Fog,
+    Rain,
+    Snow,
+    Thunder,
+}
+
+impl Condition {
+    // WMO 天气代码 (Open-Meteo)，未知代码兜底云朵
+    pub fn from_wmo(code: u8) -> Self {
+        match code {
+            0 => Self::Clear,
+            1..=3 => Self::Cloudy,
+            45 | 48 => Self::Fog,
+            51..=67 | 80..=82 => Self::Rain,
+            71..=77 | 85..=86 => Self::Snow,
+            95..=99 => Self::Thunder,
+            _ => Self::Cloudy,
+        }
+    }
+
+    // 心知天气现象代码: 0-3 晴, 4-9 云, 10-19 雨 (11/12 雷阵雨), 20-29 雪, 30-31 雾霾, 32-36 风
+    pub fn from_seniverse(code: i32) -> Self {
+        match code {
+            0..=3 => Self::Clear,
+            4..=9 => Self::Cloudy,
+            11 | 12 => Self::Thunder,
+            10..=19 => Self::Rain,
+            20..=29 => Self::Snow,
+            30 | 31 => Self::Fog,
+            32..=36 => Self::Cloudy,
+            _ => Self::Clear,
+        }
+    }
+
+    // 文字描述 (uapis 中文 "雷阵雨" / wttr 英文 "Light rain shower")
+    pub fn from_text(desc: &str) -> Self {
+        let d = desc.to_lowercase();
+        let has = |keys: &[&str]| keys.iter().any(|k| d.contains(k));
+        if has(&["雷", "thunder"]) {
+            Self::Thunder
+        } else if has(&["雪", "冰雹", "snow", "ice", "hail", "sleet", "blizzard"]) {
+            Self::Snow
+        } else if has(&["雨", "rain", "shower", "drizzle"]) {
+            Self::Rain
+        } else if has(&["雾", "霾", "mist", "fog", "haze"]) {
+            Self::Fog
+        } else if has(&["云", "阴", "cloud", "overcast"]) {
+            Self::Cloudy
+        } else {
+            Self::Clear
+        }
+    }
+
+    pub fn icon(self) -> &'static str {
+        match self {
+            Self::Clear => "☀",
+            Self::Cloudy => "☁",
+            Self::Fog => "🌫",
+            Self::Rain => "☂",
+            Self::Snow => "❄",
+            Self::Thunder => "⚡",
+        }
+    }
+}
+
+// ==========================================
+// 🌡️ 当前天气 (所有数据源统一的结构化结果)
+// ==========================================
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeatherReport {
+    pub condition: Condition,
+    pub temp: f64,             // ℃
+    pub high: Option<f64>,     // 当天最高温
+    pub low: Option<f64>,      // 当天最低温
+    pub humidity: Option<f64>, // 相对湿度 %
+    pub wind: Option<f64>,     // 风速 km/h
+}
+
+impl WeatherReport {
+    // "☀ 26℃ 20-30"；数据源没给最高/最低温时只显示 "☀ 26℃"
+    pub fn display(&self, u: Units) -> String {
+        match self.hilo(u) {
+            Some(range) => format!("{} {} {}", self.condition.icon(), self.temp_text(u), range),
+            None => format!("{} {}", self.condition.icon(), self.temp_text(u)),
+        }
+    }
+
+    // "26℃"
+    pub fn temp_text(&self, u: Units) -> String {
+        format!("{:.0}{}", u.temp(self.temp), u.temp_symbol())
+    }
+
+    // 精简动画模式用: 零下时省掉单位符号，给 "-12" 腾出宽度
+    pub fn temp_short(&self, u: Units) -> String {
+        let t = format!("{:.0}", u.temp(self.temp));
+        if t.starts_with('-') { t } else { format!("{}{}", t, u.temp_symbol()) }
+    }
+
+    // "20-30"
+    pub fn hilo(&self, u: Units) -> Option<String> {
+        Some(format!("{:.0}-{:.0}", u.temp(self.low?), u.temp(self.high?)))
+    }
+
+    pub fn field(&self, f: Field, u: Units) -> String {
+        match f {
+            Field::Temp => self.temp_text(u),
+            Field::HiLo => self.hilo(u).unwrap_or_else(|| "--".to_string()),
+            Field::Humidity => self.humidity.map_or("H:--".to_string(), |h| format!("H:{:.0}%", h)),
+            Field::Wind => self.wind.map_or("W:--".to_string(), |w| format!("W:{}", u.wind_text(w))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DayForecast {
+    pub condition: Condition,
+    pub min: f64,
+    pub max: f64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WeatherExtras {
+    pub tomorrow: Option<DayForecast>,
+    pub aqi: Option<f64>,
+    pub pm25: Option<f64>,
+    // 逐小时降水概率 (当地时间, 百分比)，按时间升序
+    pub rain: Vec<(NaiveDateTime, u8)>,
+}
+
+impl WeatherExtras {
+    // 用 other 补齐自己缺的字段 (心知天气免费版没有逐小时数据时用 Open-Meteo 补)
+    pub fn fill_from(&mut self, other: WeatherExtras) {
+        if self.tomorrow.is_none() {
+            self.tomorrow = other.tomorrow;
+        }
+        if self.aqi.is_none() {
+            self.aqi = other.aqi;
+        }
+        if self.pm25.is_none() {
+            self.pm25 = other.pm25;
+        }
+        if self.rain.is_empty() {
+            self.rain = other.rain;
+        }
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.tomorrow.is_some() && self.aqi.is_some() && !self.rain.is_empty()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tomorrow.is_none() && self.aqi.is_none() && self.pm25.is_none() && self.rain.is_empty()
+    }
+
+    // 未来一小时的降水概率: 条目 t 代表 [t, t+1h)，取包含 now 的那条与其后第一条中的最大值
+    pub fn rain_next_hour(&self, now: NaiveDateTime) -> Option<u8> {
+        let current = self.rain.iter().find(|(t, _)| *t > now - Duration::hours(1) && *t <= now);
+        let next = self.rain.iter().filter(|(t, _)| *t > now).min_by_key(|(t, _)| *t);
+        current.into_iter().chain(next).map(|(_, p)| *p).max()
+    }
+
+    pub fn display(&self, mode: ExtraMode, u: Units, now: NaiveDateTime) -> String {
+        match mode {
+            ExtraMode::Tomorrow => match &self.tomorrow {
+                Some(d) => format!("TMR {}{:.0}-{:.0}", d.condition.icon(), u.temp(d.min), u.temp(d.max)),
+                None => "TMR:--".to_string(),
+            },
+            ExtraMode::Aqi => self.aqi.map_or("AQI:--".to_string(), |v| format!("AQI:{:.0}", v)),
+            ExtraMode::Pm25 => self.pm25.map_or("PM:--".to_string(), |v| format!("PM:{:.0}", v)),
+            ExtraMode::Rain => self.rain_next_hour(now).map_or("R:--".to_string(), |p| format!("R:{}%", p)),
+        }
+    }
+}
+
+// ==========================================
+// 🔧 数据源辅助
+// ==========================================
+// 心知逐小时数据没有降水概率，只有天气现象: 雨/雪类记 100%，其余 0%
+pub fn seniverse_rain_pct(code: i32) -> u8 {
+    if (10..=29).contains(&code) { 100 } else { 0 }
+}
+
+// "2026-10-19T14:00" (Open-Meteo, timezone=auto) / "2026-10-19T14:00:00+08:00" (心知)
+pub fn parse_local_time(s: &str) -> Option<NaiveDateTime> {
+    let s = s.get(..16)?;
+    NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M").ok()
+}
+
+// ==========================================
+// 🚨 降雨告警: 未来一小时降水概率达到阈值时播报一次，降到阈值以下后重新布防
+// ==========================================
+#[derive(Debug, Default)]
+pub struct RainAlarm {
+    fired: bool, // true = 本轮降雨已播报过
+}
+
+impl RainAlarm {
+    pub fn update(&mut self, pct: Option<u8>, threshold: u8) -> Option<String> {
+        let p = pct?;
+        if p >= threshold {
+            if !self.fired {
+                self.fired = true;
+                return Some(format!("RAIN {}% 1H", p));
+            }
+        } else {
+            self.fired = false;
+        }
+        None
+    }
+}
+
+// ==========================================
+// 🧪 单元测试
+// ==========================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn t(s: &str) -> NaiveDateTime {
+        parse_local_time(s).unwrap()
+    }
+
+    #[test]
+    fn next_hour_window() {
+        let ex = WeatherExtras {
+            rain: vec![
+                (t("2026-10-19T13:00"), 90),
+                (t("2026-10-19T14:00"), 20),
+                (t("2026-10-19T15:00"), 70),
+                (t("2026-10-19T16:00"), 100),
+            ],
+            ..Default::default()
+        };
+        // 14:20: 覆盖 14:00 与 15:00 两个条目
+        assert_eq!(ex.rain_next_hour(t("2026-10-19T14:20")), Some(70));
+        assert_eq!(ex.display(ExtraMode::Rain, Units::default(), t("2026-10-19T14:20")), "R:70%");
+        assert_eq!(ex.rain_next_hour(t("2026-10-19T20:00")), None);
+        assert_eq!(ex.display(ExtraMode::Rain, Units::default(), t("2026-10-19T20:00")), "R:--");
+    }
+
+    // 整点边界: 14:00 整属于 14:00 条目 (不再算 13:00)，13:59 仍在 13:00 条目里
+    #[test]
+    fn next_hour_at_boundaries() {
+        let ex = WeatherExtras {
+            rain: vec![
+                (t("2026-10-19T13:00"), 90),
+                (t("2026-10-19T14:00"), 20),
+                (t("2026-10-19T15:00"), 70),
+                (t("2026-10-19T16:00"), 100),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(ex.rain_next_hour(t("2026-10-19T13:59")), Some(90));
+        assert_eq!(ex.rain_next_hour(t("2026-10-19T14:00")), Some(70));
+        assert_eq!(ex.rain_next_hour(t("2026-10-19T15:00")), Some(100));
+        // 最后一条之后无数据；第一条之前只看第一条
+        assert_eq!(ex.rain_next_hour(t("2026-10-19T17:00")), None);
+        assert_eq!(ex.rain_next_hour(t("2026-10-19T12:30")), Some(90));
+    }
+
+    #[test]
+    fn displays_and_merge() {
+        let now = t("2026-10-19T12:00");
+        let mut ex = WeatherExtras { aqi: Some(53.4), ..Default::default() };
+        assert_eq!(ex.display(ExtraMode::Aqi, Units::default(), now), "AQI:53");
+        assert_eq!(ex.display(ExtraMode::Tomorrow, Units::default(), now), "TMR:--");
+        assert!(!ex.is_complete());
+
+        ex.fill_from(WeatherExtras {
+            tomorrow: Some(DayForecast { condition: Condition::from_wmo(61), min: 17.6, max: 25.2 }),
+            aqi: Some(99.0),
+            pm25: Some(12.3),
+            rain: vec![(now, 40)],
+        });
+        assert_eq!(ex.display(ExtraMode::Aqi, Units::default(), now), "AQI:53"); // 已有的不覆盖
+        assert_eq!(ex.display(ExtraMode::Tomorrow, Units::default(), now), "TMR ☂18-25");
+        assert_eq!(ex.display(ExtraMode::Pm25, Units::default(), now), "PM:12");
+        assert!(ex.is_complete());
+    }
+
+    #[test]
+    fn params_icons_times() {
+        assert_eq!(parse_extra("tomorrow"), Some(ExtraMode::Tomorrow));
+        assert_eq!(parse_extra("pm2.5"), Some(ExtraMode::Pm25));
+        assert_eq!(parse_extra(""), None);
+        assert_eq!(seniverse_rain_pct(22), 100);
+        assert_eq!(
+            parse_local_time("2026-10-19T14:00:00+08:00"),
+            parse_local_time("2026-10-19T14:00")
+        );
+        assert_eq!(parse_local_time("bad"), None);
+    }
+
+    #[test]
+    fn report_fields_and_units() {
+        let r = WeatherReport {
+            condition: Condition::Clear,
+            temp: 25.0,
+            high: Some(30.0),
+            low: Some(20.0),
+            humidity: Some(81.6),
+            wind: Some(9.0),
+        };
+        let c = Units::default();
+        let f = Units::parse("F", "m/s");
+        assert_eq!(r.display(c), "☀ 25℃ 20-30");
+        assert_eq!(r.display(f), "☀ 77℉ 68-86");
+        assert_eq!(r.field(Field::Humidity, c), "H:82%");
+        assert_eq!(r.field(Field::Wind, c), "W:9KMH");
+        assert_eq!(r.field(Field::Wind, f), "W:2.5M/S");
+        assert_eq!(r.field(Field::Wind, Units::parse("c", "mph")), "W:6MPH");
+        assert_eq!(r.field(Field::HiLo, f), "68-86");
+
+        let cold = WeatherReport { temp: -12.4, high: None, humidity: None, ..r };
+        assert_eq!(cold.temp_short(c), "-12");
+        assert_eq!(cold.field(Field::Temp, c), "-12℃");
+        assert_eq!(cold.field(Field::HiLo, c), "--");
+        assert_eq!(cold.field(Field::Humidity, c), "H:--");
+        assert_eq!(r.temp_short(f), "77℉");
+        assert_eq!(parse_field("hum"), Some(Field::Humidity));
+        assert_eq!(parse_field("tomorrow"), None);
+    }
+
+    #[test]
+    fn condition_mapping() {
+        assert_eq!(Condition::from_wmo(95).icon(), "⚡");
+        assert_eq!(Condition::from_wmo(200), Condition::Cloudy);
+        assert_eq!(Condition::from_seniverse(13), Condition::Rain);
+        assert_eq!(Condition::from_seniverse(11), Condition::Thunder);
+        assert_eq!(Condition::from_seniverse(99), Condition::Clear);
+        assert_eq!(Condition::from_text("雷阵雨"), Condition::Thunder);
+        assert_eq!(Condition::from_text("Patchy light drizzle"), Condition::Rain);
+        assert_eq!(Condition::from_text("霾"), Condition::Fog);
+        assert_eq!(Condition::from_text("Partly cloudy"), Condition::Cloudy);
+        assert_eq!(Condition::from_text("Sunny"), Condition::Clear);
+    }
+
+    #[test]
+    fn rain_alarm_once_per_episode() {
+        let mut a = RainAlarm::default();
+        assert_eq!(a.update(Some(30), 60), None);
+        assert_eq!(a.update(Some(80), 60), Some("RAIN 80% 1H".to_string()));
+        assert_eq!(a.update(Some(90), 60), None);
+        assert_eq!(a.update(None, 60), None); // 暂无数据不影响状态
+        assert_eq!(a.update(Some(10), 60), None);
+        assert_eq!(a.update(Some(60), 60), Some("RAIN 60% 1H".to_string()));
+    }
+}
@@ -0,0 +1,867 @@
+// ==========================================
+// 🌤️ weather_provider.rs — 天气数据源抽象与按序故障切换 (v2.6.0)
+// 以前 get_smart_weather 按字符串只选一个数据源，四个 get_weather_from_* 各自
+// 写一份图标映射和格式化。现在:
+//   - 每个数据源实现 WeatherProvider，只负责把接口数据转换成结构化的 WeatherReport
+//   - --weather-source 可写逗号分隔的有序列表，如 "seniverse,openmeteo,uapis"，
+//     前一个出错自动换下一个；"auto" / 空 = uapis,openmeteo,wttr
+//   - 图标与显示格式由 weather::Condition / WeatherReport 统一完成
+// 缓存与失败退避仍在 net_agent (整条链路共用一份 30min 缓存 + 120s 退避)。
+// 各接口的基础地址集中在 Endpoints，可用 --api-base 名字=地址 覆盖 (镜像/反代/测试桩)。
+// ==========================================
+use crate::weather::{parse_local_time, seniverse_rain_pct, Condition, DayForecast, WeatherExtras, WeatherReport};
+use crate::http_client::Http;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+
+pub const DEFAULT_ORDER: &str = "uapis,openmeteo,wttr";
+
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+pub trait WeatherProvider: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    // 当前天气；失败返回屏幕上显示的错误短语 (均以 "W:" 开头)
+    fn current<'a>(&'a self, http: &'a Http, city: &'a str) -> BoxFuture<'a, Result<WeatherReport, String>>;
+
+    // 扩展数据 (明日预报 / 空气质量 / 逐小时降水)，不支持的数据源返回空
+    fn extras<'a>(&'a self, _http: &'a Http, _city: &'a str) -> BoxFuture<'a, WeatherExtras> {
+        Box::pin(async { WeatherExtras::default() })
+    }
+}
+
+// ==========================================
+// 🔗 接口基础地址 (不含路径)
+// ==========================================
+#[derive(Debug, Clone)]
+pub struct Endpoints {
+    pub uapis: String,
+    pub wttr: String,
+    pub seniverse: String,
+    pub open_meteo: String,
+    pub open_meteo_geo: String,
+    pub open_meteo_air: String,
+    // IP 定位接口 (完整 URL，按顺序尝试，net_agent 用)
+    pub geoip: Vec<String>,
+}
+
+impl Default for Endpoints {
+    fn default() -> Self {
+        Self {
+            uapis: "https://uapis.cn".into(),
+            wttr: "https://wttr.in".into(),
+            seniverse: "https://api.seniverse.com".into(),
+            open_meteo: "https://api.open-meteo.com".into(),
+            open_meteo_geo: "https://geocoding-api.open-meteo.com".into(),
+            open_meteo_air: "https://air-quality-api.open-meteo.com".into(),
+            geoip: vec![
+                "http://app.zichen.zone/api/geoip/api.php".into(),
+                "http://ip-api.com/json/?lang=zh-CN&fields=city,lat,lon".into(),
+            ],
+        }
+    }
+}
+
+impl Endpoints {
+    // --api-base 条目: "名字=地址"；geoip 可重复，出现即替换默认的定位接口列表
+    pub fn with_overrides(overrides: &[String]) -> Self {
+        let mut ep = Self::default();
+        let mut geoip: Vec<String> = Vec::new();
+        for item in overrides {
+            let Some((name, url)) = item.split_once('=') else {
+                println!("⚠️ [接口地址] 格式应为 名字=地址，已忽略: {}", item);
+                continue;
+            };
+            let url = url.trim().trim_end_matches('/').to_string();
+            match name.trim() {
+                "uapis" => ep.uapis = url,
+                "wttr" => ep.wttr = url,
+                "seniverse" => ep.seniverse = url,
+                "openmeteo" => ep.open_meteo = url,
+                "openmeteo_geo" => ep.open_meteo_geo = url,
+                "openmeteo_air" => ep.open_meteo_air = url,
+                "geoip" => geoip.push(url),
+                other => println!("⚠️ [接口地址] 未知名字，已忽略: {}", other),
+            }
+        }
+        if !geoip.is_empty() {
+            ep.geoip = geoip;
+        }
+        ep
+    }
+
+    // 所有 Open-Meteo 地址都指向同一个基础地址 (测试桩用)
+    #[cfg(test)]
+    pub fn all(base: &str) -> Self {
+        Self {
+            uapis: base.into(),
+            wttr: base.into(),
+            seniverse: base.into(),
+            open_meteo: base.into(),
+            open_meteo_geo: base.into(),
+            open_meteo_air: base.into(),
+            geoip: vec![format!("{}/geoip", base)],
+        }
+    }
+}
+
+// ==========================================
+// ⛓️ 数据源链: 按配置顺序故障切换
+// ==========================================
+pub struct WeatherChain {
+    providers: Vec<Box<dyn WeatherProvider>>,
+    // 扩展数据兜底 (Open-Meteo 免 key，没列在顺序里也用它补齐)
+    extras_fallback: OpenMeteo,
+}
+
+impl WeatherChain {
+    // 解析 --weather-source: 逗号分隔，按顺序尝试；未知名字忽略，重复的只保留第一个
+    pub fn new(source: &str, seniverse_key: &str, ep: &Endpoints) -> Self {
+        let list = match source.trim() {
+            "" | "auto" => DEFAULT_ORDER,
+            s => s,
+        };
+        let open_meteo = OpenMeteo::new(ep);
+        let mut providers: Vec<Box<dyn WeatherProvider>> = Vec::new();
+        for name in list.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+            let p: Box<dyn WeatherProvider> = match name {
+                "uapis" => Box::new(Uapis { base: ep.uapis.clone() }),
+                "wttr" => Box::new(Wttr { base: ep.wttr.clone() }),
+                "openmeteo" | "open-meteo" => Box::new(open_meteo.clone()),
+                "seniverse" => Box::new(Seniverse { base: ep.seniverse.clone(), key: seniverse_key.trim().to_string() }),
+                _ => {
+                    println!("⚠️ [天气] 未知数据源，已忽略: {}", name);
+                    continue;
+                }
+            };
+            if !providers.iter().any(|q| q.name() == p.name()) {
+                providers.push(p);
+            }
+        }
+        // 全部写错时兜底 (与旧版一致: 未知数据源走 uapis)
+        if providers.is_empty() {
+            providers.push(Box::new(Uapis { base: ep.uapis.clone() }));
+        }
+        Self { providers, extras_fallback: open_meteo }
+    }
+
+    pub fn names(&self) -> Vec<&'static str> {
+        self.providers.iter().map(|p| p.name()).collect()
+    }
+
+    // 按顺序尝试，第一个成功的为准 (附带数据源名字)；全部失败时返回首选数据源的错误
+    pub async fn current(&self, http: &Http, city: &str) -> Result<(WeatherReport, &'static str), String> {
+        let mut first_err: Option<String> = None;
+        for p in &self.providers {
+            match p.current(http, city).await {
+                Ok(r) => return Ok((r, p.name())),
+                Err(e) => {
+                    println!("⚠️ [天气] {} 失败 ({})", p.name(), e);
+                    first_err.get_or_insert(e);
+                }
+            }
+        }
+        Err(first_err.unwrap_or_else(|| "W:Err".to_string()))
+    }
+
+    // 扩展数据按同样的顺序逐个补齐缺失字段
+    pub async fn extras(&self, http: &Http, city: &str) -> WeatherExtras {
+        let mut extras = WeatherExtras::default();
+        for p in &self.providers {
+            if extras.is_complete() {
+                break;
+            }
+            extras.fill_from(p.extras(http, city).await);
+        }
+        if !extras.is_complete() && !self.providers.iter().any(|p| p.name() == "openmeteo") {
+            extras.fill_from(self.extras_fallback.extras(http, city).await);
+        }
+        extras
+    }
+}
+
+// 数字或数字字符串 ("63" / 63)
+fn number(v: &Value) -> Option<f64> {
+    v.as_f64().or_else(|| v.as_str().and_then(|s| s.trim().parse().ok()))
+}
+
+// ==========================================
+// [通道1] uapis.cn (适合国内，支持中文名)
+// ==========================================
+#[derive(Deserialize, Debug)]
+struct UapisResponse {
+    // 天气现象 (例如: "多云", "晴", "小雨")
+    weather: String,
+    // 当前温度
+    temperature: f64,
+    // 最高温 / 最低温 (仅 forecast=true 时返回)
+    #[serde(default)]
+    temp_max: Option<f64>,
+    #[serde(default)]
+    temp_min: Option<f64>,
+    #[serde(default)]
+    humidity: Value,
+}
+
+impl UapisResponse {
+    fn report(self) -> WeatherReport {
+        WeatherReport {
+            condition: Condition::from_text(&self.weather),
+            temp: self.temperature,
+            high: self.temp_max,
+            low: self.temp_min,
+            humidity: number(&self.humidity),
+            wind: None, // 只给风力等级 ("≤3")，没有风速
+        }
+    }
+}
+
+pub struct Uapis {
+    pub base: String,
+}
+
+impl WeatherProvider for Uapis {
+    fn name(&self) -> &'static str {
+        "uapis"
+    }
+
+    fn current<'a>(&'a self, http: &'a Http, city: &'a str) -> BoxFuture<'a, Result<WeatherReport, String>> {
+        Box::pin(async move {
+            let url = format!("{}/api/v1/misc/weather?city={}&forecast=true", self.base, city);
+            let resp = http.get("weather", &url).await.map_err(|_| "W:Err(U)".to_string())?;
+            if !resp.is_success() {
+                return Err(format!("W:Err({})", resp.status));
+            }
+            let data = resp.json::<UapisResponse>().map_err(|_| "W:Err(U)".to_string())?;
+            Ok(data.report())
+        })
+    }
+}
+
+// ==========================================
+// [通道2] Wttr
+// ==========================================
+#[derive(Deserialize, Debug)]
+struct WttrResult {
+    current_condition: Vec<WttrCurrent>,
+    weather: Vec<WttrDaily>,
+}
+
+#[derive(Deserialize, Debug)]
+#[allow(non_snake_case)]
+struct WttrCurrent {
+    temp_C: String,
+    weatherDesc: Vec<WttrValue>,
+    #[serde(default)]
+    humidity: String,
+    #[serde(default)]
+    windspeedKmph: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[allow(non_snake_case)]
+struct WttrDaily {
+    maxtempC: String,
+    mintempC: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct WttrValue {
+    value: String,
+}
+
+impl WttrResult {
+    fn report(&self) -> Option<WeatherReport> {
+        let curr = self.current_condition.first()?;
+        let daily = self.weather.first();
+        let desc = curr.weatherDesc.first().map(|d| d.value.as_str()).unwrap_or("");
+        Some(WeatherReport {
+            condition: Condition::from_text(desc),
+            temp: curr.temp_C.trim().parse().ok()?,
+            high: daily.and_then(|d| d.maxtempC.trim().parse().ok()),
+            low: daily.and_then(|d| d.mintempC.trim().parse().ok()),
+            humidity: curr.humidity.trim().parse().ok(),
+            wind: curr.windspeedKmph.trim().parse().ok(),
+        })
+    }
+}
+
+pub struct Wttr {
+    pub base: String,
+}
+
+impl WeatherProvider for Wttr {
+    fn name(&self) -> &'static str {
+        "wttr"
+    }
+
+    fn current<'a>(&'a self, http: &'a Http, city: &'a str) -> BoxFuture<'a, Result<WeatherReport, String>> {
+        Box::pin(async move {
+            let url = format!("{}/{}?format=j1", self.base, city);
+            #[cfg(debug_assertions)]
+            println!("DEBUG: Requesting Wttr: {}", url);
+
+            let resp = http.get("weather", &url).await.map_err(|e| {
+                println!("DEBUG: Wttr Network Error: {:?}", e);
+                "W:NetErr".to_string()
+            })?;
+            // 检查 HTTP 状态码 (wttr 经常封 IP 返回 429 或 503)
+            if !resp.is_success() {
+                return Err(format!("W:Err({})", resp.status));
+            }
+            let json = resp.json::<WttrResult>().map_err(|e| {
+                println!("DEBUG: Wttr JSON Parse Error: {:?}", e);
+                "W:JsonErr".to_string()
+            })?;
+            json.report().ok_or_else(|| "W:DataErr".to_string())
+        })
+    }
+}
+
+// ==========================================
+// [通道3] 心知天气
+// ==========================================
+#[derive(Deserialize, Debug)]
+struct SeniverseResponse {
+    results: Vec<SeniverseResult>,
+}
+#[derive(Deserialize, Debug)]
+struct SeniverseResult {
+    daily: Vec<SeniverseDaily>,
+}
+#[derive(Deserialize, Debug)]
+struct SeniverseDaily {
+    high: String,
+    low: String,
+    code_day: String,
+    #[serde(default)]
+    humidity: String,
+    #[serde(default)]
+    wind_speed: String,
+}
+
+impl SeniverseDaily {
+    fn condition(&self) -> Condition {
+        Condition::from_seniverse(self.code_day.parse().unwrap_or(99))
+    }
+}
+
+// 空气质量 (v3/air/now.json, scope=city)
+#[derive(Deserialize, Debug)]
+struct SeniverseAirResponse {
+    results: Vec<SeniverseAirResult>,
+}
+#[derive(Deserialize, Debug)]
+struct SeniverseAirResult {
+    air: SeniverseAir,
+}
+#[derive(Deserialize, Debug)]
+struct SeniverseAir {
+    city: SeniverseAirCity,
+}
+#[derive(Deserialize, Debug)]
+struct SeniverseAirCity {
+    aqi: String,
+    pm25: String,
+}
+
+// 逐小时预报 (v3/weather/hourly.json，付费接口，免费 key 会失败)
+#[derive(Deserialize, Debug)]
+struct SeniverseHourlyResponse {
+    results: Vec<SeniverseHourlyResult>,
+}
+#[derive(Deserialize, Debug)]
+struct SeniverseHourlyResult {
+    hourly: Vec<SeniverseHour>,
+}
+#[derive(Deserialize, Debug)]
+struct SeniverseHour {
+    time: String,
+    code: String,
+}
+
+impl SeniverseResponse {
+    fn report(&self) -> Option<WeatherReport> {
+        let daily = self.results.first()?.daily.first()?;
+        let max = daily.high.parse::<f64>().ok()?;
+        let min = daily.low.parse::<f64>().ok()?;
+        Some(WeatherReport {
+            condition: daily.condition(),
+            // 取平均值当作当前温度 (免费版日预报不返回实时温度)
+            temp: (max + min) / 2.0,
+            high: Some(max),
+            low: Some(min),
+            humidity: daily.humidity.parse().ok(),
+            wind: daily.wind_speed.parse().ok(),
+        })
+    }
+}
+
+pub struct Seniverse {
+    pub base: String,
+    pub key: String,
+}
+
+impl WeatherProvider for Seniverse {
+    fn name(&self) -> &'static str {
+        "seniverse"
+    }
+
+    fn current<'a>(&'a self, http: &'a Http, city: &'a str) -> BoxFuture<'a, Result<WeatherReport, String>> {
+        Box::pin(async move {
+            // [安全] key 为空直接提示，不再依赖内置的公共测试 key
+            if self.key.is_empty() {
+                return Err("W:NoKey".to_string());
+            }
+            let url = format!(
+                "{}/v3/weather/daily.json?key={}&location={}&language=en&unit=c&start=0&days=1",
+                self.base, self.key, city
+            );
+            let resp = http.get("weather", &url).await.map_err(|_| "W:Err(S)".to_string())?;
+            let json = resp.json::<SeniverseResponse>().map_err(|_| "W:Err(S)".to_string())?;
+            json.report().ok_or_else(|| "W:Err(S)".to_string())
+        })
+    }
+
+    fn extras<'a>(&'a self, http: &'a Http, city: &'a str) -> BoxFuture<'a, WeatherExtras> {
+        Box::pin(async move {
+            let mut extras = WeatherExtras::default();
+            if self.key.is_empty() {
+                return extras;
+            }
+
+            let url = format!(
+                "{}/v3/weather/daily.json?key={}&location={}&language=en&unit=c&start=0&days=2",
+                self.base, self.key, city
+            );
+            if let Ok(resp) = http.get("weather", &url).await {
+                if let Ok(json) = resp.json::<SeniverseResponse>() {
+                    extras.tomorrow = json.results.first().and_then(|r| r.daily.get(1)).and_then(|d| {
+                        Some(DayForecast { condition: d.condition(), min: d.low.parse().ok()?, max: d.high.parse().ok()? })
+                    });
+                }
+            }
+
+            let url = format!("{}/v3/air/now.json?key={}&location={}&language=en&scope=city", self.base, self.key, city);
+            if let Ok(resp) = http.get("weather", &url).await {
+                if let Ok(json) = resp.json::<SeniverseAirResponse>() {
+                    if let Some(r) = json.results.first() {
+                        extras.aqi = r.air.city.aqi.parse().ok();
+                        extras.pm25 = r.air.city.pm25.parse().ok();
+                    }
+                }
+            }
+
+            let url = format!(
+                "{}/v3/weather/hourly.json?key={}&location={}&language=en&unit=c&start=0&hours=3",
+                self.base, self.key, city
+            );
+            if let Ok(resp) = http.get("weather", &url).await {
+                if let Ok(json) = resp.json::<SeniverseHourlyResponse>() {
+                    if let Some(r) = json.results.first() {
+                        extras.rain = r
+                            .hourly
+                            .iter()
+                            .filter_map(|h| Some((parse_local_time(&h.time)?, seniverse_rain_pct(h.code.parse().ok()?))))
+                            .collect();
+                    }
+                }
+            }
+            extras
+        })
+    }
+}
+
+// ==========================================
+// [通道4] Open-Meteo (免 key，需先查城市坐标)
+// ==========================================
+#[derive(Deserialize, Debug)]
+struct OmGeoResponse {
+    results: Option<Vec<OmLocation>>,
+}
+
+#[derive(Deserialize, Debug)]
+#[allow(dead_code)]
+struct OmLocation {
+    name: String,
+    latitude: f64,
+    longitude: f64,
+}
+
+#[derive(Deserialize, Debug)]
+struct OmWeatherResponse {
+    current_weather: OmCurrentWeather,
+    // 当天最高/最低温 (可选，接口异常时不影响当前温度显示)
+    #[serde(default)]
+    daily: Option<OmDaily>,
+    // 相对湿度 (current_weather 里没有)
+    #[serde(default)]
+    current: Option<OmCurrent>,
+}
+
+#[derive(Deserialize, Debug)]
+struct OmCurrentWeather {
+    temperature: f64,
+    weathercode: u8,
+    #[serde(default)]
+    windspeed: Option<f64>,
+}
+
+#[derive(Deserialize, Debug)]
+struct OmCurrent {
+    #[serde(default)]
+    relative_humidity_2m: Option<f64>,
+}
+
+#[derive(Deserialize, Debug)]
+struct OmDaily {
+    #[serde(default)]
+    weathercode: Vec<u8>,
+    #[serde(default)]
+    temperature_2m_max: Vec<f64>,
+    #[serde(default)]
+    temperature_2m_min: Vec<f64>,
+}
+
+// 扩展数据请求: 两天的日预报 (取明天) + 逐小时降水概率
+#[derive(Deserialize, Debug)]
+struct OmExtrasResponse {
+    #[serde(default)]
+    daily: Option<OmDaily>,
+    #[serde(default)]
+    hourly: Option<OmHourly>,
+}
+
+#[derive(Deserialize, Debug)]
+struct OmHourly {
+    #[serde(default)]
+    time: Vec<String>,
+    #[serde(default)]
+    precipitation_probability: Vec<Option<u8>>,
+}
+
+// 空气质量 (air-quality-api.open-meteo.com)
+#[derive(Deserialize, Debug)]
+struct OmAirResponse {
+    #[serde(default)]
+    current: Option<OmAirCurrent>,
+}
+
+#[derive(Deserialize, Debug)]
+struct OmAirCurrent {
+    us_aqi: Option<f64>,
+    pm2_5: Option<f64>,
+}
+
+impl OmWeatherResponse {
+    fn report(&self) -> WeatherReport {
+        let daily = self.daily.as_ref();
+        WeatherReport {
+            condition: Condition::from_wmo(self.current_weather.weathercode),
+            temp: self.current_weather.temperature,
+            high: daily.and_then(|d| d.temperature_2m_max.first().copied()),
+            low: daily.and_then(|d| d.temperature_2m_min.first().copied()),
+            humidity: self.current.as_ref().and_then(|c| c.relative_humidity_2m),
+            wind: self.current_weather.windspeed,
+        }
+    }
+}
+
+// Open-Meteo 地理编码: 城市名 -> 经纬度 (失败时返回屏幕上显示的错误短语)
+async fn open_meteo_coords(http: &Http, base: &str, city: &str) -> Result<(f64, f64), String> {
+    // 清洗城市名：IP 定位返回的多是 "北京市"/"朝阳区" 这类带行政后缀的名字
+    let clean_city = city
+        .trim()
+        .trim_end_matches('市')
+        .trim_end_matches('区')
+        .trim_end_matches('县');
+    let clean_city = if clean_city.is_empty() { city.trim() } else { clean_city };
+
+    let geo_url = format!(
+        "{}/v1/search?name={}&count=1&language=zh&format=json",
+        base, clean_city
+    );
+
+    let resp = http.get("weather", &geo_url).await.map_err(|_| "W:GeoNet".to_string())?;
+    if !resp.is_success() {
+        return Err("W:GeoErr".to_string());
+    }
+    let data = resp.json::<OmGeoResponse>().map_err(|_| "W:GeoJson".to_string())?;
+    data.results
+        .and_then(|r| r.first().map(|loc| (loc.latitude, loc.longitude)))
+        .ok_or_else(|| "W:NoCity".to_string())
+}
+
+#[derive(Clone)]
+pub struct OpenMeteo {
+    pub api: String,
+    pub geo: String,
+    pub air: String,
+    // 城市 -> 经纬度 (只缓存成功结果)。clone 出来的实例共用一份，
+    // 链里的 Open-Meteo 与扩展数据兜底每轮刷新只需地理编码一次，不占用按主机的限流配额
+    coords: Arc<Mutex<HashMap<String, (f64, f64)>>>,
+}
+
+impl OpenMeteo {
+    pub fn new(ep: &Endpoints) -> Self {
+        Self {
+            api: ep.open_meteo.clone(),
+            geo: ep.open_meteo_geo.clone(),
+            air: ep.open_meteo_air.clone(),
+            coords: Arc::default(),
+        }
+    }
+
+    async fn coords(&self, http: &Http, city: &str) -> Result<(f64, f64), String> {
+        if let Some(c) = self.coords.lock().ok().and_then(|m| m.get(city).copied()) {
+            return Ok(c);
+        }
+        let c = open_meteo_coords(http, &self.geo, city).await?;
+        if let Ok(mut m) = self.coords.lock() {
+            m.insert(city.to_string(), c);
+        }
+        Ok(c)
+    }
+}
+
+impl WeatherProvider for OpenMeteo {
+    fn name(&self) -> &'static str {
+        "openmeteo"
+    }
+
+    fn current<'a>(&'a self, http: &'a Http, city: &'a str) -> BoxFuture<'a, Result<WeatherReport, String>> {
+        Box::pin(async move {
+            let (lat, lon) = self.coords(http, city).await?;
+
+            // 当前天气 + 当天最高/最低温 + 湿度 (一次请求全带回)
+            let url = format!(
+                "{}/v1/forecast?latitude={}&longitude={}&current_weather=true&current=relative_humidity_2m&daily=temperature_2m_max,temperature_2m_min&forecast_days=1&timezone=auto",
+                self.api, lat, lon
+            );
+            let resp = http.get("weather", &url).await.map_err(|_| "W:NetErr".to_string())?;
+            if !resp.is_success() {
+                return Err("W:ApiErr".to_string());
+            }
+            let data = resp.json::<OmWeatherResponse>().map_err(|_| "W:JsonErr".to_string())?;
+            Ok(data.report())
+        })
+    }
+
+    fn extras<'a>(&'a self, http: &'a Http, city: &'a str) -> BoxFuture<'a, WeatherExtras> {
+        Box::pin(async move {
+            let mut extras = WeatherExtras::default();
+            let (lat, lon) = match self.coords(http, city).await {
+                Ok(c) => c,
+                Err(e) => {
+                    println!("⚠️ [天气扩展] Open-Meteo 定位失败: {}", e);
+                    return extras;
+                }
+            };
+
+            let url = format!(
+                "{}/v1/forecast?latitude={}&longitude={}&daily=weathercode,temperature_2m_max,temperature_2m_min&hourly=precipitation_probability&forecast_days=2&timezone=auto",
+                self.api, lat, lon
+            );
+            if let Ok(resp) = http.get("weather", &url).await {
+                if let Ok(data) = resp.json::<OmExtrasResponse>() {
+                    if let Some(d) = &data.daily {
+                        if let (Some(min), Some(max)) = (d.temperature_2m_min.get(1), d.temperature_2m_max.get(1)) {
+                            let condition = Condition::from_wmo(d.weathercode.get(1).copied().unwrap_or(3));
+                            extras.tomorrow = Some(DayForecast { condition, min: *min, max: *max });
+                        }
+                    }
+                    if let Some(h) = &data.hourly {
+                        extras.rain = h
+                            .time
+                            .iter()
+                            .zip(&h.precipitation_probability)
+                            .filter_map(|(t, p)| Some((parse_local_time(t)?, (*p)?)))
+                            .collect();
+                    }
+                }
+            }
+
+            let url = format!(
+                "{}/v1/air-quality?latitude={}&longitude={}&current=us_aqi,pm2_5&timezone=auto",
+                self.air, lat, lon
+            );
+            if let Ok(resp) = http.get("weather", &url).await {
+                if let Ok(data) = resp.json::<OmAirResponse>() {
+                    if let Some(c) = data.current {
+                        extras.aqi = c.us_aqi;
+                        extras.pm25 = c.pm2_5;
+                    }
+                }
+            }
+            extras
+        })
+    }
+}
+
+// ==========================================
+// 🧪 单元测试
+// ==========================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::weather::Units;
+
+    fn names(source: &str) -> Vec<&'static str> {
+        WeatherChain::new(source, "k", &Endpoints::default()).names()
+    }
+
+    #[test]
+    fn order_parsing() {
+        assert_eq!(names("auto"), ["uapis", "openmeteo", "wttr"]);
+        assert_eq!(names(""), ["uapis", "openmeteo", "wttr"]);
+        assert_eq!(names("seniverse, open-meteo,bogus,openmeteo"), ["seniverse", "openmeteo"]);
+        assert_eq!(names("bogus"), ["uapis"]);
+    }
+
+    #[test]
+    fn endpoint_overrides() {
+        let ep = Endpoints::with_overrides(&[
+            "uapis=http://10.0.0.2:8080/".into(),
+            "geoip=http://a/geo".into(),
+            "geoip=http://b/geo".into(),
+            "nonsense".into(),
+        ]);
+        assert_eq!(ep.uapis, "http://10.0.0.2:8080");
+        assert_eq!(ep.geoip, ["http://a/geo", "http://b/geo"]);
+        assert_eq!(ep.wttr, Endpoints::default().wttr);
+    }
+
+    #[test]
+    fn provider_payloads_to_report() {
+        let u: UapisResponse =
+            serde_json::from_str(r#"{"weather":"雷阵雨","temperature":23.4,"temp_max":27,"temp_min":19,"humidity":"81"}"#)
+                .unwrap();
+        let r = u.report();
+        assert_eq!((r.condition, r.humidity, r.wind), (Condition::Thunder, Some(81.0), None));
+        assert_eq!(r.display(Units::default()), "⚡ 23℃ 19-27");
+
+        let w: WttrResult = serde_json::from_str(
+            r#"{"current_condition":[{"temp_C":"-3","weatherDesc":[{"value":"Light snow"}],"humidity":"90","windspeedKmph":"11"}],
+                "weather":[{"maxtempC":"0","mintempC":"-6"}]}"#,
+        )
+        .unwrap();
+        let r = w.report().unwrap();
+        assert_eq!((r.condition, r.wind), (Condition::Snow, Some(11.0)));
+        assert_eq!(r.display(Units::default()), "❄ -3℃ -6-0");
+
+        let s: SeniverseResponse = serde_json::from_str(
+            r#"{"results":[{"daily":[{"high":"30","low":"20","code_day":"4","humidity":"55","wind_speed":"8.4"}]}]}"#,
+        )
+        .unwrap();
+        assert_eq!(s.report().unwrap().display(Units::default()), "☁ 25℃ 20-30");
+
+        let o: OmWeatherResponse = serde_json::from_str(
+            r#"{"current_weather":{"temperature":26.6,"weathercode":0,"windspeed":7.2},"current":{"relative_humidity_2m":40}}"#,
+        )
+        .unwrap();
+        let r = o.report();
+        assert_eq!((r.humidity, r.wind), (Some(40.0), Some(7.2)));
+        assert_eq!(r.display(Units::default()), "☀ 27℃"); // daily 缺失时只显示当前温度
+    }
+
+    // ------------------------------------------
+    // 本地 HTTP 桩: 录制的响应走完整的请求/解析链路
+    // ------------------------------------------
+    use crate::mock_http::{fixtures, http, MockServer, Reply};
+    use std::time::Duration;
+
+    async fn mock_all_good() -> MockServer {
+        let m = MockServer::start().await;
+        m.route("/api/v1/misc/weather", Reply::Body(fixtures::UAPIS));
+        m.route("/Beijing", Reply::Body(fixtures::WTTR));
+        m.route("/v3/weather/daily.json", Reply::Body(fixtures::SENIVERSE_DAILY));
+        m.route("/v3/air/now.json", Reply::Body(fixtures::SENIVERSE_AIR));
+        m.route("/v1/search", Reply::Body(fixtures::OM_GEO));
+        m.route("/v1/forecast", Reply::Body(fixtures::OM_FORECAST));
+        m.route("/v1/air-quality", Reply::Body(fixtures::OM_AIR));
+        m
+    }
+
+    #[tokio::test]
+    async fn recorded_responses_parse() {
+        let m = mock_all_good().await;
+        let ep = Endpoints::all(&m.base);
+        let c = http();
+        let show = |src: &str| {
+            let chain = WeatherChain::new(src, "k", &ep);
+            let c = c.clone();
+            async move { chain.current(&c, "Beijing").await.map(|(r, _)| r) }
+        };
+
+        let r = show("uapis").await.unwrap();
+        assert_eq!((r.display(Units::default()), r.humidity), ("☂ 18℃ 14-21".to_string(), Some(82.0)));
+        let r = show("wttr").await.unwrap();
+        assert_eq!((r.display(Units::default()), r.wind), ("☁ 17℃ 12-22".to_string(), Some(11.0)));
+        let r = show("seniverse").await.unwrap();
+        assert_eq!((r.display(Units::default()), r.wind), ("☀ 17℃ 10-24".to_string(), Some(8.5)));
+        let r = show("openmeteo").await.unwrap();
+        assert_eq!((r.display(Units::default()), r.humidity, r.wind), ("☁ 20℃ 12-23".to_string(), Some(55.0), Some(9.4)));
+
+        // 心知没有逐小时 (路由未配置 -> 404)，缺的由 Open-Meteo 补齐
+        let ex = WeatherChain::new("seniverse", "k", &ep).extras(&c, "Beijing").await;
+        let tmr = ex.tomorrow.clone().unwrap();
+        assert_eq!((tmr.condition, tmr.max), (Condition::Rain, 19.0)); // 心知优先
+        assert_eq!((ex.aqi, ex.pm25), (Some(68.0), Some(41.0)));
+        assert_eq!(ex.rain.len(), 2);
+    }
+
+    // 当前天气 + 扩展数据 (或扩展数据兜底) 多轮刷新，同一城市只做一次地理编码
+    #[tokio::test]
+    async fn open_meteo_geocodes_once() {
+        let m = mock_all_good().await;
+        let ep = Endpoints::all(&m.base);
+        let c = http();
+        let chain = WeatherChain::new("openmeteo", "k", &ep);
+        for _ in 0..2 {
+            assert!(chain.current(&c, "Beijing").await.is_ok());
+            assert!(chain.extras(&c, "Beijing").await.is_complete());
+        }
+        assert_eq!(m.hits("/v1/search"), 1);
+
+        // 心知缺逐小时，每轮都由兜底实例补齐
+        let chain = WeatherChain::new("seniverse", "k", &ep);
+        for _ in 0..2 {
+            assert_eq!(chain.extras(&c, "Beijing").await.rain.len(), 2);
+        }
+        assert_eq!(m.hits("/v1/search"), 2);
+    }
+
+    #[tokio::test]
+    async fn failures_map_to_codes_and_fail_over() {
+        let m = mock_all_good().await;
+        m.route("/api/v1/misc/weather", Reply::Status(500));
+        m.route("/Beijing", Reply::Body(fixtures::NOT_JSON));
+        m.route("/v1/search", Reply::Slow(Duration::from_secs(3), fixtures::OM_GEO));
+        m.route("/v3/weather/daily.json", Reply::Body(fixtures::MISSING_FIELDS));
+        let ep = Endpoints::all(&m.base);
+        let c = http();
+
+        let err = |src: &'static str| {
+            let chain = WeatherChain::new(src, "k", &ep);
+            let c = c.clone();
+            async move { chain.current(&c, "Beijing").await.unwrap_err() }
+        };
+        assert_eq!(err("uapis").await, "W:Err(500)");
+        assert_eq!(err("wttr").await, "W:JsonErr");
+        assert_eq!(err("openmeteo").await, "W:GeoNet"); // 超时
+        assert_eq!(err("seniverse").await, "W:Err(S)");
+        assert_eq!(WeatherChain::new("seniverse", "", &ep).current(&c, "Beijing").await.unwrap_err(), "W:NoKey");
+
+        // 整条链全挂: 报首选数据源的错误
+        assert_eq!(err("uapis,wttr,seniverse").await, "W:Err(500)");
+
+        // 前面挂掉的自动换下一个
+        m.route("/Beijing", Reply::Body(fixtures::WTTR));
+        let (r, src) = WeatherChain::new("uapis,seniverse,wttr", "k", &ep).current(&c, "Beijing").await.unwrap();
+        assert_eq!((src, r.display(Units::default()).as_str()), ("wttr", "☁ 17℃ 12-22"));
+    }
+}
@@ -0,0 +1,318 @@
+// ==========================================
+// 📶 wifi.rs — 无线射频与终端状态 (wifi / wifi_ch / wifi_rssi 模块)
+// 射频列表来自 /sys/class/ieee80211/phyN，接口归属读 /sys/class/net/*/phy80211/name，
+// 终端/信道/繁忙度用 `iw` 的 station dump / info / survey dump 输出解析 (OpenWrt 默认自带 iw)。
+// 参数为射频编号，与 temp_single:N 相同用法: "wifi:0" / "wifi_ch:1" / "wifi_rssi:2"
+// 🌟 iw 是外部进程 (fork/exec，驱动忙时还可能卡住)，交给 poller.rs 在后台定期采集
+// ==========================================
+use crate::poller::Poller;
+use std::fs;
+use std::process::Command;
+use std::time::Duration;
+
+// 采集间隔与单轮 iw 超时
+const POLL_SECS: u64 = 5;
+const IW_TIMEOUT_SECS: u64 = 10;
+
+// 单个射频的汇总状态
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RadioStatus {
+    pub stations: usize,
+    pub channel: Option<u32>,
+    pub busy_pct: Option<u32>,    // 当前信道繁忙度 (相邻两次 survey 的 Δbusy/Δactive)
+    pub survey: Option<(u64, u64)>, // 在用频点的累计 (active, busy) 毫秒数，开机以来一直累加
+    pub rssi_best: Option<i32>,   // 最强终端信号 (dBm)
+    pub rssi_worst: Option<i32>,  // 最弱终端信号 (dBm)
+}
+
+// 解析 `iw dev X station dump`: 返回每个终端的信号强度 (缺失 signal 行的终端记 None)
+fn parse_station_dump(text: &str) -> Vec<Option<i32>> {
+    let mut stations = Vec::new();
+    for line in text.lines() {
+        let line = line.trim();
+        if line.starts_with("Station ") {
+            stations.push(None);
+        } else if let Some(rest) = line.strip_prefix("signal:") {
+            // "signal:  -45 [-47, -48] dBm" -> -45
+            let dbm = rest.split_whitespace().next().and_then(|v| v.parse::<i32>().ok());
+            if let Some(last) = stations.last_mut() {
+                *last = dbm;
+            }
+        }
+    }
+    stations
+}
+
+// 解析 `iw dev X info` 中的信道号: "channel 36 (5180 MHz), width: 80 MHz, ..."
+fn parse_channel(text: &str) -> Option<u32> {
+    text.lines()
+        .map(str::trim)
+        .find_map(|l| l.strip_prefix("channel "))
+        .and_then(|rest| rest.split_whitespace().next())
+        .and_then(|v| v.parse().ok())
+}
+
+// 解析 `iw dev X survey dump`: 取标记 [in use] 的频点的累计 (active, busy) 毫秒数
+fn parse_survey(text: &str) -> Option<(u64, u64)> {
+    let mut in_use = false;
+    let mut active: Option<u64> = None;
+    let mut busy: Option<u64> = None;
+    for line in text.lines() {
+        let line = line.trim();
+        if line.starts_with("Survey data") {
+            // 新频点开始: 如果上一个就是在用频点，结果已齐
+            if in_use {
+                break;
+            }
+            active = None;
+            busy = None;
+        } else if line.starts_with("frequency:") {
+            in_use = line.contains("[in use]");
+        } else if let Some(v) = line.strip_prefix("channel active time:") {
+            active = v.split_whitespace().next().and_then(|x| x.parse().ok());
+        } else if let Some(v) = line.strip_prefix("channel busy time:") {
+            busy = v.split_whitespace().next().and_then(|x| x.parse().ok());
+        }
+    }
+    match (in_use, active, busy) {
+        (true, Some(a), Some(b)) => Some((a, b)),
+        _ => None,
+    }
+}
+
+// 两次采样间的繁忙度 = Δbusy / Δactive；缺一次采样或计数器回退 (驱动重载) 时为 None
+fn busy_between(prev: Option<(u64, u64)>, cur: Option<(u64, u64)>) -> Option<u32> {
+    let ((a0, b0), (a1, b1)) = (prev?, cur?);
+    let (da, db) = (a1.checked_sub(a0)?, b1.checked_sub(b0)?);
+    (da > 0).then(|| ((db * 100) / da).min(100) as u32)
+}
+
+// 把多个接口 (同一射频上的多个 SSID) 的数据合并成射频汇总
+fn summarize(dumps: &[String], info: Option<&str>, survey: Option<&str>) -> RadioStatus {
+    let signals: Vec<Option<i32>> = dumps.iter().flat_map(|d| parse_station_dump(d)).collect();
+    let known: Vec<i32> = signals.iter().flatten().copied().collect();
+    RadioStatus {
+        stations: signals.len(),
+        channel: info.and_then(parse_channel),
+        busy_pct: None, // 需要上一次采样，由 radio_sampler 填
+        survey: survey.and_then(parse_survey),
+        rssi_best: known.iter().copied().max(),
+        rssi_worst: known.iter().copied().min(),
+    }
+}
+
+// 屏显格式
+fn stations_string(radio: &str, st: &RadioStatus) -> String {
+    format!("W{}:{}", radio, st.stations)
+}
+
+fn channel_string(st: &RadioStatus) -> String {
+    match (st.channel, st.busy_pct) {
+        (Some(ch), Some(busy)) => format!("CH{} {}%", ch, busy),
+        (Some(ch), None) => format!("CH{}", ch),
+        _ => "CH:--".to_string(),
+    }
+}
+
+fn rssi_string(st: &RadioStatus) -> String {
+    match (st.rssi_best, st.rssi_worst) {
+        (Some(best), Some(worst)) => format!("{}/{}", best, worst),
+        _ => "RSSI:--".to_string(),
+    }
+}
+
+// 某射频 (phyN) 下的所有无线接口名
+fn radio_interfaces(radio: &str) -> Vec<String> {
+    let phy = format!("phy{}", radio);
+    let mut ifaces = Vec::new();
+    if let Ok(entries) = fs::read_dir("/sys/class/net") {
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let owner = fs::read_to_string(entry.path().join("phy80211/name")).unwrap_or_default();
+            if owner.trim() == phy {
+                ifaces.push(name);
+            }
+        }
+    }
+    ifaces.sort();
+    ifaces
+}
+
+fn run_iw(args: &[&str]) -> Option<String> {
+    let out = Command::new("iw").args(args).output().ok()?;
+    if out.status.success() {
+        Some(String::from_utf8_lossy(&out.stdout).into_owned())
+    } else {
+        None
+    }
+}
+
+// 采集一个射频的完整状态 (阻塞)。None = 射频不存在或 iw 不可用
+fn poll_radio(radio: &str) -> Option<RadioStatus> {
+    if !std::path::Path::new(&format!("/sys/class/ieee80211/phy{}", radio)).exists() {
+        return None;
+    }
+    let ifaces = radio_interfaces(radio);
+    let dumps: Vec<String> = ifaces
+        .iter()
+        .filter_map(|i| run_iw(&["dev", i, "station", "dump"]))
+        .collect();
+    // 信道/繁忙度在同一射频的所有接口上相同，取第一个能查到的
+    let info = ifaces.iter().find_map(|i| run_iw(&["dev", i, "info"]));
+    let survey = ifaces.iter().find_map(|i| run_iw(&["dev", i, "survey", "dump"]));
+    if ifaces.is_empty() || (dumps.is_empty() && info.is_none()) {
+        None
+    } else {
+        Some(summarize(&dumps, info.as_deref(), survey.as_deref()))
+    }
+}
+
+// 某射频的采集函数: 上一轮的 (信道, survey 计数) 留在闭包里，换信道后计数属于另一个频点，不做差
+fn radio_sampler(radio: String) -> impl FnMut() -> Option<RadioStatus> + Send + 'static {
+    let mut prev: Option<(Option<u32>, (u64, u64))> = None;
+    move || {
+        let Some(mut st) = poll_radio(&radio) else {
+            prev = None;
+            return None;
+        };
+        let last = prev.filter(|(ch, _)| *ch == st.channel).map(|(_, c)| c);
+        st.busy_pct = busy_between(last, st.survey);
+        prev = st.survey.map(|c| (st.channel, c));
+        Some(st)
+    }
+}
+
+// ==========================================
+// 🧠 采集器 (由 SystemMonitor 持有): 首次用到某射频时起后台任务，之后只读快照
+// ==========================================
+pub struct WifiMonitor {
+    poller: Poller<RadioStatus>,
+}
+
+impl WifiMonitor {
+    pub fn new() -> Self {
+        Self { poller: Poller::new("WiFi", Duration::from_secs(IW_TIMEOUT_SECS), Duration::from_secs(POLL_SECS)) }
+    }
+
+    // 外层 None = 首轮采集还没完成；内层 None = 射频不存在或 iw 不可用
+    fn status(&mut self, radio: &str) -> Option<Option<RadioStatus>> {
+        self.poller.get(radio, || radio_sampler(radio.to_string()))
+    }
+
+    /// 已连接终端数: "W0:12"
+    pub fn stations(&mut self, radio: &str) -> String {
+        match self.status(radio) {
+            Some(Some(st)) => stations_string(radio, &st),
+            Some(None) => format!("W{}:Err", radio),
+            None => format!("W{}:--", radio),
+        }
+    }
+
+    /// 信道与繁忙度: "CH36 43%"
+    pub fn channel(&mut self, radio: &str) -> String {
+        match self.status(radio) {
+            Some(Some(st)) => channel_string(&st),
+            Some(None) => "CH:Err".to_string(),
+            None => "CH:--".to_string(),
+        }
+    }
+
+    /// 最强/最弱终端信号: "-38/-81"
+    pub fn rssi(&mut self, radio: &str) -> String {
+        match self.status(radio) {
+            Some(Some(st)) => rssi_string(&st),
+            Some(None) => "RSSI:Err".to_string(),
+            None => "RSSI:--".to_string(),
+        }
+    }
+}
+
+// ==========================================
+// 🧪 单元测试 (iw 输出样本取自 OpenWrt 23.05 / ath11k)
+// ==========================================
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STATION_DUMP: &str = "\
+Station aa:bb:cc:dd:ee:01 (on phy0-ap0)
+	inactive time:	1200 ms
+	rx bytes:	123456
+	signal:  	-45 [-47, -48] dBm
+	signal avg:	-46 [-48, -49] dBm
+	tx bitrate:	866.7 MBit/s VHT-MCS 9 80MHz short GI VHT-NSS 2
+Station aa:bb:cc:dd:ee:02 (on phy0-ap0)
+	inactive time:	40 ms
+	signal:  	-78 dBm
+Station aa:bb:cc:dd:ee:03 (on phy0-ap0)
+	inactive time:	40 ms
+";
+
+    const INFO: &str = "\
+Interface phy0-ap0
+	ifindex 12
+	type AP
+	wiphy 0
+	channel 36 (5180 MHz), width: 80 MHz, center1: 5210 MHz
+	txpower 23.00 dBm
+";
+
+    const SURVEY: &str = "\
+Survey data from phy0-ap0
+	frequency:			5160 MHz
+	noise:				-95 dBm
+Survey data from phy0-ap0
+	frequency:			5180 MHz [in use]
+	noise:				-92 dBm
+	channel active time:		1000 ms
+	channel busy time:		430 ms
+	channel receive time:		200 ms
+Survey data from phy0-ap0
+	frequency:			5200 MHz
+";
+
+    #[test]
+    fn station_dump_parsing() {
+        let s = parse_station_dump(STATION_DUMP);
+        // "signal avg" 不应覆盖瞬时 signal
+        assert_eq!(s, vec![Some(-45), Some(-78), None]);
+        assert!(parse_station_dump("").is_empty());
+    }
+
+    #[test]
+    fn channel_and_survey() {
+        assert_eq!(parse_channel(INFO), Some(36));
+        assert_eq!(parse_channel("Interface x\n\ttype AP\n"), None);
+        assert_eq!(parse_survey(SURVEY), Some((1000, 430)));
+        assert_eq!(parse_survey("Survey data from x\n\tfrequency: 2412 MHz\n"), None);
+    }
+
+    // 计数器是开机累计值: 两次 dump 之间 active +2000ms、busy +1500ms -> 75% (而不是开机平均的 64%)
+    #[test]
+    fn busy_from_two_dumps() {
+        let second = SURVEY.replace("1000 ms", "3000 ms").replace("430 ms", "1930 ms");
+        let (first, second) = (parse_survey(SURVEY), parse_survey(&second));
+        assert_eq!(busy_between(None, first), None);
+        assert_eq!(busy_between(first, second), Some(75));
+        // 计数器回退 (驱动重载 / 射频重启)
+        assert_eq!(busy_between(second, first), None);
+        assert_eq!(busy_between(first, first), None);
+    }
+
+    #[test]
+    fn summary_and_display() {
+        let second_ssid = "Station 11:22:33:44:55:66 (on phy0-ap1)\n\tsignal:  \t-30 dBm\n".to_string();
+        let st = summarize(&[STATION_DUMP.to_string(), second_ssid], Some(INFO), Some(SURVEY));
+        assert_eq!(st.stations, 4);
+        assert_eq!(stations_string("0", &st), "W0:4");
+        assert_eq!(st.survey, Some((1000, 430)));
+        assert_eq!(channel_string(&st), "CH36"); // 单次采样不出繁忙度
+        assert_eq!(channel_string(&RadioStatus { busy_pct: Some(43), ..st.clone() }), "CH36 43%");
+        assert_eq!(rssi_string(&st), "-30/-78");
+
+        let empty = summarize(&[], None, None);
+        assert_eq!(stations_string("1", &empty), "W1:0");
+        assert_eq!(channel_string(&empty), "CH:--");
+        assert_eq!(rssi_string(&empty), "RSSI:--");
+    }
+}